@@ -8,7 +8,9 @@ let
       files = [
         "Cargo.toml"
         "Cargo.lock"
+        "macros/"
         "src/"
+        "tests/"
       ];
     };
 
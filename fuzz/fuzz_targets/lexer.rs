@@ -0,0 +1,16 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use openqasm_parser::parse::lexer::Lexer;
+use openqasm_parser::parse::token::TokenTy;
+
+fuzz_target!(|data: &[u8]| {
+    let mut lexer = Lexer::new(String::from("<fuzz>"), data);
+    while let Some(token) = lexer.peek() {
+        if let TokenTy::Eof = token.get_ty() {
+            break;
+        }
+        lexer.discard();
+    }
+});
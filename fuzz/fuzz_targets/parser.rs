@@ -0,0 +1,12 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use openqasm_parser::parse::lexer::Lexer;
+use openqasm_parser::parse::parser::Parser;
+
+fuzz_target!(|data: &[u8]| {
+    let lexer = Lexer::new(String::from("<fuzz>"), data);
+    let mut parser = Parser::new(lexer);
+    parser.parse_input();
+});
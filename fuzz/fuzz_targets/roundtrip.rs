@@ -0,0 +1,30 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::parse::lexer::Lexer;
+use openqasm_parser::parse::parser::Parser;
+use openqasm_parser::utils::error::Error;
+
+use openqasm_parser_fuzz::generator::Program;
+
+fn pretty_print(input: &str) -> String {
+    let lexer = Lexer::new(String::from("<fuzz>"), input.as_bytes());
+    let mut parser = Parser::new(lexer);
+    let program = parser.parse_input();
+    match program {
+        Some(program) => PrettyPrinter::new().pretty_print(&program),
+        None => panic!(
+            "generated program was rejected: {}\n{}",
+            parser.get_error().get_desc(),
+            input
+        ),
+    }
+}
+
+fuzz_target!(|program: Program| {
+    let source = program.to_qasm();
+    let printed = pretty_print(&source);
+    assert_eq!(printed, pretty_print(&printed));
+});
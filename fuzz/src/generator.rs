@@ -0,0 +1,154 @@
+use arbitrary::{Arbitrary, Result, Unstructured};
+
+const MAX_REGS: usize = 4;
+const MAX_REG_SIZE: u32 = 8;
+const MAX_STMTS: usize = 64;
+const MAX_EXP_DEPTH: u32 = 6;
+
+#[derive(Debug)]
+pub enum Exp {
+    Int(u32),
+    Real(u32, u32),
+    Pi,
+    Binop(Box<Exp>, &'static str, Box<Exp>),
+    Minus(Box<Exp>),
+    Unary(&'static str, Box<Exp>),
+    Paren(Box<Exp>),
+}
+
+#[derive(Debug)]
+pub enum Stmt {
+    U(Exp, Exp, Exp, String),
+    CX(String, String),
+    Measure(String, String),
+    Reset(String),
+}
+
+#[derive(Debug)]
+pub struct Program {
+    qregs: Vec<u32>,
+    cregs: Vec<u32>,
+    stmts: Vec<Stmt>,
+}
+
+fn arbitrary_exp(u: &mut Unstructured, depth: u32) -> Result<Exp> {
+    let kind = if depth >= MAX_EXP_DEPTH {
+        u.int_in_range(0..=2)?
+    } else {
+        u.int_in_range(0..=6)?
+    };
+    Ok(match kind {
+        0 => Exp::Int(u.int_in_range(0..=1000)?),
+        1 => Exp::Real(u.int_in_range(0..=1000)?, u.int_in_range(0..=99)?),
+        2 => Exp::Pi,
+        3 => Exp::Binop(
+            Box::new(arbitrary_exp(u, depth + 1)?),
+            *u.choose(&["+", "-", "*", "/", "^"])?,
+            Box::new(arbitrary_exp(u, depth + 1)?),
+        ),
+        4 => Exp::Minus(Box::new(arbitrary_exp(u, depth + 1)?)),
+        5 => Exp::Unary(
+            *u.choose(&["sin", "cos", "tan", "exp", "ln", "sqrt"])?,
+            Box::new(arbitrary_exp(u, depth + 1)?),
+        ),
+        _ => Exp::Paren(Box::new(arbitrary_exp(u, depth + 1)?)),
+    })
+}
+
+fn arbitrary_bit(u: &mut Unstructured, prefix: &str, regs: &[u32]) -> Result<String> {
+    let reg = u.choose_index(regs.len())?;
+    let index = u.int_in_range(0..=regs[reg] - 1)?;
+    Ok(format!("{}{}[{}]", prefix, reg, index))
+}
+
+fn arbitrary_arg(u: &mut Unstructured, prefix: &str, regs: &[u32]) -> Result<String> {
+    if u.arbitrary()? {
+        Ok(format!("{}{}", prefix, u.choose_index(regs.len())?))
+    } else {
+        arbitrary_bit(u, prefix, regs)
+    }
+}
+
+impl<'a> Arbitrary<'a> for Program {
+    fn arbitrary(u: &mut Unstructured<'a>) -> Result<Self> {
+        let mut qregs = Vec::new();
+        for _ in 0..u.int_in_range(1..=MAX_REGS)? {
+            qregs.push(u.int_in_range(1..=MAX_REG_SIZE)?);
+        }
+        let mut cregs = Vec::new();
+        for _ in 0..u.int_in_range(1..=MAX_REGS)? {
+            cregs.push(u.int_in_range(1..=MAX_REG_SIZE)?);
+        }
+
+        let mut stmts = Vec::new();
+        for _ in 0..u.int_in_range(0..=MAX_STMTS)? {
+            stmts.push(match u.int_in_range(0..=3)? {
+                0 => Stmt::U(
+                    arbitrary_exp(u, 0)?,
+                    arbitrary_exp(u, 0)?,
+                    arbitrary_exp(u, 0)?,
+                    arbitrary_arg(u, "q", &qregs)?,
+                ),
+                1 => Stmt::CX(arbitrary_bit(u, "q", &qregs)?, arbitrary_bit(u, "q", &qregs)?),
+                2 => Stmt::Measure(arbitrary_bit(u, "q", &qregs)?, arbitrary_bit(u, "c", &cregs)?),
+                _ => Stmt::Reset(arbitrary_arg(u, "q", &qregs)?),
+            });
+        }
+
+        Ok(Program {
+            qregs: qregs,
+            cregs: cregs,
+            stmts: stmts,
+        })
+    }
+}
+
+impl Exp {
+    fn to_qasm(&self) -> String {
+        match self {
+            Exp::Int(value) => value.to_string(),
+            Exp::Real(int, frac) => format!("{}.{:02}", int, frac),
+            Exp::Pi => String::from("pi"),
+            Exp::Binop(left, op, right) => {
+                format!("{} {} {}", left.to_qasm(), op, right.to_qasm())
+            }
+            Exp::Minus(exp) => format!("-({})", exp.to_qasm()),
+            Exp::Unary(op, exp) => format!("{}({})", op, exp.to_qasm()),
+            Exp::Paren(exp) => format!("({})", exp.to_qasm()),
+        }
+    }
+}
+
+impl Stmt {
+    fn to_qasm(&self) -> String {
+        match self {
+            Stmt::U(theta, phi, lambda, arg) => format!(
+                "U ({}, {}, {}) {};",
+                theta.to_qasm(),
+                phi.to_qasm(),
+                lambda.to_qasm(),
+                arg
+            ),
+            Stmt::CX(control, target) => format!("CX {}, {};", control, target),
+            Stmt::Measure(src, dst) => format!("measure {} -> {};", src, dst),
+            Stmt::Reset(arg) => format!("reset {};", arg),
+        }
+    }
+}
+
+impl Program {
+    pub fn to_qasm(&self) -> String {
+        let mut output = String::from("OPENQASM 2.0;\n");
+        for (i, size) in self.qregs.iter().enumerate() {
+            output += &format!("qreg q{}[{}];\n", i, size);
+        }
+        for (i, size) in self.cregs.iter().enumerate() {
+            output += &format!("creg c{}[{}];\n", i, size);
+        }
+        for stmt in &self.stmts {
+            output += &stmt.to_qasm();
+            output += "\n";
+        }
+        output
+    }
+}
@@ -0,0 +1,63 @@
+use proc_macro::TokenStream;
+
+use openqasm_parser::ast::{builder, fragment};
+
+use openqasm_parser::utils::error::Error;
+
+/// Builds a `node::Exp` from an OpenQASM expression, e.g. `exp!(pi / 2)`.
+///
+/// The expression is parsed when the macro is expanded, so an invalid
+/// expression is a compile error:
+///
+/// ```compile_fail
+/// let exp = openqasm_parser_macros::exp!(pi / );
+/// ```
+#[proc_macro]
+pub fn exp(input: TokenStream) -> TokenStream {
+    let text = input.to_string();
+    match fragment::parse_exp("<exp!>", &text) {
+        Ok(_) => expand(&format!("fragment::parse_exp(\"<exp!>\", {:?})", text)),
+        Err(error) => compile_error(&error.get_desc()),
+    }
+}
+
+/// Parses, binds and type checks an OpenQASM program written inline.
+///
+/// The program is checked when the macro is expanded, so an invalid program
+/// is a compile error:
+///
+/// ```compile_fail
+/// let program = openqasm_parser_macros::qasm! {
+///     OPENQASM 2.0;
+///     qreg q[1];
+///     CX q[0], q[0];
+/// };
+/// ```
+#[proc_macro]
+pub fn qasm(input: TokenStream) -> TokenStream {
+    let text = input.to_string();
+    match builder::parse_program(&text) {
+        Ok(_) => expand(&format!("builder::parse_program({:?})", text)),
+        Err(error) => compile_error(&error.get_desc()),
+    }
+}
+
+/// Makes the same call again at run time. The text was accepted when the
+/// macro was expanded, so the call cannot fail.
+fn expand(call: &str) -> TokenStream {
+    format!(
+        "match ::openqasm_parser::ast::{} {{ \
+             Ok(value) => value, \
+             Err(_) => unreachable!(\"checked when the macro was expanded\"), \
+         }}",
+        call
+    )
+    .parse()
+    .unwrap()
+}
+
+fn compile_error(desc: &str) -> TokenStream {
+    format!("::core::compile_error!({:?})", desc)
+        .parse()
+        .unwrap()
+}
@@ -0,0 +1,319 @@
+use std::collections::{BTreeMap, HashMap};
+use std::rc::Rc;
+use std::vec::Vec;
+
+use crate::ast::node;
+
+use crate::bind::reg::RegDec;
+
+use crate::typing::ty::Ty;
+
+use crate::utils::location::Location;
+
+// Critical path entries point at the source a statement came from, so inlined
+// statements report their call. Programs rebuilt from a circuit have no source
+// and report synthetic locations.
+pub struct Stats {
+    pub qubits: usize,
+    pub clbits: usize,
+    pub gates: BTreeMap<String, usize>,
+    pub depth: usize,
+    pub two_qubit_depth: usize,
+    pub critical_path: Vec<Location>,
+    pub measurements: usize,
+    pub resets: usize,
+}
+
+// The state of a wire: the depth reached so far, the depth counting only
+// two-qubit gates, and the last operation on the critical path through it.
+struct WireState {
+    depth: usize,
+    two_qubit_depth: usize,
+    last: Option<usize>,
+}
+
+impl Clone for WireState {
+    fn clone(&self) -> WireState {
+        WireState {
+            depth: self.depth,
+            two_qubit_depth: self.two_qubit_depth,
+            last: self.last,
+        }
+    }
+}
+
+struct Instance {
+    loc: Location,
+    prev: Option<usize>,
+}
+
+pub struct StatsCollector {
+    offsets: HashMap<*const RegDec, usize>,
+    wires: Vec<WireState>,
+    instances: Vec<Instance>,
+    stats: Stats,
+}
+
+fn get_size(dec: &RegDec) -> usize {
+    match dec.get_ty() {
+        Ty::QRegTy(size) | Ty::CRegTy(size) => *size as usize,
+        _ => 1,
+    }
+}
+
+impl Default for StatsCollector {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl StatsCollector {
+    pub fn new() -> StatsCollector {
+        StatsCollector {
+            offsets: HashMap::new(),
+            wires: Vec::new(),
+            instances: Vec::new(),
+            stats: Stats {
+                qubits: 0,
+                clbits: 0,
+                gates: BTreeMap::new(),
+                depth: 0,
+                two_qubit_depth: 0,
+                critical_path: Vec::new(),
+                measurements: 0,
+                resets: 0,
+            },
+        }
+    }
+
+    pub fn collect(mut self, program: &node::Program) -> Stats {
+        for stmt in program.stmts.iter() {
+            if let node::Stmt::DecStmt(node::Dec::RegDec {
+                ty, dec: Some(dec), ..
+            }) = stmt
+            {
+                match ty {
+                    node::RegTy::QReg => {
+                        self.offsets.insert(Rc::as_ptr(dec), self.stats.qubits);
+                        self.stats.qubits += get_size(dec);
+                    }
+                    node::RegTy::CReg => {
+                        self.offsets.insert(Rc::as_ptr(dec), self.stats.clbits);
+                        self.stats.clbits += get_size(dec);
+                    }
+                }
+            }
+        }
+        let state = WireState {
+            depth: 0,
+            two_qubit_depth: 0,
+            last: None,
+        };
+        self.wires = vec![state; self.stats.qubits + self.stats.clbits];
+
+        for stmt in program.stmts.iter() {
+            self.collect_stmt(stmt, &[]);
+        }
+
+        let mut last = self
+            .wires
+            .iter()
+            .filter(|wire| wire.depth == self.stats.depth)
+            .find_map(|wire| wire.last);
+        while let Some(id) = last {
+            self.stats
+                .critical_path
+                .push(self.instances[id].loc.clone());
+            last = self.instances[id].prev;
+        }
+        self.stats.critical_path.reverse();
+        self.stats
+    }
+
+    fn collect_stmt(&mut self, stmt: &node::Stmt, condition: &[usize]) {
+        match stmt {
+            node::Stmt::GateStmt {
+                loc, gate, args, ..
+            } => {
+                for qubits in self.broadcast(args.iter().map(|arg| self.qubits(arg)).collect()) {
+                    *self.stats.gates.entry(gate.clone()).or_insert(0) += 1;
+                    let two_qubit = qubits.len() >= 2;
+                    self.apply(loc, qubits, condition, two_qubit);
+                }
+            }
+            node::Stmt::MeasureStmt { loc, src, dst } => {
+                for bits in self.broadcast(vec![self.qubits(src), self.clbits(dst)]) {
+                    self.stats.measurements += 1;
+                    self.apply(loc, bits, condition, false);
+                }
+            }
+            node::Stmt::ResetStmt { loc, reg } => {
+                for qubit in self.qubits(reg) {
+                    self.stats.resets += 1;
+                    self.apply(loc, vec![qubit], condition, false);
+                }
+            }
+            node::Stmt::BarrierStmt { loc: _, args } => {
+                let wires = args
+                    .iter()
+                    .flat_map(|arg| self.qubits(arg))
+                    .collect::<Vec<usize>>();
+                let state = self.latest(&wires);
+                for wire in wires {
+                    self.wires[wire] = state.clone();
+                }
+            }
+            node::Stmt::IfStmt { reg, stmt, .. } => {
+                let clbits = self.clbits(reg);
+                self.collect_stmt(stmt, &clbits);
+            }
+            node::Stmt::DecStmt(_) => {}
+        }
+    }
+
+    fn apply(
+        &mut self,
+        loc: &Location,
+        mut wires: Vec<usize>,
+        condition: &[usize],
+        two_qubit: bool,
+    ) {
+        wires.extend_from_slice(condition);
+        let state = self.latest(&wires);
+        let id = self.instances.len();
+        self.instances.push(Instance {
+            loc: loc.get_source().unwrap_or(loc).clone(),
+            prev: state.last,
+        });
+        let state = WireState {
+            depth: state.depth + 1,
+            two_qubit_depth: state.two_qubit_depth + if two_qubit { 1 } else { 0 },
+            last: Some(id),
+        };
+        self.stats.depth = self.stats.depth.max(state.depth);
+        self.stats.two_qubit_depth = self.stats.two_qubit_depth.max(state.two_qubit_depth);
+        for wire in wires {
+            self.wires[wire] = state.clone();
+        }
+    }
+
+    fn latest(&self, wires: &[usize]) -> WireState {
+        let mut state = WireState {
+            depth: 0,
+            two_qubit_depth: 0,
+            last: None,
+        };
+        for wire in wires.iter().map(|wire| &self.wires[*wire]) {
+            if wire.depth > state.depth {
+                state.depth = wire.depth;
+                state.last = wire.last;
+            }
+            state.two_qubit_depth = state.two_qubit_depth.max(wire.two_qubit_depth);
+        }
+        state
+    }
+
+    fn bits(&self, reg: &node::Reg, base: usize) -> Vec<usize> {
+        let dec = match reg.get_dec() {
+            Some(dec) => dec,
+            None => return Vec::new(),
+        };
+        let offset = match self.offsets.get(&Rc::as_ptr(dec)) {
+            Some(offset) => base + offset,
+            None => return Vec::new(),
+        };
+        match reg {
+            node::Reg::SimpleReg { .. } => (offset..offset + get_size(dec)).collect(),
+            node::Reg::SubscriptReg { index, .. } => vec![offset + *index as usize],
+        }
+    }
+
+    fn qubits(&self, reg: &node::Reg) -> Vec<usize> {
+        self.bits(reg, 0)
+    }
+
+    fn clbits(&self, reg: &node::Reg) -> Vec<usize> {
+        self.bits(reg, self.stats.qubits)
+    }
+
+    fn broadcast(&self, operands: Vec<Vec<usize>>) -> Vec<Vec<usize>> {
+        if operands.iter().any(|bits| bits.is_empty()) {
+            return Vec::new();
+        }
+        let width = operands.iter().map(|bits| bits.len()).max().unwrap_or(1);
+        (0..width)
+            .map(|i| {
+                operands
+                    .iter()
+                    .map(|bits| bits[i.min(bits.len() - 1)])
+                    .collect()
+            })
+            .collect()
+    }
+}
+
+fn json_string(text: &str) -> String {
+    let mut out = String::from("\"");
+    for c in text.chars() {
+        match c {
+            '"' => out.push_str("\\\""),
+            '\\' => out.push_str("\\\\"),
+            '\n' => out.push_str("\\n"),
+            '\t' => out.push_str("\\t"),
+            c if (c as u32) < 0x20 => out.push_str(&format!("\\u{:04x}", c as u32)),
+            c => out.push(c),
+        }
+    }
+    out.push('"');
+    out
+}
+
+impl Stats {
+    pub fn get_gate_count(&self) -> usize {
+        self.gates.values().sum()
+    }
+
+    pub fn to_text(&self) -> String {
+        let mut out = String::new();
+        out += &format!("qubits: {}\n", self.qubits);
+        out += &format!("clbits: {}\n", self.clbits);
+        out += &format!("gates: {}\n", self.get_gate_count());
+        for (gate, count) in self.gates.iter() {
+            out += &format!("  {}: {}\n", gate, count);
+        }
+        out += &format!("depth: {}\n", self.depth);
+        out += &format!("two-qubit depth: {}\n", self.two_qubit_depth);
+        out += &format!("measurements: {}\n", self.measurements);
+        out += &format!("resets: {}\n", self.resets);
+        out += "critical path:\n";
+        for loc in self.critical_path.iter() {
+            out += &format!("  {}\n", loc);
+        }
+        out
+    }
+
+    pub fn to_json(&self) -> String {
+        format!(
+            "{{\"qubits\": {}, \"clbits\": {}, \"gates\": {}, \"gate_counts\": {{{}}}, \
+             \"depth\": {}, \"two_qubit_depth\": {}, \"measurements\": {}, \"resets\": {}, \
+             \"critical_path\": [{}]}}\n",
+            self.qubits,
+            self.clbits,
+            self.get_gate_count(),
+            self.gates
+                .iter()
+                .map(|(gate, count)| format!("{}: {}", json_string(gate), count))
+                .collect::<Vec<String>>()
+                .join(", "),
+            self.depth,
+            self.two_qubit_depth,
+            self.measurements,
+            self.resets,
+            self.critical_path
+                .iter()
+                .map(|loc| json_string(&loc.to_string()))
+                .collect::<Vec<String>>()
+                .join(", ")
+        )
+    }
+}
@@ -0,0 +1,247 @@
+use std::vec::Vec;
+
+use crate::ast::{factory, node};
+
+use crate::bind::binder::Binder;
+
+use crate::parse::parser;
+
+use crate::typing::type_checker::TypeChecker;
+
+use crate::utils::error::CompoundError;
+use crate::utils::location::Location;
+
+#[allow(non_upper_case_globals)]
+pub const pi: Pi = Pi;
+
+pub struct Pi;
+
+pub trait IntoExp {
+    fn into_exp(self) -> node::Exp;
+}
+
+impl IntoExp for node::Exp {
+    fn into_exp(self) -> node::Exp {
+        self
+    }
+}
+
+impl IntoExp for Pi {
+    fn into_exp(self) -> node::Exp {
+        factory::make_pi_exp(location())
+    }
+}
+
+impl IntoExp for u64 {
+    fn into_exp(self) -> node::Exp {
+        factory::make_int_exp(location(), self, self.to_string())
+    }
+}
+
+impl IntoExp for u32 {
+    fn into_exp(self) -> node::Exp {
+        (self as u64).into_exp()
+    }
+}
+
+impl IntoExp for i32 {
+    fn into_exp(self) -> node::Exp {
+        let exp = (self.unsigned_abs() as u64).into_exp();
+        if self < 0 {
+            factory::make_unary_exp(location(), node::UnaryOp::Minus, exp)
+        } else {
+            exp
+        }
+    }
+}
+
+impl IntoExp for f64 {
+    fn into_exp(self) -> node::Exp {
+        let exp = factory::make_real_exp(location(), self.abs(), real_text(self.abs()));
+        if self.is_sign_negative() {
+            factory::make_unary_exp(location(), node::UnaryOp::Minus, exp)
+        } else {
+            exp
+        }
+    }
+}
+
+impl IntoExp for f32 {
+    fn into_exp(self) -> node::Exp {
+        f64::from(self).into_exp()
+    }
+}
+
+pub(crate) fn real_text(value: f64) -> String {
+    let text = format!("{:?}", value);
+    if text.contains('.') {
+        text
+    } else if let Some(exponent) = text.find('e') {
+        format!("{}.0{}", &text[..exponent], &text[exponent..])
+    } else {
+        format!("{}.0", text)
+    }
+}
+
+pub trait IntoReg {
+    fn into_reg(self) -> node::Reg;
+}
+
+impl IntoReg for node::Reg {
+    fn into_reg(self) -> node::Reg {
+        self
+    }
+}
+
+impl IntoReg for &str {
+    fn into_reg(self) -> node::Reg {
+        reg(self)
+    }
+}
+
+impl IntoReg for (&str, u64) {
+    fn into_reg(self) -> node::Reg {
+        bit(self.0, self.1)
+    }
+}
+
+pub fn reg(name: &str) -> node::Reg {
+    factory::make_simple_reg(location(), String::from(name))
+}
+
+pub fn bit(name: &str, index: u64) -> node::Reg {
+    factory::make_subscript_reg(location(), String::from(name), index)
+}
+
+/// Parses, binds and type checks an OpenQASM program, as expanded by `qasm!`
+/// from the `openqasm-parser-macros` crate.
+pub fn parse_program(input: &str) -> Result<node::Program, CompoundError> {
+    parser::parse_str("<qasm!>", input).and_then(|program| check(program, CompoundError::new()))
+}
+
+fn check(
+    mut program: node::Program,
+    mut error: CompoundError,
+) -> Result<node::Program, CompoundError> {
+    let mut binder = Binder::new();
+    binder.bind(&mut program);
+    error.consume(binder.get_error_mut());
+
+    if error.empty() {
+        let mut type_checker = TypeChecker::new();
+        type_checker.check_types(&program);
+        error.consume(type_checker.get_error_mut());
+    }
+
+    if error.empty() {
+        Ok(program)
+    } else {
+        Err(error)
+    }
+}
+
+fn location() -> Location {
+    Location::new_synthetic()
+}
+
+pub struct Circuit {
+    includes: Vec<node::Include>,
+    stmts: Vec<node::Stmt>,
+}
+
+impl Default for Circuit {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Circuit {
+    pub fn new() -> Circuit {
+        Circuit {
+            includes: Vec::new(),
+            stmts: Vec::new(),
+        }
+    }
+
+    pub fn include(mut self, path: &str) -> Circuit {
+        self.includes.push(factory::make_include(
+            location(),
+            String::from(path),
+            self.stmts.len(),
+        ));
+        self
+    }
+
+    pub fn qreg(self, name: &str, size: u64) -> Circuit {
+        self.push(factory::make_reg_dec(
+            location(),
+            String::from(name),
+            node::RegTy::QReg,
+            size,
+        ))
+    }
+
+    pub fn creg(self, name: &str, size: u64) -> Circuit {
+        self.push(factory::make_reg_dec(
+            location(),
+            String::from(name),
+            node::RegTy::CReg,
+            size,
+        ))
+    }
+
+    pub fn u(
+        self,
+        theta: impl IntoExp,
+        phi: impl IntoExp,
+        lambda: impl IntoExp,
+        qubit: impl IntoReg,
+    ) -> Circuit {
+        self.gate(
+            "U",
+            vec![theta.into_exp(), phi.into_exp(), lambda.into_exp()],
+            vec![qubit.into_reg()],
+        )
+    }
+
+    pub fn cx(self, control: impl IntoReg, target: impl IntoReg) -> Circuit {
+        self.gate("CX", vec![], vec![control.into_reg(), target.into_reg()])
+    }
+
+    pub fn gate(self, name: &str, pars: Vec<node::Exp>, args: Vec<node::Reg>) -> Circuit {
+        self.push(factory::make_gate_stmt(
+            location(),
+            String::from(name),
+            pars,
+            args,
+        ))
+    }
+
+    pub fn measure(self, src: impl IntoReg, dst: impl IntoReg) -> Circuit {
+        self.push(factory::make_measure_stmt(
+            location(),
+            src.into_reg(),
+            dst.into_reg(),
+        ))
+    }
+
+    pub fn reset(self, reg: impl IntoReg) -> Circuit {
+        self.push(factory::make_reset_stmt(location(), reg.into_reg()))
+    }
+
+    pub fn push(mut self, stmt: node::Stmt) -> Circuit {
+        self.stmts.push(stmt);
+        self
+    }
+
+    pub fn build(self) -> Result<node::Program, CompoundError> {
+        let program = factory::make_program(
+            location(),
+            node::Version { major: 2, minor: 0 },
+            self.includes,
+            Vec::new(),
+            self.stmts,
+        );
+        check(program, CompoundError::new())
+    }
+}
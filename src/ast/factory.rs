@@ -2,29 +2,70 @@ use crate::ast::node;
 
 use crate::utils::location::Location;
 
-pub fn make_reg_dec(loc: Location, name: String, ty: node::RegTy, size: u32) -> node::Stmt {
+pub fn make_program(
+    loc: Location,
+    version: node::Version,
+    includes: Vec<node::Include>,
+    pragmas: Vec<node::Pragma>,
+    stmts: Vec<node::Stmt>,
+) -> node::Program {
+    node::Program {
+        loc,
+        version,
+        includes,
+        pragmas,
+        stmts,
+    }
+}
+
+pub fn make_include(loc: Location, path: String, index: usize) -> node::Include {
+    node::Include { loc, path, index }
+}
+
+pub fn make_pragma(loc: Location, text: String, index: usize) -> node::Pragma {
+    node::Pragma { loc, text, index }
+}
+
+pub fn make_reg_dec(loc: Location, name: String, ty: node::RegTy, size: u64) -> node::Stmt {
     node::Stmt::DecStmt(node::Dec::RegDec {
-        loc: loc,
-        name: name,
-        ty: ty,
-        size: size,
+        loc,
+        name,
+        ty,
+        size,
+        dec: None,
+    })
+}
+
+pub fn make_gate_dec(
+    loc: Location,
+    name: String,
+    pars: Vec<String>,
+    args: Vec<String>,
+    body: Option<Vec<node::Stmt>>,
+) -> node::Stmt {
+    node::Stmt::DecStmt(node::Dec::GateDec {
+        loc,
+        name,
+        pars,
+        args,
+        body,
         dec: None,
     })
 }
 
 pub fn make_simple_reg(loc: Location, name: String) -> node::Reg {
     node::Reg::SimpleReg {
-        loc: loc,
-        name: name,
+        loc,
+        name,
         dec: None,
     }
 }
 
-pub fn make_subscript_reg(loc: Location, name: String, index: u32) -> node::Reg {
+pub fn make_subscript_reg(loc: Location, name: String, index: u64) -> node::Reg {
     node::Reg::SubscriptReg {
-        loc: loc,
-        name: name,
-        index: index,
+        loc,
+        name,
+        index,
         dec: None,
     }
 }
@@ -36,42 +77,53 @@ pub fn make_gate_stmt(
     args: Vec<node::Reg>,
 ) -> node::Stmt {
     node::Stmt::GateStmt {
-        loc: loc,
-        gate: gate,
-        pars: pars,
-        args: args,
+        loc,
+        gate,
+        pars,
+        args,
         dec: None,
     }
 }
 
 pub fn make_measure_stmt(loc: Location, src: node::Reg, dst: node::Reg) -> node::Stmt {
-    node::Stmt::MeasureStmt {
-        loc: loc,
-        src: src,
-        dst: dst,
-    }
+    node::Stmt::MeasureStmt { loc, src, dst }
 }
 
 pub fn make_reset_stmt(loc: Location, reg: node::Reg) -> node::Stmt {
-    node::Stmt::ResetStmt { loc: loc, reg: reg }
+    node::Stmt::ResetStmt { loc, reg }
 }
 
-pub fn make_int_exp(loc: Location, value: u32) -> node::Exp {
-    node::Exp::IntExp {
-        loc: loc,
-        value: value,
-    }
+pub fn make_barrier_stmt(loc: Location, args: Vec<node::Reg>) -> node::Stmt {
+    node::Stmt::BarrierStmt { loc, args }
 }
 
-pub fn make_real_exp(loc: Location, value: f32) -> node::Exp {
-    node::Exp::RealExp {
-        loc: loc,
-        value: value,
+pub fn make_if_stmt(loc: Location, reg: node::Reg, value: u64, stmt: node::Stmt) -> node::Stmt {
+    node::Stmt::IfStmt {
+        loc,
+        reg,
+        value,
+        stmt: Box::new(stmt),
     }
 }
 
+pub fn make_int_exp(loc: Location, value: u64, text: String) -> node::Exp {
+    node::Exp::IntExp { loc, value, text }
+}
+
+pub fn make_real_exp(loc: Location, value: f64, text: String) -> node::Exp {
+    node::Exp::RealExp { loc, value, text }
+}
+
 pub fn make_pi_exp(loc: Location) -> node::Exp {
-    node::Exp::PiExp { loc: loc }
+    node::Exp::PiExp { loc }
+}
+
+pub fn make_id_exp(loc: Location, name: String) -> node::Exp {
+    node::Exp::IdExp {
+        loc,
+        name,
+        dec: None,
+    }
 }
 
 pub fn make_binop_exp(
@@ -81,17 +133,46 @@ pub fn make_binop_exp(
     right: node::Exp,
 ) -> node::Exp {
     node::Exp::BinopExp {
-        loc: loc,
+        loc,
         left: Box::new(left),
-        op: op,
+        op,
         right: Box::new(right),
     }
 }
 
 pub fn make_unary_exp(loc: Location, op: node::UnaryOp, exp: node::Exp) -> node::Exp {
     node::Exp::UnaryExp {
-        loc: loc,
-        op: op,
+        loc,
+        op,
         exp: Box::new(exp),
     }
 }
+
+/// The expression inside a pair of parentheses, taking their location.
+pub fn make_delimited_exp(loc: Location, exp: node::Exp) -> node::Exp {
+    match exp {
+        node::Exp::IntExp {
+            loc: _,
+            value,
+            text,
+        } => make_int_exp(loc, value, text),
+        node::Exp::RealExp {
+            loc: _,
+            value,
+            text,
+        } => make_real_exp(loc, value, text),
+        node::Exp::PiExp { loc: _ } => make_pi_exp(loc),
+        node::Exp::IdExp {
+            loc: _,
+            name,
+            dec: _,
+        } => make_id_exp(loc, name),
+        node::Exp::BinopExp {
+            loc: _,
+            left,
+            op,
+            right,
+        } => make_binop_exp(loc, *left, op, *right),
+        node::Exp::UnaryExp { loc: _, op, exp } => make_unary_exp(loc, op, *exp),
+    }
+}
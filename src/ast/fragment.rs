@@ -0,0 +1,149 @@
+use std::rc::Rc;
+use std::vec::Vec;
+
+use crate::ast::node;
+use crate::ast::node::RegTy;
+
+use crate::bind::binder::Binder;
+use crate::bind::gate::GateDec;
+use crate::bind::par::ParDec;
+use crate::bind::reg::RegDec;
+
+use crate::parse::lexer::Lexer;
+use crate::parse::parser::Parser;
+
+use crate::typing::type_checker::TypeChecker;
+
+use crate::utils::error::CompoundError;
+
+pub fn parse_exp(name: &str, input: &str) -> Result<node::Exp, CompoundError> {
+    let mut parser = Parser::new(Lexer::from_str(String::from(name), input));
+    let exp = parser.parse_exp_input();
+
+    let mut error = CompoundError::new();
+    error.consume(parser.get_error_mut());
+    let mut exp = match exp {
+        Some(exp) if error.empty() => exp,
+        _ => return Err(error),
+    };
+
+    let mut binder = Binder::new();
+    binder.bind_exp(&mut exp);
+    error.consume(binder.get_error_mut());
+    if !error.empty() {
+        return Err(error);
+    }
+
+    let mut type_checker = TypeChecker::new();
+    type_checker.check_exp(&exp);
+    error.consume(type_checker.get_error_mut());
+    if !error.empty() {
+        return Err(error);
+    }
+
+    Ok(exp)
+}
+
+pub fn parse_stmt(
+    name: &str,
+    input: &str,
+    context: Option<&node::Program>,
+) -> Result<node::Stmt, CompoundError> {
+    let mut parser = Parser::new(Lexer::from_str(String::from(name), input));
+    let stmt = parser.parse_stmt_input();
+
+    let mut error = CompoundError::new();
+    error.consume(parser.get_error_mut());
+    let stmt = match stmt {
+        Some(stmt) if error.empty() => stmt,
+        _ => return Err(error),
+    };
+
+    let mut binder = Binder::new();
+    if let Some(program) = context {
+        declare(&mut binder, program);
+    }
+    check(binder, vec![stmt]).map(|mut stmts| stmts.remove(0))
+}
+
+pub fn parse_gate_body(
+    name: &str,
+    input: &str,
+    pars: &[&str],
+    args: &[&str],
+) -> Result<Vec<node::Stmt>, CompoundError> {
+    let mut parser = Parser::new(Lexer::from_str(String::from(name), input));
+    let body = parser.parse_gate_body_input();
+
+    let mut error = CompoundError::new();
+    error.consume(parser.get_error_mut());
+    let body = match body {
+        Some(body) if error.empty() => body,
+        _ => return Err(error),
+    };
+
+    let mut binder = Binder::new();
+    for par in pars {
+        binder.declare_par(Rc::new(ParDec::new(String::from(*par))));
+    }
+    for arg in args {
+        binder.declare_reg(Rc::new(RegDec::new_bit(String::from(*arg), RegTy::QReg)));
+    }
+    check(binder, body)
+}
+
+// Declares the registers and gates of a program without binding it, reusing
+// the declarations of a bound program so that fragments refer to them.
+fn declare(binder: &mut Binder, program: &node::Program) {
+    for stmt in program.stmts.iter() {
+        match stmt {
+            node::Stmt::DecStmt(node::Dec::RegDec {
+                name,
+                ty,
+                size,
+                dec,
+                ..
+            }) => binder.declare_reg(match dec {
+                Some(dec) => Rc::clone(dec),
+                None => Rc::new(RegDec::new(name.clone(), ty.clone(), *size)),
+            }),
+            node::Stmt::DecStmt(node::Dec::GateDec {
+                name,
+                pars,
+                args,
+                dec,
+                ..
+            }) => binder.declare_gate(match dec {
+                Some(dec) => Rc::clone(dec),
+                None => Rc::new(GateDec::new(
+                    name.clone(),
+                    pars.iter()
+                        .map(|par| Rc::new(ParDec::new(par.clone())))
+                        .collect(),
+                    args.iter()
+                        .map(|arg| Rc::new(RegDec::new_bit(arg.clone(), RegTy::QReg)))
+                        .collect(),
+                )),
+            }),
+            _ => {}
+        }
+    }
+}
+
+fn check(mut binder: Binder, mut stmts: Vec<node::Stmt>) -> Result<Vec<node::Stmt>, CompoundError> {
+    let mut error = CompoundError::new();
+    stmts.iter_mut().for_each(|stmt| binder.bind_stmt(stmt));
+    error.consume(binder.get_error_mut());
+    if !error.empty() {
+        return Err(error);
+    }
+
+    let mut type_checker = TypeChecker::new();
+    stmts.iter().for_each(|stmt| type_checker.check_stmt(stmt));
+    error.consume(type_checker.get_error_mut());
+    if !error.empty() {
+        return Err(error);
+    }
+
+    Ok(stmts)
+}
@@ -1,4 +1,6 @@
+pub mod builder;
 pub mod factory;
+pub mod fragment;
 pub mod node;
 pub mod pretty_printer;
 pub mod visitor;
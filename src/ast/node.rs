@@ -1,20 +1,83 @@
+use std::fmt;
 use std::rc::Rc;
 
 use crate::bind::gate;
+use crate::bind::par;
 use crate::bind::reg;
 
 use crate::utils::location::Location;
 
+pub struct Version {
+    pub major: u32,
+    pub minor: u32,
+}
+
+impl Clone for Version {
+    fn clone(&self) -> Version {
+        Version {
+            major: self.major,
+            minor: self.minor,
+        }
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}", self.major, self.minor)
+    }
+}
+
+/// An include line, printed before the statement at `index` in
+/// `Program::stmts` so that the source order survives a round trip.
+pub struct Include {
+    pub loc: Location,
+    pub path: String,
+    pub index: usize,
+}
+
+impl Include {
+    pub fn get_loc(&self) -> &Location {
+        &self.loc
+    }
+}
+
+/// A pragma, printed before the statement at `index` in `Program::stmts`.
+pub struct Pragma {
+    pub loc: Location,
+    pub text: String,
+    pub index: usize,
+}
+
+impl Pragma {
+    pub fn get_loc(&self) -> &Location {
+        &self.loc
+    }
+}
+
+pub struct Program {
+    pub loc: Location,
+    pub version: Version,
+    pub includes: Vec<Include>,
+    pub pragmas: Vec<Pragma>,
+    pub stmts: Vec<Stmt>,
+}
+
+impl Program {
+    pub fn get_loc(&self) -> &Location {
+        &self.loc
+    }
+}
+
 pub enum RegTy {
     QReg,
     CReg,
 }
 
-impl ToString for RegTy {
-    fn to_string(&self) -> String {
+impl fmt::Display for RegTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            RegTy::QReg => String::from("qreg"),
-            RegTy::CReg => String::from("creg"),
+            RegTy::QReg => f.write_str("qreg"),
+            RegTy::CReg => f.write_str("creg"),
         }
     }
 }
@@ -33,9 +96,52 @@ pub enum Dec {
         loc: Location,
         name: String,
         ty: RegTy,
-        size: u32,
+        size: u64,
         dec: Option<Rc<reg::RegDec>>,
     },
+    GateDec {
+        loc: Location,
+        name: String,
+        pars: Vec<String>,
+        args: Vec<String>,
+        body: Option<Vec<Stmt>>,
+        dec: Option<Rc<gate::GateDec>>,
+    },
+}
+
+impl Clone for Dec {
+    fn clone(&self) -> Dec {
+        match self {
+            Dec::RegDec {
+                loc,
+                name,
+                ty,
+                size,
+                dec,
+            } => Dec::RegDec {
+                loc: loc.clone(),
+                name: name.clone(),
+                ty: ty.clone(),
+                size: *size,
+                dec: dec.clone(),
+            },
+            Dec::GateDec {
+                loc,
+                name,
+                pars,
+                args,
+                body,
+                dec,
+            } => Dec::GateDec {
+                loc: loc.clone(),
+                name: name.clone(),
+                pars: pars.clone(),
+                args: args.clone(),
+                body: body.clone(),
+                dec: dec.clone(),
+            },
+        }
+    }
 }
 
 impl Dec {
@@ -47,7 +153,15 @@ impl Dec {
                 ty: _,
                 size: _,
                 dec: _,
-            } => &loc,
+            } => loc,
+            Dec::GateDec {
+                loc,
+                name: _,
+                pars: _,
+                args: _,
+                body: _,
+                dec: _,
+            } => loc,
         }
     }
 }
@@ -61,11 +175,34 @@ pub enum Reg {
     SubscriptReg {
         loc: Location,
         name: String,
-        index: u32,
+        index: u64,
         dec: Option<Rc<reg::RegDec>>,
     },
 }
 
+impl Clone for Reg {
+    fn clone(&self) -> Reg {
+        match self {
+            Reg::SimpleReg { loc, name, dec } => Reg::SimpleReg {
+                loc: loc.clone(),
+                name: name.clone(),
+                dec: dec.clone(),
+            },
+            Reg::SubscriptReg {
+                loc,
+                name,
+                index,
+                dec,
+            } => Reg::SubscriptReg {
+                loc: loc.clone(),
+                name: name.clone(),
+                index: *index,
+                dec: dec.clone(),
+            },
+        }
+    }
+}
+
 impl Reg {
     pub fn get_loc(&self) -> &Location {
         match self {
@@ -73,13 +210,29 @@ impl Reg {
                 loc,
                 name: _,
                 dec: _,
-            } => &loc,
+            } => loc,
             Reg::SubscriptReg {
                 loc,
                 name: _,
                 index: _,
                 dec: _,
-            } => &loc,
+            } => loc,
+        }
+    }
+
+    pub fn get_dec(&self) -> Option<&Rc<reg::RegDec>> {
+        match self {
+            Reg::SimpleReg {
+                loc: _,
+                name: _,
+                dec,
+            } => dec.as_ref(),
+            Reg::SubscriptReg {
+                loc: _,
+                name: _,
+                index: _,
+                dec,
+            } => dec.as_ref(),
         }
     }
 }
@@ -102,6 +255,61 @@ pub enum Stmt {
         loc: Location,
         reg: Reg,
     },
+    BarrierStmt {
+        loc: Location,
+        args: Vec<Reg>,
+    },
+    IfStmt {
+        loc: Location,
+        reg: Reg,
+        value: u64,
+        stmt: Box<Stmt>,
+    },
+}
+
+impl Clone for Stmt {
+    fn clone(&self) -> Stmt {
+        match self {
+            Stmt::DecStmt(dec) => Stmt::DecStmt(dec.clone()),
+            Stmt::GateStmt {
+                loc,
+                gate,
+                pars,
+                args,
+                dec,
+            } => Stmt::GateStmt {
+                loc: loc.clone(),
+                gate: gate.clone(),
+                pars: pars.clone(),
+                args: args.clone(),
+                dec: dec.clone(),
+            },
+            Stmt::MeasureStmt { loc, src, dst } => Stmt::MeasureStmt {
+                loc: loc.clone(),
+                src: src.clone(),
+                dst: dst.clone(),
+            },
+            Stmt::ResetStmt { loc, reg } => Stmt::ResetStmt {
+                loc: loc.clone(),
+                reg: reg.clone(),
+            },
+            Stmt::BarrierStmt { loc, args } => Stmt::BarrierStmt {
+                loc: loc.clone(),
+                args: args.clone(),
+            },
+            Stmt::IfStmt {
+                loc,
+                reg,
+                value,
+                stmt,
+            } => Stmt::IfStmt {
+                loc: loc.clone(),
+                reg: reg.clone(),
+                value: *value,
+                stmt: stmt.clone(),
+            },
+        }
+    }
 }
 
 impl Stmt {
@@ -114,13 +322,20 @@ impl Stmt {
                 pars: _,
                 args: _,
                 dec: _,
-            } => &loc,
+            } => loc,
             Stmt::MeasureStmt {
                 loc,
                 src: _,
                 dst: _,
-            } => &loc,
-            Stmt::ResetStmt { loc, reg: _ } => &loc,
+            } => loc,
+            Stmt::ResetStmt { loc, reg: _ } => loc,
+            Stmt::BarrierStmt { loc, args: _ } => loc,
+            Stmt::IfStmt {
+                loc,
+                reg: _,
+                value: _,
+                stmt: _,
+            } => loc,
         }
     }
 }
@@ -133,14 +348,26 @@ pub enum BinopTy {
     Pow,
 }
 
-impl ToString for BinopTy {
-    fn to_string(&self) -> String {
+impl Clone for BinopTy {
+    fn clone(&self) -> BinopTy {
         match self {
-            BinopTy::Add => String::from("+"),
-            BinopTy::Sub => String::from("-"),
-            BinopTy::Mul => String::from("*"),
-            BinopTy::Div => String::from("/"),
-            BinopTy::Pow => String::from("^"),
+            BinopTy::Add => BinopTy::Add,
+            BinopTy::Sub => BinopTy::Sub,
+            BinopTy::Mul => BinopTy::Mul,
+            BinopTy::Div => BinopTy::Div,
+            BinopTy::Pow => BinopTy::Pow,
+        }
+    }
+}
+
+impl fmt::Display for BinopTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BinopTy::Add => f.write_str("+"),
+            BinopTy::Sub => f.write_str("-"),
+            BinopTy::Mul => f.write_str("*"),
+            BinopTy::Div => f.write_str("/"),
+            BinopTy::Pow => f.write_str("^"),
         }
     }
 }
@@ -155,16 +382,30 @@ pub enum UnaryOp {
     Sqrt,
 }
 
-impl ToString for UnaryOp {
-    fn to_string(&self) -> String {
+impl Clone for UnaryOp {
+    fn clone(&self) -> UnaryOp {
         match self {
-            UnaryOp::Minus => String::from("-"),
-            UnaryOp::Sin => String::from("sin"),
-            UnaryOp::Cos => String::from("cos"),
-            UnaryOp::Tan => String::from("tan"),
-            UnaryOp::Exp => String::from("exp"),
-            UnaryOp::Ln => String::from("ln"),
-            UnaryOp::Sqrt => String::from("sqrt"),
+            UnaryOp::Minus => UnaryOp::Minus,
+            UnaryOp::Sin => UnaryOp::Sin,
+            UnaryOp::Cos => UnaryOp::Cos,
+            UnaryOp::Tan => UnaryOp::Tan,
+            UnaryOp::Exp => UnaryOp::Exp,
+            UnaryOp::Ln => UnaryOp::Ln,
+            UnaryOp::Sqrt => UnaryOp::Sqrt,
+        }
+    }
+}
+
+impl fmt::Display for UnaryOp {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            UnaryOp::Minus => f.write_str("-"),
+            UnaryOp::Sin => f.write_str("sin"),
+            UnaryOp::Cos => f.write_str("cos"),
+            UnaryOp::Tan => f.write_str("tan"),
+            UnaryOp::Exp => f.write_str("exp"),
+            UnaryOp::Ln => f.write_str("ln"),
+            UnaryOp::Sqrt => f.write_str("sqrt"),
         }
     }
 }
@@ -172,15 +413,22 @@ impl ToString for UnaryOp {
 pub enum Exp {
     IntExp {
         loc: Location,
-        value: u32,
+        value: u64,
+        text: String,
     },
     RealExp {
         loc: Location,
-        value: f32,
+        value: f64,
+        text: String,
     },
     PiExp {
         loc: Location,
     },
+    IdExp {
+        loc: Location,
+        name: String,
+        dec: Option<Rc<par::ParDec>>,
+    },
     BinopExp {
         loc: Location,
         left: Box<Exp>,
@@ -194,19 +442,71 @@ pub enum Exp {
     },
 }
 
+impl Clone for Exp {
+    fn clone(&self) -> Exp {
+        match self {
+            Exp::IntExp { loc, value, text } => Exp::IntExp {
+                loc: loc.clone(),
+                value: *value,
+                text: text.clone(),
+            },
+            Exp::RealExp { loc, value, text } => Exp::RealExp {
+                loc: loc.clone(),
+                value: *value,
+                text: text.clone(),
+            },
+            Exp::PiExp { loc } => Exp::PiExp { loc: loc.clone() },
+            Exp::IdExp { loc, name, dec } => Exp::IdExp {
+                loc: loc.clone(),
+                name: name.clone(),
+                dec: dec.clone(),
+            },
+            Exp::BinopExp {
+                loc,
+                left,
+                op,
+                right,
+            } => Exp::BinopExp {
+                loc: loc.clone(),
+                left: left.clone(),
+                op: op.clone(),
+                right: right.clone(),
+            },
+            Exp::UnaryExp { loc, op, exp } => Exp::UnaryExp {
+                loc: loc.clone(),
+                op: op.clone(),
+                exp: exp.clone(),
+            },
+        }
+    }
+}
+
 impl Exp {
     pub fn get_loc(&self) -> &Location {
         match self {
-            Exp::IntExp { loc, value: _ } => &loc,
-            Exp::RealExp { loc, value: _ } => &loc,
-            Exp::PiExp { loc } => &loc,
+            Exp::IntExp {
+                loc,
+                value: _,
+                text: _,
+            } => loc,
+            Exp::RealExp {
+                loc,
+                value: _,
+                text: _,
+            } => loc,
+            Exp::PiExp { loc } => loc,
+            Exp::IdExp {
+                loc,
+                name: _,
+                dec: _,
+            } => loc,
             Exp::BinopExp {
                 loc,
                 left: _,
                 op: _,
                 right: _,
-            } => &loc,
-            Exp::UnaryExp { loc, op: _, exp: _ } => &loc,
+            } => loc,
+            Exp::UnaryExp { loc, op: _, exp: _ } => loc,
         }
     }
 }
@@ -1,9 +1,22 @@
-use std::vec::Vec;
-
 use crate::ast::node;
 use crate::ast::visitor::Visitor;
 
-pub struct PrettyPrinter;
+pub struct PrettyPrinter {
+    output: String,
+}
+
+macro_rules! emit {
+    ($printer:expr, $($arg:tt)*) => {
+        $printer.output += &format!($($arg)*)
+    };
+}
+
+macro_rules! emitln {
+    ($printer:expr, $($arg:tt)*) => {{
+        emit!($printer, $($arg)*);
+        $printer.output += "\n";
+    }};
+}
 
 impl Visitor for PrettyPrinter {
     fn visit_dec(&mut self, e: &node::Dec) {
@@ -16,7 +29,8 @@ impl Visitor for PrettyPrinter {
                 ref dec,
             } => {
                 if let Some(dec) = dec {
-                    println!(
+                    emitln!(
+                        self,
                         "{} {} /* {} */ [{}];",
                         ty.to_string(),
                         name,
@@ -24,7 +38,38 @@ impl Visitor for PrettyPrinter {
                         size
                     )
                 } else {
-                    println!("{} {}[{}];", ty.to_string(), name, size)
+                    emitln!(self, "{} {}[{}];", ty.to_string(), name, size)
+                }
+            }
+            node::Dec::GateDec {
+                loc: _,
+                name,
+                pars,
+                args,
+                body,
+                dec,
+            } => {
+                match body {
+                    Some(_) => emit!(self, "gate {}", name),
+                    None => emit!(self, "opaque {}", name),
+                }
+                if let Some(dec) = dec {
+                    emit!(self, " /* {} */", dec.to_string());
+                }
+                if !pars.is_empty() {
+                    emit!(self, "({})", pars.join(", "));
+                }
+                emit!(self, " {}", args.join(", "));
+                match body {
+                    Some(body) => {
+                        emitln!(self, " {{");
+                        let outer = std::mem::take(&mut self.output);
+                        body.iter().for_each(|e| self.visit_stmt(e));
+                        let inner = std::mem::replace(&mut self.output, outer);
+                        inner.lines().for_each(|line| emitln!(self, "  {}", line));
+                        emitln!(self, "}}");
+                    }
+                    None => emitln!(self, ";"),
                 }
             }
         }
@@ -32,15 +77,11 @@ impl Visitor for PrettyPrinter {
 
     fn visit_reg(&mut self, e: &node::Reg) {
         match e {
-            &node::Reg::SimpleReg {
-                loc: _,
-                ref name,
-                ref dec,
-            } => {
+            node::Reg::SimpleReg { loc: _, name, dec } => {
                 if let Some(dec) = dec {
-                    print!("{} /* {} */", name, dec.to_string())
+                    emit!(self, "{} /* {} */", name, dec.to_string())
                 } else {
-                    print!("{}", name)
+                    emit!(self, "{}", name)
                 }
             }
             &node::Reg::SubscriptReg {
@@ -50,9 +91,9 @@ impl Visitor for PrettyPrinter {
                 ref dec,
             } => {
                 if let Some(dec) = dec {
-                    print!("{} /* {} */ [{}]", name, dec.to_string(), index)
+                    emit!(self, "{} /* {} */ [{}]", name, dec.to_string(), index)
                 } else {
-                    print!("{}[{}]", name, index)
+                    emit!(self, "{}[{}]", name, index)
                 }
             }
         }
@@ -60,86 +101,153 @@ impl Visitor for PrettyPrinter {
 
     fn visit_stmt(&mut self, e: &node::Stmt) {
         match e {
-            &node::Stmt::DecStmt(ref dec) => self.visit_dec(dec),
-            &node::Stmt::GateStmt {
+            node::Stmt::DecStmt(dec) => self.visit_dec(dec),
+            node::Stmt::GateStmt {
                 loc: _,
-                ref gate,
-                ref pars,
-                ref args,
-                ref dec,
+                gate,
+                pars,
+                args,
+                dec,
             } => {
-                print!("{} ", gate);
+                emit!(self, "{} ", gate);
                 if let Some(dec) = dec {
-                    print!("/* {} */ ", dec.to_string());
+                    emit!(self, "/* {} */ ", dec.to_string());
                 }
                 if !pars.is_empty() {
-                    print!("(");
+                    emit!(self, "(");
                     self.visit_exp(&pars[0]);
                     pars[1..].iter().for_each(|e| {
-                        print!(", ");
+                        emit!(self, ", ");
                         self.visit_exp(e);
                     });
-                    print!(") ");
+                    emit!(self, ") ");
                 }
                 self.visit_reg(&args[0]);
                 args[1..].iter().for_each(|e| {
-                    print!(", ");
+                    emit!(self, ", ");
                     self.visit_reg(e);
                 });
-                println!(";");
+                emitln!(self, ";");
             }
-            &node::Stmt::MeasureStmt {
-                loc: _,
-                ref src,
-                ref dst,
-            } => {
-                print!("measure ");
+            node::Stmt::MeasureStmt { loc: _, src, dst } => {
+                emit!(self, "measure ");
                 self.visit_reg(src);
-                print!(" -> ");
+                emit!(self, " -> ");
                 self.visit_reg(dst);
-                println!(";");
+                emitln!(self, ";");
             }
-            &node::Stmt::ResetStmt { loc: _, ref reg } => {
-                print!("reset ");
+            node::Stmt::ResetStmt { loc: _, reg } => {
+                emit!(self, "reset ");
                 self.visit_reg(reg);
-                println!(";");
+                emitln!(self, ";");
+            }
+            node::Stmt::BarrierStmt { loc: _, args } => {
+                emit!(self, "barrier ");
+                self.visit_reg(&args[0]);
+                args[1..].iter().for_each(|e| {
+                    emit!(self, ", ");
+                    self.visit_reg(e);
+                });
+                emitln!(self, ";");
+            }
+            &node::Stmt::IfStmt {
+                loc: _,
+                ref reg,
+                value,
+                ref stmt,
+            } => {
+                emit!(self, "if (");
+                self.visit_reg(reg);
+                emit!(self, " == {}) ", value);
+                self.visit_stmt(stmt);
             }
         }
     }
 
     fn visit_exp(&mut self, e: &node::Exp) {
         match e {
-            &node::Exp::IntExp { loc: _, value } => print!("{}", value),
-            &node::Exp::RealExp { loc: _, value } => print!("{}", value),
-            &node::Exp::PiExp { loc: _ } => print!("pi"),
-            &node::Exp::BinopExp {
+            node::Exp::IntExp {
+                loc: _,
+                value: _,
+                text,
+            } => emit!(self, "{}", text),
+            node::Exp::RealExp {
+                loc: _,
+                value: _,
+                text,
+            } => emit!(self, "{}", text),
+            &node::Exp::PiExp { loc: _ } => emit!(self, "pi"),
+            node::Exp::IdExp { loc: _, name, dec } => {
+                if let Some(dec) = dec {
+                    emit!(self, "{} /* {} */", name, dec.to_string())
+                } else {
+                    emit!(self, "{}", name)
+                }
+            }
+            node::Exp::BinopExp {
                 loc: _,
-                ref left,
-                ref op,
-                ref right,
+                left,
+                op,
+                right,
             } => {
-                print!("(");
+                emit!(self, "(");
                 self.visit_exp(left.as_ref());
-                print!(" {} ", op.to_string());
+                emit!(self, " {} ", op.to_string());
                 self.visit_exp(right.as_ref());
-                print!(")");
+                emit!(self, ")");
             }
-            &node::Exp::UnaryExp {
-                loc: _,
-                ref op,
-                ref exp,
-            } => {
-                print!("{}(", op.to_string());
+            node::Exp::UnaryExp { loc: _, op, exp } => {
+                emit!(self, "{}(", op.to_string());
                 self.visit_exp(exp.as_ref());
-                print!(")");
+                emit!(self, ")");
             }
         }
     }
 }
 
+impl Default for PrettyPrinter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl PrettyPrinter {
-    pub fn pretty_print(&mut self, program: &Vec<node::Stmt>) {
-        println!("OPENQASM 2.0;");
-        program.iter().for_each(|e| self.visit_stmt(e));
+    pub fn new() -> PrettyPrinter {
+        PrettyPrinter {
+            output: String::new(),
+        }
+    }
+
+    pub fn pretty_print(&mut self, program: &node::Program) -> String {
+        emitln!(self, "OPENQASM {};", program.version.to_string());
+        let mut includes = program.includes.iter().peekable();
+        let mut pragmas = program.pragmas.iter().peekable();
+        for index in 0..=program.stmts.len() {
+            loop {
+                let include = includes.peek().copied().filter(|e| e.index <= index);
+                let pragma = pragmas.peek().copied().filter(|e| e.index <= index);
+                match (include, pragma) {
+                    (Some(include), Some(pragma))
+                        if pragma.loc.get_span() < include.loc.get_span() =>
+                    {
+                        emitln!(self, "pragma {}", pragma.text);
+                        pragmas.next();
+                    }
+                    (Some(include), _) => {
+                        emitln!(self, "include \"{}\";", include.path);
+                        includes.next();
+                    }
+                    (None, Some(pragma)) => {
+                        emitln!(self, "pragma {}", pragma.text);
+                        pragmas.next();
+                    }
+                    (None, None) => break,
+                }
+            }
+            if let Some(stmt) = program.stmts.get(index) {
+                self.visit_stmt(stmt);
+            }
+        }
+        std::mem::take(&mut self.output)
     }
 }
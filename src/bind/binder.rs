@@ -1,6 +1,5 @@
 use std::collections::HashMap;
 use std::rc::Rc;
-use std::vec::Vec;
 
 use crate::ast::node;
 use crate::ast::node::RegTy;
@@ -10,11 +9,15 @@ use crate::bind::gate::GateDec;
 use crate::bind::par::ParDec;
 use crate::bind::reg::RegDec;
 
+use crate::parse::keywords::Dialect;
+
 use crate::utils::error::{CompoundError, ErrorTy, LocatedError};
 
 pub struct Binder {
     gates: HashMap<String, Rc<GateDec>>,
     regs: HashMap<String, Rc<RegDec>>,
+    pars: HashMap<String, Rc<ParDec>>,
+    gate: Option<String>,
     error: CompoundError,
 }
 
@@ -40,6 +43,52 @@ impl MutVisitor for Binder {
                 dec.replace(Rc::clone(&it));
                 self.regs.insert(name.clone(), it);
             }
+            node::Dec::GateDec {
+                loc,
+                name,
+                pars,
+                args,
+                body,
+                dec,
+            } => {
+                if self.gates.contains_key(name) {
+                    self.error.add(LocatedError::new(
+                        ErrorTy::Bind,
+                        format!("redefined gate '{}'", name),
+                        loc.clone(),
+                    ));
+                    return;
+                }
+                let pars = pars
+                    .iter()
+                    .map(|par| Rc::new(ParDec::new(par.clone())))
+                    .collect::<Vec<Rc<ParDec>>>();
+                let args = args
+                    .iter()
+                    .map(|arg| Rc::new(RegDec::new_bit(arg.clone(), RegTy::QReg)))
+                    .collect::<Vec<Rc<RegDec>>>();
+                let it = Rc::new(GateDec::new(name.clone(), pars.clone(), args.clone()));
+                dec.replace(Rc::clone(&it));
+                self.gates.insert(name.clone(), it);
+
+                if let Some(body) = body {
+                    let regs = std::mem::replace(
+                        &mut self.regs,
+                        args.into_iter()
+                            .map(|arg| (arg.get_name().clone(), arg))
+                            .collect(),
+                    );
+                    self.pars = pars
+                        .into_iter()
+                        .map(|par| (par.get_name().clone(), par))
+                        .collect();
+                    self.gate = Some(name.clone());
+                    body.iter_mut().for_each(|stmt| self.visit_stmt(stmt));
+                    self.gate = None;
+                    self.pars.clear();
+                    self.regs = regs;
+                }
+            }
         }
     }
 
@@ -75,10 +124,17 @@ impl MutVisitor for Binder {
             node::Stmt::GateStmt {
                 loc,
                 gate,
-                pars: _,
+                pars,
                 args,
                 dec,
             } => {
+                if self.gate.as_ref() == Some(gate) {
+                    self.error.add(LocatedError::new(
+                        ErrorTy::Bind,
+                        format!("recursive gate '{}'", gate),
+                        loc.clone(),
+                    ));
+                }
                 match self.gates.get(gate) {
                     Some(it) => drop(dec.replace(Rc::clone(it))),
                     _ => self.error.add(LocatedError::new(
@@ -87,6 +143,7 @@ impl MutVisitor for Binder {
                         loc.clone(),
                     )),
                 };
+                pars.iter_mut().for_each(|par| self.visit_exp(par));
                 args.iter_mut().for_each(|arg| self.visit_reg(arg));
             }
             node::Stmt::MeasureStmt { loc: _, src, dst } => {
@@ -94,10 +151,50 @@ impl MutVisitor for Binder {
                 self.visit_reg(dst);
             }
             node::Stmt::ResetStmt { loc: _, reg } => self.visit_reg(reg),
+            node::Stmt::BarrierStmt { loc: _, args } => {
+                args.iter_mut().for_each(|arg| self.visit_reg(arg));
+            }
+            node::Stmt::IfStmt {
+                loc: _,
+                reg,
+                value: _,
+                stmt,
+            } => {
+                self.visit_reg(reg);
+                self.visit_stmt(stmt);
+            }
         }
     }
 
-    fn visit_exp(&mut self, _: &mut node::Exp) {}
+    fn visit_exp(&mut self, e: &mut node::Exp) {
+        match e {
+            node::Exp::IntExp { .. } | node::Exp::RealExp { .. } | node::Exp::PiExp { .. } => {}
+            node::Exp::IdExp { loc, name, dec } => match self.pars.get(name) {
+                Some(it) => drop(dec.replace(Rc::clone(it))),
+                _ => self.error.add(LocatedError::new(
+                    ErrorTy::Bind,
+                    format!("undeclared parameter '{}'", name),
+                    loc.clone(),
+                )),
+            },
+            node::Exp::BinopExp {
+                loc: _,
+                left,
+                op: _,
+                right,
+            } => {
+                self.visit_exp(left);
+                self.visit_exp(right);
+            }
+            node::Exp::UnaryExp { loc: _, op: _, exp } => self.visit_exp(exp),
+        }
+    }
+}
+
+impl Default for Binder {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Binder {
@@ -125,14 +222,41 @@ impl Binder {
         gates.insert(cxgate.get_name().clone(), cxgate);
 
         Binder {
-            gates: gates,
+            gates,
             regs: HashMap::new(),
+            pars: HashMap::new(),
+            gate: None,
             error: CompoundError::new(),
         }
     }
 
-    pub fn bind(&mut self, program: &mut Vec<node::Stmt>) {
-        program.iter_mut().for_each(|x| self.visit_stmt(x));
+    pub fn bind(&mut self, program: &mut node::Program) {
+        if let Some(Dialect::OpenQASM3) =
+            Dialect::from_version(program.version.major, program.version.minor)
+        {
+            self.gates.remove("CX");
+        }
+        program.stmts.iter_mut().for_each(|x| self.visit_stmt(x));
+    }
+
+    pub fn bind_stmt(&mut self, stmt: &mut node::Stmt) {
+        self.visit_stmt(stmt);
+    }
+
+    pub fn bind_exp(&mut self, exp: &mut node::Exp) {
+        self.visit_exp(exp);
+    }
+
+    pub fn declare_reg(&mut self, dec: Rc<RegDec>) {
+        self.regs.insert(dec.get_name().clone(), dec);
+    }
+
+    pub fn declare_gate(&mut self, dec: Rc<GateDec>) {
+        self.gates.insert(dec.get_name().clone(), dec);
+    }
+
+    pub fn declare_par(&mut self, dec: Rc<ParDec>) {
+        self.pars.insert(dec.get_name().clone(), dec);
     }
 
     pub fn get_error(&self) -> &CompoundError {
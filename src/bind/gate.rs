@@ -1,3 +1,4 @@
+use std::fmt;
 use std::rc::Rc;
 use std::vec::Vec;
 
@@ -13,9 +14,10 @@ pub struct GateDec {
     ty: Ty,
 }
 
-impl ToString for GateDec {
-    fn to_string(&self) -> String {
-        format!(
+impl fmt::Display for GateDec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(
+            f,
             "{:p} ({},[{}],[{}])",
             self,
             self.name,
@@ -37,10 +39,10 @@ impl GateDec {
     pub fn new(name: String, pars: Vec<Rc<ParDec>>, args: Vec<Rc<RegDec>>) -> GateDec {
         let ty = Ty::GateTy(pars.len() as u32, args.len() as u32);
         GateDec {
-            name: name,
-            pars: pars,
-            args: args,
-            ty: ty,
+            name,
+            pars,
+            args,
+            ty,
         }
     }
 
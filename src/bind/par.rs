@@ -1,19 +1,35 @@
+use std::fmt;
+
+use crate::typing::ty::Ty;
+
 pub struct ParDec {
     name: String,
+    ty: Ty,
 }
 
-impl ToString for ParDec {
-    fn to_string(&self) -> String {
-        format!("{:p} ({})", self, self.name)
+impl fmt::Display for ParDec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:p} ({})", self, self.name)
     }
 }
 
 impl ParDec {
     pub fn new(name: String) -> ParDec {
-        ParDec { name: name }
+        ParDec {
+            name,
+            ty: Ty::AngleTy,
+        }
+    }
+
+    pub fn new_typed(name: String, ty: Ty) -> ParDec {
+        ParDec { name, ty }
     }
 
     pub fn get_name(&self) -> &String {
         &self.name
     }
+
+    pub fn get_ty(&self) -> &Ty {
+        &self.ty
+    }
 }
@@ -1,3 +1,5 @@
+use std::fmt;
+
 use crate::ast::node::RegTy;
 
 use crate::typing::ty::Ty;
@@ -7,16 +9,16 @@ pub struct RegDec {
     ty: Ty,
 }
 
-impl ToString for RegDec {
-    fn to_string(&self) -> String {
-        format!("{:p} ({},{})", self, self.ty.to_string(), self.name)
+impl fmt::Display for RegDec {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{:p} ({},{})", self, self.ty, self.name)
     }
 }
 
 impl RegDec {
-    pub fn new(name: String, ty: RegTy, size: u32) -> RegDec {
+    pub fn new(name: String, ty: RegTy, size: u64) -> RegDec {
         RegDec {
-            name: name,
+            name,
             ty: match ty {
                 RegTy::QReg => Ty::QRegTy(size),
                 RegTy::CReg => Ty::CRegTy(size),
@@ -26,7 +28,7 @@ impl RegDec {
 
     pub fn new_bit(name: String, ty: RegTy) -> RegDec {
         RegDec {
-            name: name,
+            name,
             ty: match ty {
                 RegTy::QReg => Ty::QubitTy,
                 RegTy::CReg => Ty::BitTy,
@@ -0,0 +1,265 @@
+use std::f64::consts::{FRAC_PI_2, PI};
+use std::vec::Vec;
+
+use crate::ast::node;
+
+use crate::circuit::ir::{Circuit, GateSignature, Operation};
+use crate::circuit::unitary::normalize_angle;
+
+use crate::parse::parser;
+
+use crate::utils::error::{CompoundError, ErrorTy, SimpleError};
+
+const EPSILON: f64 = 1e-9;
+
+// Definitions in terms of U and CX, equal to the usual gates up to a global
+// phase, so that translated programs do not depend on any include file.
+const DEFINITIONS: [(&str, &str); 8] = [
+    (
+        "u3",
+        "gate u3(theta, phi, lambda) q { U(theta, phi, lambda) q; }",
+    ),
+    ("rz", "gate rz(phi) a { U(0, 0, phi) a; }"),
+    ("sx", "gate sx a { U(pi/2, -pi/2, pi/2) a; }"),
+    ("x", "gate x a { U(pi, 0, pi) a; }"),
+    ("rx", "gate rx(theta) a { U(theta, -pi/2, pi/2) a; }"),
+    ("ry", "gate ry(theta) a { U(theta, 0, 0) a; }"),
+    ("cx", "gate cx c, t { CX c, t; }"),
+    (
+        "cz",
+        "gate cz a, b { U(pi/2, 0, pi) b; CX a, b; U(pi/2, 0, pi) b; }",
+    ),
+];
+
+enum OneQubitRule {
+    U3,
+    RzSx,
+    RzRy,
+}
+
+enum TwoQubitRule {
+    Cx,
+    Cz,
+}
+
+pub struct Basis {
+    gates: Vec<String>,
+    one_qubit: OneQubitRule,
+    two_qubit: TwoQubitRule,
+}
+
+impl Basis {
+    // A comma separated list of gate names, e.g. `rz,sx,x,cx`.
+    pub fn parse(text: &str) -> Result<Basis, SimpleError> {
+        let mut gates: Vec<String> = Vec::new();
+        for name in text.split(',').map(|name| name.trim()) {
+            if !DEFINITIONS.iter().any(|(it, _)| *it == name) {
+                return Err(SimpleError::new(
+                    ErrorTy::Translate,
+                    format!("unknown basis gate '{}'", name),
+                ));
+            }
+            if !gates.iter().any(|it| it == name) {
+                gates.push(String::from(name));
+            }
+        }
+        let has = |name: &str| gates.iter().any(|it| it == name);
+
+        let one_qubit = if has("u3") {
+            OneQubitRule::U3
+        } else if has("rz") && has("sx") {
+            OneQubitRule::RzSx
+        } else if has("rz") && has("ry") {
+            OneQubitRule::RzRy
+        } else {
+            return Err(SimpleError::new(
+                ErrorTy::Translate,
+                "the basis cannot express every single-qubit gate",
+            ));
+        };
+        let two_qubit = if has("cx") {
+            TwoQubitRule::Cx
+        } else if has("cz") {
+            TwoQubitRule::Cz
+        } else {
+            return Err(SimpleError::new(
+                ErrorTy::Translate,
+                "the basis has no two-qubit entangling gate",
+            ));
+        };
+
+        Ok(Basis {
+            gates,
+            one_qubit,
+            two_qubit,
+        })
+    }
+
+    pub fn get_gates(&self) -> &Vec<String> {
+        &self.gates
+    }
+
+    fn contains(&self, name: &str) -> bool {
+        self.gates.iter().any(|it| it == name)
+    }
+}
+
+fn definition(name: &str) -> GateSignature {
+    let (_, text) = DEFINITIONS.iter().find(|(it, _)| *it == name).unwrap();
+    let program = parser::parse_str("<basis>", &format!("OPENQASM 2.0;\n{}\n", text))
+        .ok()
+        .unwrap();
+    match program.stmts.into_iter().next() {
+        Some(node::Stmt::DecStmt(node::Dec::GateDec {
+            loc: _,
+            name,
+            pars,
+            args,
+            body,
+            dec: _,
+        })) => GateSignature {
+            name,
+            pars,
+            args,
+            body,
+        },
+        _ => unreachable!(),
+    }
+}
+
+fn gate(name: &str, params: Vec<f64>, qubits: Vec<usize>) -> Operation {
+    Operation::Gate {
+        name: String::from(name),
+        params: params.into_iter().map(normalize_angle).collect(),
+        qubits,
+    }
+}
+
+fn is_zero(angle: f64) -> bool {
+    normalize_angle(angle).abs() < EPSILON
+}
+
+pub struct Translator {
+    basis: Basis,
+    error: CompoundError,
+}
+
+impl Translator {
+    pub fn new(basis: Basis) -> Translator {
+        Translator {
+            basis,
+            error: CompoundError::new(),
+        }
+    }
+
+    pub fn translate(&mut self, circuit: &Circuit) -> Option<Circuit> {
+        let mut ops: Vec<Operation> = Vec::new();
+        for op in circuit.ops.iter() {
+            self.translate_op(op, &mut ops);
+        }
+        if !self.error.empty() {
+            return None;
+        }
+        // The output declares every gate it uses, and an include such as
+        // qelib1.inc would declare some of them a second time.
+        Some(Circuit {
+            version: circuit.version.clone(),
+            includes: Vec::new(),
+            pragmas: circuit.pragmas.clone(),
+            qregs: circuit.qregs.clone(),
+            cregs: circuit.cregs.clone(),
+            gates: self
+                .basis
+                .gates
+                .iter()
+                .map(|name| definition(name))
+                .collect(),
+            ops,
+        })
+    }
+
+    fn translate_op(&mut self, op: &Operation, out: &mut Vec<Operation>) {
+        match op {
+            Operation::Gate { name, .. } if self.basis.contains(name) => out.push(op.clone()),
+            Operation::Gate {
+                name,
+                params,
+                qubits,
+            } if name == "U" && params.len() == 3 && qubits.len() == 1 => {
+                self.one_qubit(params[0], params[1], params[2], qubits[0], out)
+            }
+            Operation::Gate { name, qubits, .. } if name == "CX" && qubits.len() == 2 => {
+                self.two_qubit(qubits[0], qubits[1], out)
+            }
+            Operation::Gate { name, .. } => self.error.add(SimpleError::new(
+                ErrorTy::Translate,
+                format!("cannot translate gate '{}' to the basis", name),
+            )),
+            Operation::Conditional { creg, value, op } => {
+                let mut ops: Vec<Operation> = Vec::new();
+                self.translate_op(op, &mut ops);
+                for op in ops {
+                    out.push(Operation::Conditional {
+                        creg: *creg,
+                        value: *value,
+                        op: Box::new(op),
+                    });
+                }
+            }
+            _ => out.push(op.clone()),
+        }
+    }
+
+    fn one_qubit(&self, theta: f64, phi: f64, lambda: f64, qubit: usize, out: &mut Vec<Operation>) {
+        let rz = |angle: f64, out: &mut Vec<Operation>| {
+            if !is_zero(angle) {
+                out.push(gate("rz", vec![angle], vec![qubit]));
+            }
+        };
+        match self.basis.one_qubit {
+            OneQubitRule::U3 => out.push(gate("u3", vec![theta, phi, lambda], vec![qubit])),
+            OneQubitRule::RzSx if is_zero(theta) => rz(phi + lambda, out),
+            OneQubitRule::RzSx if is_zero(theta - FRAC_PI_2) => {
+                rz(lambda - FRAC_PI_2, out);
+                out.push(gate("sx", vec![], vec![qubit]));
+                rz(phi + FRAC_PI_2, out);
+            }
+            OneQubitRule::RzSx if is_zero(theta - PI) && self.basis.contains("x") => {
+                out.push(gate("x", vec![], vec![qubit]));
+                rz(phi - lambda + PI, out);
+            }
+            OneQubitRule::RzSx => {
+                rz(lambda, out);
+                out.push(gate("sx", vec![], vec![qubit]));
+                rz(theta + PI, out);
+                out.push(gate("sx", vec![], vec![qubit]));
+                rz(phi + PI, out);
+            }
+            OneQubitRule::RzRy if is_zero(theta) => rz(phi + lambda, out),
+            OneQubitRule::RzRy => {
+                rz(lambda, out);
+                out.push(gate("ry", vec![theta], vec![qubit]));
+                rz(phi, out);
+            }
+        }
+    }
+
+    fn two_qubit(&self, control: usize, target: usize, out: &mut Vec<Operation>) {
+        match self.basis.two_qubit {
+            TwoQubitRule::Cx => out.push(gate("cx", vec![], vec![control, target])),
+            TwoQubitRule::Cz => {
+                self.one_qubit(FRAC_PI_2, 0.0, PI, target, out);
+                out.push(gate("cz", vec![], vec![control, target]));
+                self.one_qubit(FRAC_PI_2, 0.0, PI, target, out);
+            }
+        }
+    }
+
+    pub fn get_error(&self) -> &CompoundError {
+        &self.error
+    }
+
+    pub fn get_error_mut(&mut self) -> &mut CompoundError {
+        &mut self.error
+    }
+}
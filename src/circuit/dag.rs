@@ -0,0 +1,343 @@
+use std::cmp::Reverse;
+use std::collections::BinaryHeap;
+use std::fmt;
+use std::vec::Vec;
+
+use crate::circuit::ir::{Circuit, Operation};
+
+pub type NodeId = usize;
+
+pub enum Wire {
+    Qubit(usize),
+    Clbit(usize),
+}
+
+impl Clone for Wire {
+    fn clone(&self) -> Wire {
+        *self
+    }
+}
+
+impl Copy for Wire {}
+
+impl PartialEq for Wire {
+    fn eq(&self, other: &Wire) -> bool {
+        match (self, other) {
+            (Wire::Qubit(a), Wire::Qubit(b)) => a == b,
+            (Wire::Clbit(a), Wire::Clbit(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for Wire {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Wire::Qubit(index) => write!(f, "q{}", index),
+            Wire::Clbit(index) => write!(f, "c{}", index),
+        }
+    }
+}
+
+struct Node {
+    op: Operation,
+    wires: Vec<Wire>,
+    prev: Vec<Option<NodeId>>,
+    next: Vec<Option<NodeId>>,
+}
+
+impl Node {
+    fn position(&self, wire: Wire) -> Option<usize> {
+        self.wires.iter().position(|it| *it == wire)
+    }
+}
+
+// Nodes keep their id for as long as they live; removed slots stay empty so
+// ids handed out earlier never refer to a different operation.
+pub struct Dag {
+    circuit: Circuit,
+    nodes: Vec<Option<Node>>,
+    first: Vec<Option<NodeId>>,
+    last: Vec<Option<NodeId>>,
+}
+
+impl Dag {
+    pub fn new(mut circuit: Circuit) -> Dag {
+        let ops = std::mem::take(&mut circuit.ops);
+        let wires = circuit.num_qubits() + circuit.num_clbits();
+        let mut dag = Dag {
+            circuit,
+            nodes: Vec::new(),
+            first: vec![None; wires],
+            last: vec![None; wires],
+        };
+        for op in ops {
+            dag.push(op);
+        }
+        dag
+    }
+
+    pub fn to_circuit(&self) -> Circuit {
+        Circuit {
+            version: self.circuit.version.clone(),
+            includes: self.circuit.includes.clone(),
+            pragmas: self.circuit.pragmas.clone(),
+            qregs: self.circuit.qregs.clone(),
+            cregs: self.circuit.cregs.clone(),
+            gates: self.circuit.gates.clone(),
+            ops: self
+                .topological_order()
+                .into_iter()
+                .map(|id| self.node(id).op.clone())
+                .collect(),
+        }
+    }
+
+    pub fn get_circuit(&self) -> &Circuit {
+        &self.circuit
+    }
+
+    fn slot(&self, wire: Wire) -> usize {
+        match wire {
+            Wire::Qubit(index) => index,
+            Wire::Clbit(index) => self.circuit.num_qubits() + index,
+        }
+    }
+
+    fn node(&self, id: NodeId) -> &Node {
+        self.nodes[id].as_ref().expect("removed node")
+    }
+
+    fn node_mut(&mut self, id: NodeId) -> &mut Node {
+        self.nodes[id].as_mut().expect("removed node")
+    }
+
+    fn wires(&self, op: &Operation) -> Vec<Wire> {
+        let mut wires: Vec<Wire> = Vec::new();
+        let qubits = op.get_qubits().into_iter().map(Wire::Qubit);
+        let clbits = self.circuit.get_clbits(op).into_iter().map(Wire::Clbit);
+        for wire in qubits.chain(clbits) {
+            if !wires.contains(&wire) {
+                wires.push(wire);
+            }
+        }
+        wires
+    }
+
+    pub fn push(&mut self, op: Operation) -> NodeId {
+        let id = self.nodes.len();
+        let wires = self.wires(&op);
+        let mut prev: Vec<Option<NodeId>> = Vec::new();
+        for wire in wires.iter() {
+            let slot = self.slot(*wire);
+            prev.push(self.last[slot]);
+            match self.last[slot] {
+                Some(last) => {
+                    let node = self.node_mut(last);
+                    let position = node.position(*wire).unwrap();
+                    node.next[position] = Some(id);
+                }
+                None => self.first[slot] = Some(id),
+            }
+            self.last[slot] = Some(id);
+        }
+        self.nodes.push(Some(Node {
+            op,
+            next: vec![None; wires.len()],
+            wires,
+            prev,
+        }));
+        id
+    }
+
+    fn insert_before(&mut self, at: NodeId, op: Operation) -> NodeId {
+        let id = self.nodes.len();
+        let wires = self.wires(&op);
+        let mut prev: Vec<Option<NodeId>> = Vec::new();
+        let mut next: Vec<Option<NodeId>> = Vec::new();
+        for wire in wires.iter() {
+            let position = self.node(at).position(*wire).unwrap();
+            let before = self.node(at).prev[position];
+            match before {
+                Some(before) => {
+                    let node = self.node_mut(before);
+                    let position = node.position(*wire).unwrap();
+                    node.next[position] = Some(id);
+                }
+                None => {
+                    let slot = self.slot(*wire);
+                    self.first[slot] = Some(id);
+                }
+            }
+            self.node_mut(at).prev[position] = Some(id);
+            prev.push(before);
+            next.push(Some(at));
+        }
+        self.nodes.push(Some(Node {
+            op,
+            wires,
+            prev,
+            next,
+        }));
+        id
+    }
+
+    pub fn remove(&mut self, id: NodeId) -> Option<Operation> {
+        let node = self.nodes.get_mut(id)?.take()?;
+        for (i, wire) in node.wires.iter().enumerate() {
+            let slot = self.slot(*wire);
+            match node.prev[i] {
+                Some(prev) => {
+                    let prev = self.node_mut(prev);
+                    let position = prev.position(*wire).unwrap();
+                    prev.next[position] = node.next[i];
+                }
+                None => self.first[slot] = node.next[i],
+            }
+            match node.next[i] {
+                Some(next) => {
+                    let next = self.node_mut(next);
+                    let position = next.position(*wire).unwrap();
+                    next.prev[position] = node.prev[i];
+                }
+                None => self.last[slot] = node.prev[i],
+            }
+        }
+        Some(node.op)
+    }
+
+    // Replaces a node by a sequence of operations, which may only act on the
+    // wires of the node they replace.
+    pub fn substitute(&mut self, id: NodeId, ops: Vec<Operation>) -> Option<Vec<NodeId>> {
+        let wires = &self.nodes.get(id)?.as_ref()?.wires;
+        if ops
+            .iter()
+            .flat_map(|op| self.wires(op))
+            .any(|wire| !wires.contains(&wire))
+        {
+            return None;
+        }
+        let ids = ops
+            .into_iter()
+            .map(|op| self.insert_before(id, op))
+            .collect();
+        self.remove(id);
+        Some(ids)
+    }
+
+    pub fn len(&self) -> usize {
+        self.nodes.iter().filter(|node| node.is_some()).count()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn node_ids(&self) -> Vec<NodeId> {
+        (0..self.nodes.len())
+            .filter(|id| self.nodes[*id].is_some())
+            .collect()
+    }
+
+    pub fn get_op(&self, id: NodeId) -> Option<&Operation> {
+        Some(&self.nodes.get(id)?.as_ref()?.op)
+    }
+
+    pub fn get_wires(&self, id: NodeId) -> Option<&Vec<Wire>> {
+        Some(&self.nodes.get(id)?.as_ref()?.wires)
+    }
+
+    pub fn first(&self, wire: Wire) -> Option<NodeId> {
+        self.first[self.slot(wire)]
+    }
+
+    pub fn last(&self, wire: Wire) -> Option<NodeId> {
+        self.last[self.slot(wire)]
+    }
+
+    pub fn predecessor(&self, id: NodeId, wire: Wire) -> Option<NodeId> {
+        let node = self.nodes.get(id)?.as_ref()?;
+        node.prev[node.position(wire)?]
+    }
+
+    pub fn successor(&self, id: NodeId, wire: Wire) -> Option<NodeId> {
+        let node = self.nodes.get(id)?.as_ref()?;
+        node.next[node.position(wire)?]
+    }
+
+    pub fn predecessors(&self, id: NodeId) -> Vec<NodeId> {
+        let mut ids: Vec<NodeId> = Vec::new();
+        for prev in self.node(id).prev.iter().flatten() {
+            if !ids.contains(prev) {
+                ids.push(*prev);
+            }
+        }
+        ids
+    }
+
+    pub fn successors(&self, id: NodeId) -> Vec<NodeId> {
+        let mut ids: Vec<NodeId> = Vec::new();
+        for next in self.node(id).next.iter().flatten() {
+            if !ids.contains(next) {
+                ids.push(*next);
+            }
+        }
+        ids
+    }
+
+    pub fn front_layer(&self) -> Vec<NodeId> {
+        self.node_ids()
+            .into_iter()
+            .filter(|id| self.node(*id).prev.iter().all(|prev| prev.is_none()))
+            .collect()
+    }
+
+    // Ties are broken by node id, so an untouched DAG yields its operations in
+    // program order.
+    pub fn topological_order(&self) -> Vec<NodeId> {
+        let mut order: Vec<NodeId> = Vec::new();
+        let mut pending = self.pending();
+        let mut ready: BinaryHeap<Reverse<NodeId>> =
+            self.front_layer().into_iter().map(Reverse).collect();
+        while let Some(Reverse(id)) = ready.pop() {
+            order.push(id);
+            for next in self.successors(id) {
+                pending[next] -= 1;
+                if pending[next] == 0 {
+                    ready.push(Reverse(next));
+                }
+            }
+        }
+        order
+    }
+
+    pub fn layers(&self) -> Vec<Vec<NodeId>> {
+        let mut layers: Vec<Vec<NodeId>> = Vec::new();
+        let mut pending = self.pending();
+        let mut layer = self.front_layer();
+        while !layer.is_empty() {
+            let mut next_layer: Vec<NodeId> = Vec::new();
+            for id in layer.iter() {
+                for next in self.successors(*id) {
+                    pending[next] -= 1;
+                    if pending[next] == 0 {
+                        next_layer.push(next);
+                    }
+                }
+            }
+            next_layer.sort();
+            layers.push(layer);
+            layer = next_layer;
+        }
+        layers
+    }
+
+    fn pending(&self) -> Vec<usize> {
+        (0..self.nodes.len())
+            .map(|id| match &self.nodes[id] {
+                Some(_) => self.predecessors(id).len(),
+                None => 0,
+            })
+            .collect()
+    }
+}
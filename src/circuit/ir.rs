@@ -0,0 +1,253 @@
+use std::vec::Vec;
+
+use crate::ast::factory;
+use crate::ast::node;
+
+use crate::eval::value::Value;
+
+use crate::utils::location::Location;
+
+pub struct Register {
+    pub name: String,
+    pub offset: usize,
+    pub size: usize,
+}
+
+impl Clone for Register {
+    fn clone(&self) -> Register {
+        Register {
+            name: self.name.clone(),
+            offset: self.offset,
+            size: self.size,
+        }
+    }
+}
+
+impl Register {
+    pub fn contains(&self, index: usize) -> bool {
+        self.offset <= index && index < self.offset + self.size
+    }
+}
+
+pub struct GateSignature {
+    pub name: String,
+    pub pars: Vec<String>,
+    pub args: Vec<String>,
+    pub body: Option<Vec<node::Stmt>>,
+}
+
+impl Clone for GateSignature {
+    fn clone(&self) -> GateSignature {
+        GateSignature {
+            name: self.name.clone(),
+            pars: self.pars.clone(),
+            args: self.args.clone(),
+            body: self.body.clone(),
+        }
+    }
+}
+
+pub enum Operation {
+    Gate {
+        name: String,
+        params: Vec<f64>,
+        qubits: Vec<usize>,
+    },
+    Measure {
+        qubit: usize,
+        clbit: usize,
+    },
+    Reset {
+        qubit: usize,
+    },
+    Barrier {
+        qubits: Vec<usize>,
+    },
+    Conditional {
+        creg: usize,
+        value: u64,
+        op: Box<Operation>,
+    },
+}
+
+impl Clone for Operation {
+    fn clone(&self) -> Operation {
+        match self {
+            Operation::Gate {
+                name,
+                params,
+                qubits,
+            } => Operation::Gate {
+                name: name.clone(),
+                params: params.clone(),
+                qubits: qubits.clone(),
+            },
+            Operation::Measure { qubit, clbit } => Operation::Measure {
+                qubit: *qubit,
+                clbit: *clbit,
+            },
+            Operation::Reset { qubit } => Operation::Reset { qubit: *qubit },
+            Operation::Barrier { qubits } => Operation::Barrier {
+                qubits: qubits.clone(),
+            },
+            Operation::Conditional { creg, value, op } => Operation::Conditional {
+                creg: *creg,
+                value: *value,
+                op: op.clone(),
+            },
+        }
+    }
+}
+
+impl Operation {
+    pub fn get_qubits(&self) -> Vec<usize> {
+        match self {
+            Operation::Gate { qubits, .. } => qubits.clone(),
+            Operation::Measure { qubit, clbit: _ } => vec![*qubit],
+            Operation::Reset { qubit } => vec![*qubit],
+            Operation::Barrier { qubits } => qubits.clone(),
+            Operation::Conditional { op, .. } => op.get_qubits(),
+        }
+    }
+}
+
+// User gates are kept as named operations and their declarations are printed
+// back with their bodies; run the inliner first to get a circuit over U and CX
+// only.
+pub struct Circuit {
+    pub version: node::Version,
+    pub includes: Vec<String>,
+    pub pragmas: Vec<String>,
+    pub qregs: Vec<Register>,
+    pub cregs: Vec<Register>,
+    pub gates: Vec<GateSignature>,
+    pub ops: Vec<Operation>,
+}
+
+fn find(regs: &[Register], index: usize) -> &Register {
+    regs.iter()
+        .find(|reg| reg.contains(index))
+        .expect("index outside of every register")
+}
+
+fn make_bit(regs: &[Register], index: usize) -> node::Reg {
+    let reg = find(regs, index);
+    factory::make_subscript_reg(
+        Location::new_synthetic(),
+        reg.name.clone(),
+        (index - reg.offset) as u64,
+    )
+}
+
+impl Circuit {
+    pub fn num_qubits(&self) -> usize {
+        self.qregs.iter().map(|reg| reg.size).sum()
+    }
+
+    pub fn num_clbits(&self) -> usize {
+        self.cregs.iter().map(|reg| reg.size).sum()
+    }
+
+    pub fn get_clbits(&self, op: &Operation) -> Vec<usize> {
+        match op {
+            Operation::Measure { qubit: _, clbit } => vec![*clbit],
+            Operation::Conditional { creg, value: _, op } => {
+                let reg = &self.cregs[*creg];
+                let mut clbits: Vec<usize> = (reg.offset..reg.offset + reg.size).collect();
+                for clbit in self.get_clbits(op) {
+                    if !clbits.contains(&clbit) {
+                        clbits.push(clbit);
+                    }
+                }
+                clbits
+            }
+            _ => Vec::new(),
+        }
+    }
+
+    pub fn to_program(&self) -> node::Program {
+        let loc = Location::new_synthetic();
+        let mut stmts: Vec<node::Stmt> = Vec::new();
+        for gate in self.gates.iter() {
+            stmts.push(factory::make_gate_dec(
+                loc.clone(),
+                gate.name.clone(),
+                gate.pars.clone(),
+                gate.args.clone(),
+                gate.body.clone(),
+            ));
+        }
+        for (regs, ty) in [
+            (&self.qregs, node::RegTy::QReg),
+            (&self.cregs, node::RegTy::CReg),
+        ] {
+            for reg in regs.iter() {
+                stmts.push(factory::make_reg_dec(
+                    loc.clone(),
+                    reg.name.clone(),
+                    ty.clone(),
+                    reg.size as u64,
+                ));
+            }
+        }
+        for op in self.ops.iter() {
+            stmts.push(self.make_stmt(op));
+        }
+        factory::make_program(
+            loc.clone(),
+            self.version.clone(),
+            self.includes
+                .iter()
+                .map(|path| factory::make_include(loc.clone(), path.clone(), 0))
+                .collect(),
+            self.pragmas
+                .iter()
+                .map(|text| factory::make_pragma(loc.clone(), text.clone(), 0))
+                .collect(),
+            stmts,
+        )
+    }
+
+    fn make_stmt(&self, op: &Operation) -> node::Stmt {
+        let loc = Location::new_synthetic();
+        match op {
+            Operation::Gate {
+                name,
+                params,
+                qubits,
+            } => factory::make_gate_stmt(
+                loc.clone(),
+                name.clone(),
+                params
+                    .iter()
+                    .map(|param| Value::from_f64(*param).to_exp(&loc))
+                    .collect(),
+                qubits
+                    .iter()
+                    .map(|qubit| make_bit(&self.qregs, *qubit))
+                    .collect(),
+            ),
+            Operation::Measure { qubit, clbit } => factory::make_measure_stmt(
+                loc,
+                make_bit(&self.qregs, *qubit),
+                make_bit(&self.cregs, *clbit),
+            ),
+            Operation::Reset { qubit } => {
+                factory::make_reset_stmt(loc, make_bit(&self.qregs, *qubit))
+            }
+            Operation::Barrier { qubits } => factory::make_barrier_stmt(
+                loc,
+                qubits
+                    .iter()
+                    .map(|qubit| make_bit(&self.qregs, *qubit))
+                    .collect(),
+            ),
+            Operation::Conditional { creg, value, op } => factory::make_if_stmt(
+                loc.clone(),
+                factory::make_simple_reg(loc, self.cregs[*creg].name.clone()),
+                *value,
+                self.make_stmt(op),
+            ),
+        }
+    }
+}
@@ -0,0 +1,302 @@
+use std::collections::HashMap;
+use std::vec::Vec;
+
+use crate::ast::node;
+
+use crate::circuit::ir::{Circuit, GateSignature, Operation, Register};
+
+use crate::eval::evaluator::Evaluator;
+
+use crate::utils::error::{CompoundError, ErrorTy, LocatedError};
+use crate::utils::location::Location;
+
+pub struct Lowerer {
+    qregs: HashMap<String, Register>,
+    cregs: HashMap<String, (usize, Register)>,
+    evaluator: Evaluator,
+    error: CompoundError,
+}
+
+impl Default for Lowerer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Lowerer {
+    pub fn new() -> Lowerer {
+        Lowerer {
+            qregs: HashMap::new(),
+            cregs: HashMap::new(),
+            evaluator: Evaluator::new(),
+            error: CompoundError::new(),
+        }
+    }
+
+    pub fn lower(&mut self, program: &node::Program) -> Option<Circuit> {
+        let mut circuit = Circuit {
+            version: program.version.clone(),
+            includes: program
+                .includes
+                .iter()
+                .map(|include| include.path.clone())
+                .collect(),
+            pragmas: program
+                .pragmas
+                .iter()
+                .map(|pragma| pragma.text.clone())
+                .collect(),
+            qregs: Vec::new(),
+            cregs: Vec::new(),
+            gates: Vec::new(),
+            ops: Vec::new(),
+        };
+
+        for stmt in program.stmts.iter() {
+            match stmt {
+                node::Stmt::DecStmt(node::Dec::RegDec {
+                    loc: _,
+                    name,
+                    ty,
+                    size,
+                    dec: _,
+                }) => self.lower_reg_dec(&mut circuit, name, ty, *size as usize),
+                node::Stmt::DecStmt(node::Dec::GateDec {
+                    loc: _,
+                    name,
+                    pars,
+                    args,
+                    body,
+                    dec: _,
+                }) => circuit.gates.push(GateSignature {
+                    name: name.clone(),
+                    pars: pars.clone(),
+                    args: args.clone(),
+                    body: body.clone(),
+                }),
+                stmt => self.lower_stmt(stmt, &mut circuit.ops),
+            }
+        }
+
+        if self.error.empty() {
+            Some(circuit)
+        } else {
+            None
+        }
+    }
+
+    fn lower_reg_dec(&mut self, circuit: &mut Circuit, name: &str, ty: &node::RegTy, size: usize) {
+        let regs = match ty {
+            node::RegTy::QReg => &mut circuit.qregs,
+            node::RegTy::CReg => &mut circuit.cregs,
+        };
+        let reg = Register {
+            name: String::from(name),
+            offset: regs.iter().map(|reg| reg.size).sum(),
+            size,
+        };
+        match ty {
+            node::RegTy::QReg => {
+                self.qregs.insert(String::from(name), reg.clone());
+            }
+            node::RegTy::CReg => {
+                self.cregs
+                    .insert(String::from(name), (regs.len(), reg.clone()));
+            }
+        }
+        regs.push(reg);
+    }
+
+    fn lower_stmt(&mut self, stmt: &node::Stmt, out: &mut Vec<Operation>) {
+        match stmt {
+            node::Stmt::DecStmt(_) => {}
+            node::Stmt::GateStmt {
+                loc,
+                gate,
+                pars,
+                args,
+                dec: _,
+            } => {
+                let params = match self.lower_pars(pars) {
+                    Some(params) => params,
+                    None => return,
+                };
+                let operands = args
+                    .iter()
+                    .map(|arg| self.lower_qubits(arg))
+                    .collect::<Option<Vec<Vec<usize>>>>();
+                for qubits in self.broadcast(operands, loc) {
+                    out.push(Operation::Gate {
+                        name: gate.clone(),
+                        params: params.clone(),
+                        qubits,
+                    });
+                }
+            }
+            node::Stmt::MeasureStmt { loc, src, dst } => {
+                let operands = match (self.lower_qubits(src), self.lower_clbits(dst)) {
+                    (Some(src), Some(dst)) => Some(vec![src, dst]),
+                    _ => None,
+                };
+                for bits in self.broadcast(operands, loc) {
+                    out.push(Operation::Measure {
+                        qubit: bits[0],
+                        clbit: bits[1],
+                    });
+                }
+            }
+            node::Stmt::ResetStmt { loc: _, reg } => {
+                for qubit in self.lower_qubits(reg).unwrap_or_default() {
+                    out.push(Operation::Reset { qubit });
+                }
+            }
+            node::Stmt::BarrierStmt { loc: _, args } => {
+                let mut qubits: Vec<usize> = Vec::new();
+                for qubit in args
+                    .iter()
+                    .filter_map(|arg| self.lower_qubits(arg))
+                    .flatten()
+                {
+                    if !qubits.contains(&qubit) {
+                        qubits.push(qubit);
+                    }
+                }
+                out.push(Operation::Barrier { qubits });
+            }
+            node::Stmt::IfStmt {
+                loc: _,
+                reg,
+                value,
+                stmt,
+            } => {
+                let creg = match self.cregs.get(reg_name(reg)) {
+                    Some((creg, _)) => *creg,
+                    None => return self.undeclared(reg),
+                };
+                let mut ops: Vec<Operation> = Vec::new();
+                self.lower_stmt(stmt, &mut ops);
+                for op in ops {
+                    out.push(Operation::Conditional {
+                        creg,
+                        value: *value,
+                        op: Box::new(op),
+                    });
+                }
+            }
+        }
+    }
+
+    fn lower_pars(&mut self, pars: &[node::Exp]) -> Option<Vec<f64>> {
+        let mut params: Vec<f64> = Vec::new();
+        for par in pars.iter() {
+            match self.evaluator.eval(par) {
+                Some(value) => params.push(value.to_f64()),
+                None => {
+                    self.error.consume(self.evaluator.get_error_mut());
+                    if self.error.empty() {
+                        self.error.add(LocatedError::new(
+                            ErrorTy::Eval,
+                            "expected a constant parameter",
+                            par.get_loc().clone(),
+                        ));
+                    }
+                    return None;
+                }
+            }
+        }
+        Some(params)
+    }
+
+    fn lower_qubits(&mut self, reg: &node::Reg) -> Option<Vec<usize>> {
+        match self.qregs.get(reg_name(reg)) {
+            Some(it) => {
+                let it = it.clone();
+                self.lower_bits(reg, &it)
+            }
+            None => {
+                self.undeclared(reg);
+                None
+            }
+        }
+    }
+
+    fn lower_clbits(&mut self, reg: &node::Reg) -> Option<Vec<usize>> {
+        match self.cregs.get(reg_name(reg)) {
+            Some((_, it)) => {
+                let it = it.clone();
+                self.lower_bits(reg, &it)
+            }
+            None => {
+                self.undeclared(reg);
+                None
+            }
+        }
+    }
+
+    fn lower_bits(&mut self, reg: &node::Reg, dec: &Register) -> Option<Vec<usize>> {
+        match reg {
+            node::Reg::SimpleReg { .. } => Some((dec.offset..dec.offset + dec.size).collect()),
+            node::Reg::SubscriptReg { loc, index, .. } => {
+                if *index as usize >= dec.size {
+                    self.error.add(LocatedError::new(
+                        ErrorTy::Type,
+                        format!("index {} out of range for '{}'", index, dec.name),
+                        loc.clone(),
+                    ));
+                    return None;
+                }
+                Some(vec![dec.offset + *index as usize])
+            }
+        }
+    }
+
+    fn broadcast(&mut self, operands: Option<Vec<Vec<usize>>>, loc: &Location) -> Vec<Vec<usize>> {
+        let operands = match operands {
+            Some(operands) => operands,
+            None => return Vec::new(),
+        };
+        let width = operands.iter().map(|bits| bits.len()).max().unwrap_or(1);
+        if operands
+            .iter()
+            .any(|bits| bits.len() != 1 && bits.len() != width)
+        {
+            self.error.add(LocatedError::new(
+                ErrorTy::Type,
+                "registers of different sizes",
+                loc.clone(),
+            ));
+            return Vec::new();
+        }
+        (0..width)
+            .map(|i| {
+                operands
+                    .iter()
+                    .map(|bits| if bits.len() == 1 { bits[0] } else { bits[i] })
+                    .collect()
+            })
+            .collect()
+    }
+
+    fn undeclared(&mut self, reg: &node::Reg) {
+        self.error.add(LocatedError::new(
+            ErrorTy::Bind,
+            format!("undeclared register '{}'", reg_name(reg)),
+            reg.get_loc().clone(),
+        ));
+    }
+
+    pub fn get_error(&self) -> &CompoundError {
+        &self.error
+    }
+
+    pub fn get_error_mut(&mut self) -> &mut CompoundError {
+        &mut self.error
+    }
+}
+
+fn reg_name(reg: &node::Reg) -> &str {
+    match reg {
+        node::Reg::SimpleReg { name, .. } => name,
+        node::Reg::SubscriptReg { name, .. } => name,
+    }
+}
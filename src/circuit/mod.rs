@@ -0,0 +1,7 @@
+pub mod basis;
+pub mod dag;
+pub mod ir;
+pub mod lower;
+pub mod optimizer;
+pub mod routing;
+pub mod unitary;
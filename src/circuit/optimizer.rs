@@ -0,0 +1,112 @@
+use crate::circuit::dag::{Dag, NodeId, Wire};
+use crate::circuit::ir::Operation;
+use crate::circuit::unitary::{self, Matrix};
+
+// Level 1 drops identities and cancels pairs of CX, level 2 also merges runs
+// of U gates on the same qubit.
+pub struct Optimizer {
+    level: u32,
+}
+
+fn as_u(op: Option<&Operation>) -> Option<(f64, f64, f64, usize)> {
+    match op? {
+        Operation::Gate {
+            name,
+            params,
+            qubits,
+        } if name == "U" && params.len() == 3 && qubits.len() == 1 => {
+            Some((params[0], params[1], params[2], qubits[0]))
+        }
+        _ => None,
+    }
+}
+
+fn as_cx(op: Option<&Operation>) -> Option<(usize, usize)> {
+    match op? {
+        Operation::Gate { name, qubits, .. } if name == "CX" && qubits.len() == 2 => {
+            Some((qubits[0], qubits[1]))
+        }
+        _ => None,
+    }
+}
+
+impl Optimizer {
+    pub fn new(level: u32) -> Optimizer {
+        Optimizer { level }
+    }
+
+    pub fn optimize(&self, dag: &mut Dag) {
+        if self.level == 0 {
+            return;
+        }
+        let mut changed = true;
+        while changed {
+            changed = false;
+            for id in dag.topological_order() {
+                if dag.get_op(id).is_none() {
+                    continue;
+                }
+                changed |= self.drop_identity(dag, id)
+                    || self.cancel_cx(dag, id)
+                    || (self.level >= 2 && self.merge_u(dag, id));
+            }
+        }
+    }
+
+    fn drop_identity(&self, dag: &mut Dag, id: NodeId) -> bool {
+        match as_u(dag.get_op(id)) {
+            Some((theta, phi, lambda, _))
+                if unitary::u_matrix(theta, phi, lambda).equivalent(&Matrix::identity(2)) =>
+            {
+                dag.remove(id);
+                true
+            }
+            _ => false,
+        }
+    }
+
+    fn cancel_cx(&self, dag: &mut Dag, id: NodeId) -> bool {
+        let (control, target) = match as_cx(dag.get_op(id)) {
+            Some(it) => it,
+            None => return false,
+        };
+        let next = match dag.successor(id, Wire::Qubit(control)) {
+            Some(next) if dag.successor(id, Wire::Qubit(target)) == Some(next) => next,
+            _ => return false,
+        };
+        if as_cx(dag.get_op(next)) != Some((control, target)) {
+            return false;
+        }
+        dag.remove(next);
+        dag.remove(id);
+        true
+    }
+
+    fn merge_u(&self, dag: &mut Dag, id: NodeId) -> bool {
+        let (theta, phi, lambda, qubit) = match as_u(dag.get_op(id)) {
+            Some(it) => it,
+            None => return false,
+        };
+        let next = match dag.successor(id, Wire::Qubit(qubit)) {
+            Some(next) => next,
+            None => return false,
+        };
+        let matrix = match as_u(dag.get_op(next)) {
+            Some((theta2, phi2, lambda2, _)) => {
+                unitary::u_matrix(theta2, phi2, lambda2).mul(&unitary::u_matrix(theta, phi, lambda))
+            }
+            None => return false,
+        };
+        let (theta, phi, lambda) = unitary::euler_angles(&matrix);
+        dag.substitute(
+            next,
+            vec![Operation::Gate {
+                name: String::from("U"),
+                params: vec![theta, phi, lambda],
+                qubits: vec![qubit],
+            }],
+        );
+        dag.remove(id);
+        true
+    }
+}
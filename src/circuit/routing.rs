@@ -0,0 +1,531 @@
+use std::collections::VecDeque;
+use std::iter::Peekable;
+use std::str::Chars;
+use std::vec::Vec;
+
+use crate::circuit::dag::{Dag, NodeId};
+use crate::circuit::ir::{Circuit, Operation, Register};
+
+use crate::utils::error::{CompoundError, ErrorTy, SimpleError};
+
+const EXTENDED_SET_SIZE: usize = 20;
+const EXTENDED_SET_WEIGHT: f64 = 0.5;
+const DECAY_DELTA: f64 = 0.001;
+const DECAY_RESET: usize = 5;
+const MAX_COUPLING_QUBITS: usize = 4096;
+
+// Edges are undirected: a SWAP is three CX in alternating directions anyway.
+pub struct CouplingMap {
+    neighbours: Vec<Vec<usize>>,
+    distances: Vec<Vec<usize>>,
+}
+
+struct JsonCursor<'a> {
+    chars: Peekable<Chars<'a>>,
+}
+
+impl<'a> JsonCursor<'a> {
+    fn peek(&mut self) -> Option<char> {
+        while self.chars.next_if(|c| c.is_whitespace()).is_some() {}
+        self.chars.peek().copied()
+    }
+
+    fn eat(&mut self, c: char) -> Option<()> {
+        if self.peek()? != c {
+            return None;
+        }
+        self.chars.next();
+        Some(())
+    }
+
+    fn number(&mut self) -> Option<usize> {
+        self.peek()?;
+        let mut text = String::new();
+        while let Some(c) = self.chars.next_if(|c| c.is_ascii_digit()) {
+            text.push(c);
+        }
+        text.parse::<usize>().ok()
+    }
+
+    fn edges(&mut self) -> Option<Vec<(usize, usize)>> {
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        self.eat('[')?;
+        if self.eat(']').is_some() {
+            return Some(edges);
+        }
+        loop {
+            self.eat('[')?;
+            let a = self.number()?;
+            self.eat(',')?;
+            let b = self.number()?;
+            self.eat(']')?;
+            edges.push((a, b));
+            if self.eat(']').is_some() {
+                break;
+            }
+            self.eat(',')?;
+        }
+        match self.peek() {
+            Some(_) => None,
+            None => Some(edges),
+        }
+    }
+}
+
+fn parse_edge(line: &str) -> Option<(usize, usize)> {
+    let mut numbers = line
+        .split(|c: char| c.is_whitespace() || c == ',' || c == '-')
+        .filter(|it| !it.is_empty())
+        .map(|it| it.parse::<usize>().ok());
+    match (numbers.next(), numbers.next(), numbers.next()) {
+        (Some(a), Some(b), None) => Some((a?, b?)),
+        _ => None,
+    }
+}
+
+impl CouplingMap {
+    // The distance matrix is dense, so the map is checked to be connected and
+    // of a reasonable size before it is allocated.
+    pub fn new(edges: Vec<(usize, usize)>) -> Result<CouplingMap, SimpleError> {
+        let size = edges.iter().map(|(a, b)| a.max(b) + 1).max().unwrap_or(0);
+        if size > edges.len() + 1 {
+            return Err(not_connected());
+        }
+        if size > MAX_COUPLING_QUBITS {
+            return Err(SimpleError::new(
+                ErrorTy::Route,
+                format!(
+                    "invalid coupling map: more than {} qubits",
+                    MAX_COUPLING_QUBITS
+                ),
+            ));
+        }
+        let mut neighbours: Vec<Vec<usize>> = vec![Vec::new(); size];
+        for (a, b) in edges {
+            if a != b && !neighbours[a].contains(&b) {
+                neighbours[a].push(b);
+                neighbours[b].push(a);
+            }
+        }
+        neighbours.iter_mut().for_each(|it| it.sort());
+
+        let mut reached = vec![false; size];
+        let mut queue: VecDeque<usize> = VecDeque::new();
+        if size > 0 {
+            reached[0] = true;
+            queue.push_back(0);
+        }
+        while let Some(current) = queue.pop_front() {
+            for next in neighbours[current].iter() {
+                if !reached[*next] {
+                    reached[*next] = true;
+                    queue.push_back(*next);
+                }
+            }
+        }
+        if reached.contains(&false) {
+            return Err(not_connected());
+        }
+
+        let mut distances: Vec<Vec<usize>> = vec![vec![usize::MAX; size]; size];
+        for (source, row) in distances.iter_mut().enumerate() {
+            let mut queue = VecDeque::from([source]);
+            row[source] = 0;
+            while let Some(current) = queue.pop_front() {
+                for next in neighbours[current].iter() {
+                    if row[*next] == usize::MAX {
+                        row[*next] = row[current] + 1;
+                        queue.push_back(*next);
+                    }
+                }
+            }
+        }
+
+        Ok(CouplingMap {
+            neighbours,
+            distances,
+        })
+    }
+
+    // Either a JSON list of pairs, e.g. `[[0, 1], [1, 2]]`, or one pair per
+    // line such as `0 1`, `0,1` or `0-1`, with `#` starting a comment.
+    pub fn parse(text: &str) -> Result<CouplingMap, SimpleError> {
+        if text.trim_start().starts_with('[') {
+            let mut cursor = JsonCursor {
+                chars: text.chars().peekable(),
+            };
+            return match cursor.edges() {
+                Some(edges) => CouplingMap::new(edges),
+                None => Err(SimpleError::new(
+                    ErrorTy::Route,
+                    "invalid coupling map: expected a JSON list of qubit pairs",
+                )),
+            };
+        }
+
+        let mut edges: Vec<(usize, usize)> = Vec::new();
+        for (number, line) in text.lines().enumerate() {
+            let line = line.split('#').next().unwrap_or("").trim();
+            if line.is_empty() {
+                continue;
+            }
+            match parse_edge(line) {
+                Some(edge) => edges.push(edge),
+                None => {
+                    return Err(SimpleError::new(
+                        ErrorTy::Route,
+                        format!("invalid coupling map: bad edge on line {}", number + 1),
+                    ));
+                }
+            }
+        }
+        CouplingMap::new(edges)
+    }
+
+    pub fn get_num_qubits(&self) -> usize {
+        self.neighbours.len()
+    }
+
+    pub fn get_neighbours(&self, qubit: usize) -> &Vec<usize> {
+        &self.neighbours[qubit]
+    }
+
+    pub fn get_distance(&self, a: usize, b: usize) -> Option<usize> {
+        match self.distances[a][b] {
+            usize::MAX => None,
+            distance => Some(distance),
+        }
+    }
+
+    pub fn is_connected(&self, a: usize, b: usize) -> bool {
+        self.distances[a][b] == 1
+    }
+}
+
+fn not_connected() -> SimpleError {
+    SimpleError::new(ErrorTy::Route, "the coupling map is not connected")
+}
+
+// Layouts map each logical qubit to the physical qubit holding it.
+pub struct Routing {
+    pub circuit: Circuit,
+    pub initial_layout: Vec<usize>,
+    pub final_layout: Vec<usize>,
+}
+
+pub struct Router {
+    coupling: CouplingMap,
+    error: CompoundError,
+}
+
+struct Layout {
+    physical: Vec<usize>,
+    logical: Vec<usize>,
+}
+
+impl Layout {
+    fn new(physical: Vec<usize>) -> Layout {
+        let mut logical = vec![0; physical.len()];
+        for (qubit, at) in physical.iter().enumerate() {
+            logical[*at] = qubit;
+        }
+        Layout { physical, logical }
+    }
+
+    fn swap(&mut self, a: usize, b: usize) {
+        self.logical.swap(a, b);
+        self.physical[self.logical[a]] = a;
+        self.physical[self.logical[b]] = b;
+    }
+}
+
+fn map_op(op: &Operation, layout: &Layout) -> Operation {
+    let map = |qubit: &usize| layout.physical[*qubit];
+    match op {
+        Operation::Gate {
+            name,
+            params,
+            qubits,
+        } => Operation::Gate {
+            name: name.clone(),
+            params: params.clone(),
+            qubits: qubits.iter().map(map).collect(),
+        },
+        Operation::Measure { qubit, clbit } => Operation::Measure {
+            qubit: map(qubit),
+            clbit: *clbit,
+        },
+        Operation::Reset { qubit } => Operation::Reset { qubit: map(qubit) },
+        Operation::Barrier { qubits } => Operation::Barrier {
+            qubits: qubits.iter().map(map).collect(),
+        },
+        Operation::Conditional { creg, value, op } => Operation::Conditional {
+            creg: *creg,
+            value: *value,
+            op: Box::new(map_op(op, layout)),
+        },
+    }
+}
+
+fn cx(control: usize, target: usize) -> Operation {
+    Operation::Gate {
+        name: String::from("CX"),
+        params: Vec::new(),
+        qubits: vec![control, target],
+    }
+}
+
+fn two_qubits(op: &Operation) -> Option<(usize, usize)> {
+    match op {
+        Operation::Barrier { .. } => None,
+        Operation::Conditional { op, .. } => two_qubits(op),
+        op => match op.get_qubits()[..] {
+            [a, b] => Some((a, b)),
+            _ => None,
+        },
+    }
+}
+
+impl Router {
+    pub fn new(coupling: CouplingMap) -> Router {
+        Router {
+            coupling,
+            error: CompoundError::new(),
+        }
+    }
+
+    pub fn route(&mut self, dag: &Dag) -> Option<Routing> {
+        let circuit = dag.get_circuit();
+        let size = self.coupling.get_num_qubits();
+        if circuit.num_qubits() > size {
+            self.error.add(SimpleError::new(
+                ErrorTy::Route,
+                format!(
+                    "the circuit needs {} qubits but the coupling map has {}",
+                    circuit.num_qubits(),
+                    size
+                ),
+            ));
+            return None;
+        }
+        for id in dag.node_ids() {
+            let op = dag.get_op(id).unwrap();
+            if let Some((a, b)) = two_qubits(op)
+                && a == b
+            {
+                self.error.add(SimpleError::new(
+                    ErrorTy::Route,
+                    format!("cannot route an operation using qubit {} twice", a),
+                ));
+                return None;
+            }
+            if two_qubits(op).is_none()
+                && op.get_qubits().len() > 2
+                && !matches!(op, Operation::Barrier { .. })
+            {
+                self.error.add(SimpleError::new(
+                    ErrorTy::Route,
+                    format!(
+                        "cannot route an operation on {} qubits",
+                        op.get_qubits().len()
+                    ),
+                ));
+                return None;
+            }
+        }
+
+        // As in SABRE, the initial layout comes from routing the circuit
+        // forwards from the trivial layout, then backwards from where it ended.
+        let mut reversed = dag.to_circuit();
+        reversed.ops.reverse();
+        let routed = self
+            .run(dag, Layout::new((0..size).collect()))
+            .and_then(|(_, layout)| self.run(&Dag::new(reversed), layout))
+            .and_then(|(_, layout)| {
+                let initial_layout = layout.physical.clone();
+                self.run(dag, layout)
+                    .map(|(ops, layout)| (initial_layout, ops, layout))
+            });
+        let (initial_layout, ops, layout) = match routed {
+            Some(routed) => routed,
+            None => {
+                self.error.add(SimpleError::new(
+                    ErrorTy::Route,
+                    "cannot find a swap bringing the qubits of a gate closer",
+                ));
+                return None;
+            }
+        };
+
+        let mut name = String::from("q");
+        while circuit.cregs.iter().any(|reg| reg.name == name) {
+            name.push('_');
+        }
+        Some(Routing {
+            circuit: Circuit {
+                version: circuit.version.clone(),
+                includes: circuit.includes.clone(),
+                pragmas: circuit.pragmas.clone(),
+                qregs: vec![Register {
+                    name,
+                    offset: 0,
+                    size,
+                }],
+                cregs: circuit.cregs.clone(),
+                gates: circuit.gates.clone(),
+                ops,
+            },
+            initial_layout: initial_layout[..circuit.num_qubits()].to_vec(),
+            final_layout: layout.physical[..circuit.num_qubits()].to_vec(),
+        })
+    }
+
+    fn run(&self, dag: &Dag, mut layout: Layout) -> Option<(Vec<Operation>, Layout)> {
+        let ids = dag.node_ids();
+        let mut pending: Vec<usize> = vec![0; ids.last().map_or(0, |id| id + 1)];
+        for id in ids.iter() {
+            pending[*id] = dag.predecessors(*id).len();
+        }
+        let mut front = dag.front_layer();
+        let mut decay: Vec<f64> = vec![1.0; layout.physical.len()];
+        let mut swaps = 0;
+        let mut ops: Vec<Operation> = Vec::new();
+
+        while !front.is_empty() {
+            let (ready, blocked): (Vec<NodeId>, Vec<NodeId>) =
+                front
+                    .iter()
+                    .partition(|id| match two_qubits(dag.get_op(**id).unwrap()) {
+                        Some((a, b)) => self
+                            .coupling
+                            .is_connected(layout.physical[a], layout.physical[b]),
+                        None => true,
+                    });
+            if !ready.is_empty() {
+                front = blocked;
+                for id in ready {
+                    ops.push(map_op(dag.get_op(id).unwrap(), &layout));
+                    for next in dag.successors(id) {
+                        pending[next] -= 1;
+                        if pending[next] == 0 {
+                            front.push(next);
+                        }
+                    }
+                }
+                decay.iter_mut().for_each(|it| *it = 1.0);
+                swaps = 0;
+                continue;
+            }
+
+            // Heuristic swaps can go round in circles; past a bound, bring
+            // the qubits of the first blocked gate closer to each other.
+            let (a, b) = if swaps > 2 * layout.physical.len() {
+                self.closer(dag, &front, &layout)?
+            } else {
+                self.best_swap(dag, &front, &layout, &decay)?
+            };
+            ops.push(cx(a, b));
+            ops.push(cx(b, a));
+            ops.push(cx(a, b));
+            layout.swap(a, b);
+            decay[a] += DECAY_DELTA;
+            decay[b] += DECAY_DELTA;
+            swaps += 1;
+            if swaps % DECAY_RESET == 0 {
+                decay.iter_mut().for_each(|it| *it = 1.0);
+            }
+        }
+        Some((ops, layout))
+    }
+
+    fn extended_set(&self, dag: &Dag, front: &[NodeId]) -> Vec<(usize, usize)> {
+        let mut set: Vec<(usize, usize)> = Vec::new();
+        let mut seen: Vec<NodeId> = front.to_vec();
+        let mut queue: VecDeque<NodeId> = front.iter().copied().collect();
+        while let Some(id) = queue.pop_front() {
+            for next in dag.successors(id) {
+                if set.len() >= EXTENDED_SET_SIZE {
+                    return set;
+                }
+                if seen.contains(&next) {
+                    continue;
+                }
+                seen.push(next);
+                if let Some(pair) = two_qubits(dag.get_op(next).unwrap()) {
+                    set.push(pair);
+                }
+                queue.push_back(next);
+            }
+        }
+        set
+    }
+
+    fn best_swap(
+        &self,
+        dag: &Dag,
+        front: &[NodeId],
+        layout: &Layout,
+        decay: &[f64],
+    ) -> Option<(usize, usize)> {
+        let gates: Vec<(usize, usize)> = front
+            .iter()
+            .filter_map(|id| two_qubits(dag.get_op(*id).unwrap()))
+            .collect();
+        let extended = self.extended_set(dag, front);
+
+        let mut candidates: Vec<(usize, usize)> = Vec::new();
+        for (a, b) in gates.iter() {
+            for at in [layout.physical[*a], layout.physical[*b]] {
+                for next in self.coupling.get_neighbours(at) {
+                    let edge = (at.min(*next), at.max(*next));
+                    if !candidates.contains(&edge) {
+                        candidates.push(edge);
+                    }
+                }
+            }
+        }
+
+        let mut best: Option<((usize, usize), f64)> = None;
+        for (a, b) in candidates {
+            let at = |qubit: &usize| match layout.physical[*qubit] {
+                it if it == a => b,
+                it if it == b => a,
+                it => it,
+            };
+            let cost = |pairs: &Vec<(usize, usize)>| {
+                pairs
+                    .iter()
+                    .map(|(x, y)| self.coupling.distances[at(x)][at(y)] as f64)
+                    .sum::<f64>()
+                    / pairs.len().max(1) as f64
+            };
+            let score =
+                decay[a].max(decay[b]) * (cost(&gates) + EXTENDED_SET_WEIGHT * cost(&extended));
+            if best.is_none_or(|(_, it)| score < it) {
+                best = Some(((a, b), score));
+            }
+        }
+        best.map(|(swap, _)| swap)
+    }
+
+    fn closer(&self, dag: &Dag, front: &[NodeId], layout: &Layout) -> Option<(usize, usize)> {
+        let (a, b) = front
+            .iter()
+            .find_map(|id| two_qubits(dag.get_op(*id).unwrap()))?;
+        let (a, b) = (layout.physical[a], layout.physical[b]);
+        let next = self
+            .coupling
+            .get_neighbours(a)
+            .iter()
+            .find(|next| self.coupling.distances[**next][b] < self.coupling.distances[a][b])?;
+        Some((a, *next))
+    }
+
+    pub fn get_error(&self) -> &CompoundError {
+        &self.error
+    }
+
+    pub fn get_error_mut(&mut self) -> &mut CompoundError {
+        &mut self.error
+    }
+}
@@ -0,0 +1,222 @@
+use std::f64::consts::PI;
+
+const EPSILON: f64 = 1e-9;
+
+pub struct Complex {
+    pub re: f64,
+    pub im: f64,
+}
+
+impl Clone for Complex {
+    fn clone(&self) -> Complex {
+        *self
+    }
+}
+
+impl Copy for Complex {}
+
+impl Complex {
+    pub fn new(re: f64, im: f64) -> Complex {
+        Complex { re, im }
+    }
+
+    pub fn from_polar(r: f64, arg: f64) -> Complex {
+        Complex::new(r * arg.cos(), r * arg.sin())
+    }
+
+    pub fn add(&self, other: &Complex) -> Complex {
+        Complex::new(self.re + other.re, self.im + other.im)
+    }
+
+    pub fn sub(&self, other: &Complex) -> Complex {
+        Complex::new(self.re - other.re, self.im - other.im)
+    }
+
+    pub fn mul(&self, other: &Complex) -> Complex {
+        Complex::new(
+            self.re * other.re - self.im * other.im,
+            self.re * other.im + self.im * other.re,
+        )
+    }
+
+    pub fn neg(&self) -> Complex {
+        Complex::new(-self.re, -self.im)
+    }
+
+    pub fn norm(&self) -> f64 {
+        self.re.hypot(self.im)
+    }
+
+    pub fn arg(&self) -> f64 {
+        self.im.atan2(self.re)
+    }
+}
+
+// A square matrix stored row by row.
+pub struct Matrix {
+    size: usize,
+    data: Vec<Complex>,
+}
+
+impl Clone for Matrix {
+    fn clone(&self) -> Matrix {
+        Matrix {
+            size: self.size,
+            data: self.data.clone(),
+        }
+    }
+}
+
+impl Matrix {
+    pub fn identity(size: usize) -> Matrix {
+        let mut data = vec![Complex::new(0.0, 0.0); size * size];
+        for i in 0..size {
+            data[i * size + i] = Complex::new(1.0, 0.0);
+        }
+        Matrix { size, data }
+    }
+
+    pub fn from_rows(rows: Vec<Vec<Complex>>) -> Matrix {
+        Matrix {
+            size: rows.len(),
+            data: rows.into_iter().flatten().collect(),
+        }
+    }
+
+    pub fn get_size(&self) -> usize {
+        self.size
+    }
+
+    pub fn get(&self, row: usize, column: usize) -> Complex {
+        self.data[row * self.size + column]
+    }
+
+    pub fn mul(&self, other: &Matrix) -> Matrix {
+        let size = self.size;
+        let mut data = vec![Complex::new(0.0, 0.0); size * size];
+        for row in 0..size {
+            for column in 0..size {
+                let mut sum = Complex::new(0.0, 0.0);
+                for k in 0..size {
+                    sum = sum.add(&self.get(row, k).mul(&other.get(k, column)));
+                }
+                data[row * size + column] = sum;
+            }
+        }
+        Matrix { size, data }
+    }
+
+    // Applies a gate on some of the qubits of a register, qubit 0 being the
+    // least significant bit of a basis state index.
+    pub fn apply(&self, gate: &Matrix, qubits: &[usize]) -> Matrix {
+        let size = self.size;
+        let mut data = vec![Complex::new(0.0, 0.0); size * size];
+        for column in 0..size {
+            for state in 0..size {
+                let amplitude = self.get(state, column);
+                if amplitude.norm() == 0.0 {
+                    continue;
+                }
+                let local = qubits
+                    .iter()
+                    .enumerate()
+                    .fold(0, |acc, (i, qubit)| acc | (((state >> qubit) & 1) << i));
+                for out in 0..gate.size {
+                    let mut target = state;
+                    for (i, qubit) in qubits.iter().enumerate() {
+                        target = (target & !(1 << qubit)) | (((out >> i) & 1) << qubit);
+                    }
+                    let value = gate.get(out, local).mul(&amplitude);
+                    data[target * size + column] = data[target * size + column].add(&value);
+                }
+            }
+        }
+        Matrix { size, data }
+    }
+
+    // Whether both matrices are equal up to a global phase.
+    pub fn equivalent(&self, other: &Matrix) -> bool {
+        if self.size != other.size {
+            return false;
+        }
+        let (index, _) = match other
+            .data
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| a.norm().total_cmp(&b.norm()))
+        {
+            Some(it) => it,
+            None => return true,
+        };
+        let (a, b) = (self.data[index], other.data[index]);
+        if a.norm() < EPSILON {
+            return false;
+        }
+        let phase = Complex::from_polar(1.0, b.arg() - a.arg());
+        self.data
+            .iter()
+            .zip(other.data.iter())
+            .all(|(a, b)| a.mul(&phase).sub(b).norm() < 1e-6)
+    }
+}
+
+pub fn u_matrix(theta: f64, phi: f64, lambda: f64) -> Matrix {
+    let (cos, sin) = ((theta / 2.0).cos(), (theta / 2.0).sin());
+    Matrix::from_rows(vec![
+        vec![
+            Complex::new(cos, 0.0),
+            Complex::from_polar(sin, lambda).neg(),
+        ],
+        vec![
+            Complex::from_polar(sin, phi),
+            Complex::from_polar(cos, phi + lambda),
+        ],
+    ])
+}
+
+// Control on qubit 0 (the least significant bit), target on qubit 1.
+pub fn cx_matrix() -> Matrix {
+    let zero = Complex::new(0.0, 0.0);
+    let one = Complex::new(1.0, 0.0);
+    Matrix::from_rows(vec![
+        vec![one, zero, zero, zero],
+        vec![zero, zero, zero, one],
+        vec![zero, zero, one, zero],
+        vec![zero, one, zero, zero],
+    ])
+}
+
+pub fn normalize_angle(angle: f64) -> f64 {
+    let angle = angle.rem_euclid(2.0 * PI);
+    let angle = if angle > PI { angle - 2.0 * PI } else { angle };
+    if angle.abs() < EPSILON {
+        0.0
+    } else if (angle + PI).abs() < EPSILON {
+        PI
+    } else {
+        angle
+    }
+}
+
+// The angles of the U gate equal to a 2x2 unitary up to a global phase.
+pub fn euler_angles(matrix: &Matrix) -> (f64, f64, f64) {
+    let (m00, m01, m10, m11) = (
+        matrix.get(0, 0),
+        matrix.get(0, 1),
+        matrix.get(1, 0),
+        matrix.get(1, 1),
+    );
+    let theta = 2.0 * m10.norm().atan2(m00.norm());
+    let (phi, lambda) = if m10.norm() < EPSILON {
+        (0.0, m11.arg() - m00.arg())
+    } else if m00.norm() < EPSILON {
+        (m10.arg() - m01.neg().arg(), 0.0)
+    } else {
+        (m10.arg() - m00.arg(), m01.neg().arg() - m00.arg())
+    };
+    (
+        normalize_angle(theta),
+        normalize_angle(phi),
+        normalize_angle(lambda),
+    )
+}
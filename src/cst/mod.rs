@@ -0,0 +1,10 @@
+//! A lossless concrete syntax tree, built by `parser::parse_cst`. The typed
+//! views in `view` give the `node` AST over it: every view reads its parts
+//! from the tokens of one syntax node, and `to_node` turns it into the owned
+//! `node` value that binding, checking and the later passes annotate and
+//! rewrite. `parse_cst` returns the program read off the tree this way, so
+//! each of its nodes spans exactly one syntax node (see `SyntaxNode::find`).
+
+pub mod rewrite;
+pub mod syntax;
+pub mod view;
@@ -0,0 +1,51 @@
+use std::vec::Vec;
+
+use crate::cst::syntax::SyntaxNode;
+use crate::cst::view::{Program, Reg, Stmt};
+
+use crate::parse::incremental::TextEdit;
+use crate::parse::token::Token;
+
+fn replace(token: &Token, text: &str) -> Option<TextEdit> {
+    let (start, end) = token.get_loc().get_span()?;
+    Some(TextEdit {
+        start,
+        end,
+        text: String::from(text),
+    })
+}
+
+pub fn rename_register(root: &SyntaxNode, old: &str, new: &str) -> Vec<TextEdit> {
+    let mut edits = Vec::new();
+    let stmts = match Program::cast(root) {
+        Some(program) => program.stmts(),
+        None => return edits,
+    };
+    for node in stmts
+        .iter()
+        .filter(|stmt| !matches!(stmt, Stmt::GateDecl(_)))
+        .flat_map(|stmt| stmt.get_syntax().descendants())
+    {
+        let name = match (Stmt::cast(node), Reg::cast(node)) {
+            (Some(Stmt::RegDecl(dec)), _) => dec.get_name(),
+            (_, Some(reg)) => reg.get_name(),
+            _ => None,
+        };
+        if let Some(token) = name
+            && token.get_text() == old
+            && let Some(edit) = replace(token, new)
+        {
+            edits.push(edit);
+        }
+    }
+    edits
+}
+
+pub fn apply_edits(text: &str, mut edits: Vec<TextEdit>) -> String {
+    let mut text = String::from(text);
+    edits.sort_by_key(|edit| std::cmp::Reverse(edit.start));
+    for edit in edits {
+        text.replace_range(edit.start as usize..edit.end as usize, &edit.text);
+    }
+    text
+}
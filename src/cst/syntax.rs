@@ -0,0 +1,257 @@
+use std::fmt;
+use std::vec::Vec;
+
+use crate::parse::token::Token;
+
+use crate::utils::location::Location;
+
+pub enum SyntaxKind {
+    Program,
+    Header,
+    Include,
+    Pragma,
+    RegDecl,
+    GateDecl,
+    GateStmt,
+    MeasureStmt,
+    ResetStmt,
+    BarrierStmt,
+    IfStmt,
+    SimpleReg,
+    SubscriptReg,
+    ExpList,
+    IntExp,
+    RealExp,
+    PiExp,
+    IdExp,
+    ParenExp,
+    BinopExp,
+    UnaryExp,
+}
+
+impl Clone for SyntaxKind {
+    fn clone(&self) -> SyntaxKind {
+        match self {
+            SyntaxKind::Program => SyntaxKind::Program,
+            SyntaxKind::Header => SyntaxKind::Header,
+            SyntaxKind::Include => SyntaxKind::Include,
+            SyntaxKind::Pragma => SyntaxKind::Pragma,
+            SyntaxKind::RegDecl => SyntaxKind::RegDecl,
+            SyntaxKind::GateDecl => SyntaxKind::GateDecl,
+            SyntaxKind::GateStmt => SyntaxKind::GateStmt,
+            SyntaxKind::MeasureStmt => SyntaxKind::MeasureStmt,
+            SyntaxKind::ResetStmt => SyntaxKind::ResetStmt,
+            SyntaxKind::BarrierStmt => SyntaxKind::BarrierStmt,
+            SyntaxKind::IfStmt => SyntaxKind::IfStmt,
+            SyntaxKind::SimpleReg => SyntaxKind::SimpleReg,
+            SyntaxKind::SubscriptReg => SyntaxKind::SubscriptReg,
+            SyntaxKind::ExpList => SyntaxKind::ExpList,
+            SyntaxKind::IntExp => SyntaxKind::IntExp,
+            SyntaxKind::RealExp => SyntaxKind::RealExp,
+            SyntaxKind::PiExp => SyntaxKind::PiExp,
+            SyntaxKind::IdExp => SyntaxKind::IdExp,
+            SyntaxKind::ParenExp => SyntaxKind::ParenExp,
+            SyntaxKind::BinopExp => SyntaxKind::BinopExp,
+            SyntaxKind::UnaryExp => SyntaxKind::UnaryExp,
+        }
+    }
+}
+
+impl fmt::Display for SyntaxKind {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            SyntaxKind::Program => f.write_str("PROGRAM"),
+            SyntaxKind::Header => f.write_str("HEADER"),
+            SyntaxKind::Include => f.write_str("INCLUDE"),
+            SyntaxKind::Pragma => f.write_str("PRAGMA"),
+            SyntaxKind::RegDecl => f.write_str("REG_DECL"),
+            SyntaxKind::GateDecl => f.write_str("GATE_DECL"),
+            SyntaxKind::GateStmt => f.write_str("GATE_STMT"),
+            SyntaxKind::MeasureStmt => f.write_str("MEASURE_STMT"),
+            SyntaxKind::ResetStmt => f.write_str("RESET_STMT"),
+            SyntaxKind::BarrierStmt => f.write_str("BARRIER_STMT"),
+            SyntaxKind::IfStmt => f.write_str("IF_STMT"),
+            SyntaxKind::SimpleReg => f.write_str("SIMPLE_REG"),
+            SyntaxKind::SubscriptReg => f.write_str("SUBSCRIPT_REG"),
+            SyntaxKind::ExpList => f.write_str("EXP_LIST"),
+            SyntaxKind::IntExp => f.write_str("INT_EXP"),
+            SyntaxKind::RealExp => f.write_str("REAL_EXP"),
+            SyntaxKind::PiExp => f.write_str("PI_EXP"),
+            SyntaxKind::IdExp => f.write_str("ID_EXP"),
+            SyntaxKind::ParenExp => f.write_str("PAREN_EXP"),
+            SyntaxKind::BinopExp => f.write_str("BINOP_EXP"),
+            SyntaxKind::UnaryExp => f.write_str("UNARY_EXP"),
+        }
+    }
+}
+
+pub enum SyntaxElement {
+    Node(SyntaxNode),
+    Token(Token),
+}
+
+pub struct SyntaxNode {
+    kind: SyntaxKind,
+    children: Vec<SyntaxElement>,
+}
+
+impl fmt::Display for SyntaxNode {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for token in self.tokens() {
+            for trivia in token.get_leading() {
+                f.write_str(trivia.get_text())?;
+            }
+            f.write_str(token.get_text())?;
+        }
+        Ok(())
+    }
+}
+
+impl SyntaxNode {
+    pub fn new(kind: SyntaxKind, children: Vec<SyntaxElement>) -> SyntaxNode {
+        SyntaxNode { kind, children }
+    }
+
+    pub fn get_kind(&self) -> &SyntaxKind {
+        &self.kind
+    }
+
+    pub fn get_children(&self) -> &Vec<SyntaxElement> {
+        &self.children
+    }
+
+    pub fn nodes(&self) -> impl Iterator<Item = &SyntaxNode> {
+        self.children.iter().filter_map(|child| match child {
+            SyntaxElement::Node(node) => Some(node),
+            SyntaxElement::Token(_) => None,
+        })
+    }
+
+    pub fn tokens(&self) -> Vec<&Token> {
+        let mut tokens = Vec::new();
+        self.collect_tokens(&mut tokens);
+        tokens
+    }
+
+    fn collect_tokens<'a>(&'a self, tokens: &mut Vec<&'a Token>) {
+        for child in self.children.iter() {
+            match child {
+                SyntaxElement::Node(node) => node.collect_tokens(tokens),
+                SyntaxElement::Token(token) => tokens.push(token),
+            }
+        }
+    }
+
+    pub fn descendants(&self) -> Vec<&SyntaxNode> {
+        let mut nodes = vec![self];
+        let mut index = 0;
+        while index < nodes.len() {
+            nodes.extend(nodes[index].nodes());
+            index += 1;
+        }
+        nodes
+    }
+
+    pub fn get_loc(&self) -> Option<Location> {
+        let tokens = self.tokens();
+        let mut loc = tokens.first()?.get_loc().clone();
+        loc.end_to_end(tokens.last()?.get_loc());
+        Some(loc)
+    }
+
+    /// Finds the innermost node spanning exactly the bytes of `loc`, which is
+    /// how an AST node built in the same parse finds its concrete syntax.
+    pub fn find(&self, loc: &Location) -> Option<&SyntaxNode> {
+        let span = loc.get_span()?;
+        self.descendants()
+            .into_iter()
+            .rev()
+            .find(|node| node.get_loc().and_then(|it| it.get_span()) == Some(span))
+    }
+
+    pub fn dump(&self) -> String {
+        let mut output = String::new();
+        self.dump_into(&mut output, 0);
+        output
+    }
+
+    fn dump_into(&self, output: &mut String, depth: usize) {
+        output.push_str(&format!("{}{}\n", "  ".repeat(depth), self.kind));
+        for child in self.children.iter() {
+            match child {
+                SyntaxElement::Node(node) => node.dump_into(output, depth + 1),
+                SyntaxElement::Token(token) => {
+                    for trivia in token.get_leading() {
+                        output.push_str(&format!("{}{}\n", "  ".repeat(depth + 1), trivia));
+                    }
+                    output.push_str(&format!("{}{}\n", "  ".repeat(depth + 1), token));
+                }
+            }
+        }
+    }
+}
+
+pub(crate) struct CstBuilder {
+    enabled: bool,
+    children: Vec<SyntaxElement>,
+    parents: Vec<(SyntaxKind, usize)>,
+}
+
+impl CstBuilder {
+    pub(crate) fn new() -> CstBuilder {
+        CstBuilder {
+            enabled: false,
+            children: Vec::new(),
+            parents: Vec::new(),
+        }
+    }
+
+    pub(crate) fn set_enabled(&mut self, enabled: bool) {
+        self.enabled = enabled;
+    }
+
+    pub(crate) fn is_enabled(&self) -> bool {
+        self.enabled
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.children.clear();
+        self.parents.clear();
+    }
+
+    pub(crate) fn checkpoint(&self) -> usize {
+        self.children.len()
+    }
+
+    pub(crate) fn start_node(&mut self, kind: SyntaxKind) {
+        let checkpoint = self.checkpoint();
+        self.start_node_at(checkpoint, kind);
+    }
+
+    pub(crate) fn start_node_at(&mut self, checkpoint: usize, kind: SyntaxKind) {
+        if self.enabled {
+            self.parents.push((kind, checkpoint));
+        }
+    }
+
+    pub(crate) fn finish_node(&mut self) {
+        if let Some((kind, first)) = self.parents.pop() {
+            let children = self.children.split_off(first);
+            self.children
+                .push(SyntaxElement::Node(SyntaxNode::new(kind, children)));
+        }
+    }
+
+    pub(crate) fn push_token(&mut self, token: Token) {
+        if self.enabled {
+            self.children.push(SyntaxElement::Token(token));
+        }
+    }
+
+    pub(crate) fn finish(&mut self) -> Option<SyntaxNode> {
+        match (self.parents.is_empty(), self.children.pop()) {
+            (true, Some(SyntaxElement::Node(node))) if self.children.is_empty() => Some(node),
+            _ => None,
+        }
+    }
+}
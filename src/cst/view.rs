@@ -0,0 +1,593 @@
+use std::vec::Vec;
+
+use crate::ast::factory;
+use crate::ast::node;
+
+use crate::cst::syntax::{SyntaxElement, SyntaxKind, SyntaxNode};
+
+use crate::parse::keywords;
+use crate::parse::token::{Token, TokenTy};
+
+fn child_token(node: &SyntaxNode, accept: fn(&TokenTy) -> bool) -> Option<&Token> {
+    node.get_children().iter().find_map(|child| match child {
+        SyntaxElement::Token(token) if accept(token.get_ty()) => Some(token),
+        _ => None,
+    })
+}
+
+fn is_id(ty: &TokenTy) -> bool {
+    matches!(ty, TokenTy::Id(_))
+}
+
+fn is_integer(ty: &TokenTy) -> bool {
+    matches!(ty, TokenTy::Integer(_))
+}
+
+fn child_tokens(node: &SyntaxNode) -> impl Iterator<Item = &Token> {
+    node.get_children().iter().filter_map(|child| match child {
+        SyntaxElement::Token(token) => Some(token),
+        SyntaxElement::Node(_) => None,
+    })
+}
+
+fn id(token: &Token) -> Option<String> {
+    match token.get_ty() {
+        TokenTy::Id(name) => Some(name.clone()),
+        _ => None,
+    }
+}
+
+fn integer(token: &Token) -> Option<u64> {
+    match token.get_ty() {
+        TokenTy::Integer(value) => Some(*value),
+        _ => None,
+    }
+}
+
+pub struct Program<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> Program<'a> {
+    pub fn cast(node: &'a SyntaxNode) -> Option<Program<'a>> {
+        match node.get_kind() {
+            SyntaxKind::Program => Some(Program { node }),
+            _ => None,
+        }
+    }
+
+    pub fn get_syntax(&self) -> &'a SyntaxNode {
+        self.node
+    }
+
+    pub fn get_header(&self) -> Option<Header<'a>> {
+        self.node.nodes().find_map(Header::cast)
+    }
+
+    pub fn includes(&self) -> Vec<Include<'a>> {
+        self.node.nodes().filter_map(Include::cast).collect()
+    }
+
+    pub fn pragmas(&self) -> Vec<Pragma<'a>> {
+        self.node.nodes().filter_map(Pragma::cast).collect()
+    }
+
+    pub fn stmts(&self) -> Vec<Stmt<'a>> {
+        self.node.nodes().filter_map(Stmt::cast).collect()
+    }
+
+    pub fn to_node(&self) -> Option<node::Program> {
+        let header = self.get_header()?;
+        let mut includes: Vec<node::Include> = Vec::new();
+        let mut pragmas: Vec<node::Pragma> = Vec::new();
+        let mut stmts: Vec<node::Stmt> = Vec::new();
+        for child in self.node.nodes() {
+            if let Some(include) = Include::cast(child) {
+                includes.push(include.to_node(stmts.len())?);
+            } else if let Some(pragma) = Pragma::cast(child) {
+                pragmas.push(pragma.to_node(stmts.len())?);
+            } else if let Some(stmt) = Stmt::cast(child) {
+                stmts.push(stmt.to_node()?);
+            }
+        }
+        Some(factory::make_program(
+            header.node.get_loc()?,
+            header.to_node()?,
+            includes,
+            pragmas,
+            stmts,
+        ))
+    }
+}
+
+pub struct Header<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> Header<'a> {
+    pub fn cast(node: &'a SyntaxNode) -> Option<Header<'a>> {
+        match node.get_kind() {
+            SyntaxKind::Header => Some(Header { node }),
+            _ => None,
+        }
+    }
+
+    pub fn get_version(&self) -> Option<&'a Token> {
+        child_token(self.node, |ty| {
+            matches!(ty, TokenTy::Real(_) | TokenTy::Integer(_))
+        })
+    }
+
+    pub fn to_node(&self) -> Option<node::Version> {
+        let (major, minor) = keywords::parse_version(self.get_version()?.get_text())?;
+        Some(node::Version { major, minor })
+    }
+}
+
+pub struct Include<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> Include<'a> {
+    pub fn cast(node: &'a SyntaxNode) -> Option<Include<'a>> {
+        match node.get_kind() {
+            SyntaxKind::Include => Some(Include { node }),
+            _ => None,
+        }
+    }
+
+    pub fn get_path(&self) -> Option<&'a Token> {
+        child_token(self.node, |ty| matches!(ty, TokenTy::Str(_)))
+    }
+
+    /// `index` is the number of statements before the include.
+    pub fn to_node(&self, index: usize) -> Option<node::Include> {
+        let path = match self.get_path()?.get_ty() {
+            TokenTy::Str(path) => path.clone(),
+            _ => return None,
+        };
+        Some(factory::make_include(self.node.get_loc()?, path, index))
+    }
+}
+
+pub struct Pragma<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> Pragma<'a> {
+    pub fn cast(node: &'a SyntaxNode) -> Option<Pragma<'a>> {
+        match node.get_kind() {
+            SyntaxKind::Pragma => Some(Pragma { node }),
+            _ => None,
+        }
+    }
+
+    pub fn get_pragma(&self) -> Option<&'a Token> {
+        child_token(self.node, |ty| matches!(ty, TokenTy::Pragma(_)))
+    }
+
+    /// `index` is the number of statements before the pragma.
+    pub fn to_node(&self, index: usize) -> Option<node::Pragma> {
+        let text = match self.get_pragma()?.get_ty() {
+            TokenTy::Pragma(text) => text.clone(),
+            _ => return None,
+        };
+        Some(factory::make_pragma(self.node.get_loc()?, text, index))
+    }
+}
+
+pub enum Stmt<'a> {
+    RegDecl(RegDecl<'a>),
+    GateDecl(GateDecl<'a>),
+    GateStmt(GateStmt<'a>),
+    MeasureStmt(MeasureStmt<'a>),
+    ResetStmt(ResetStmt<'a>),
+    BarrierStmt(BarrierStmt<'a>),
+    IfStmt(IfStmt<'a>),
+}
+
+impl<'a> Stmt<'a> {
+    pub fn cast(node: &'a SyntaxNode) -> Option<Stmt<'a>> {
+        match node.get_kind() {
+            SyntaxKind::RegDecl => Some(Stmt::RegDecl(RegDecl { node })),
+            SyntaxKind::GateDecl => Some(Stmt::GateDecl(GateDecl { node })),
+            SyntaxKind::GateStmt => Some(Stmt::GateStmt(GateStmt { node })),
+            SyntaxKind::MeasureStmt => Some(Stmt::MeasureStmt(MeasureStmt { node })),
+            SyntaxKind::ResetStmt => Some(Stmt::ResetStmt(ResetStmt { node })),
+            SyntaxKind::BarrierStmt => Some(Stmt::BarrierStmt(BarrierStmt { node })),
+            SyntaxKind::IfStmt => Some(Stmt::IfStmt(IfStmt { node })),
+            _ => None,
+        }
+    }
+
+    pub fn get_syntax(&self) -> &'a SyntaxNode {
+        match self {
+            Stmt::RegDecl(it) => it.node,
+            Stmt::GateDecl(it) => it.node,
+            Stmt::GateStmt(it) => it.node,
+            Stmt::MeasureStmt(it) => it.node,
+            Stmt::ResetStmt(it) => it.node,
+            Stmt::BarrierStmt(it) => it.node,
+            Stmt::IfStmt(it) => it.node,
+        }
+    }
+
+    pub fn to_node(&self) -> Option<node::Stmt> {
+        match self {
+            Stmt::RegDecl(it) => it.to_node(),
+            Stmt::GateDecl(it) => it.to_node(),
+            Stmt::GateStmt(it) => it.to_node(),
+            Stmt::MeasureStmt(it) => it.to_node(),
+            Stmt::ResetStmt(it) => it.to_node(),
+            Stmt::BarrierStmt(it) => it.to_node(),
+            Stmt::IfStmt(it) => it.to_node(),
+        }
+    }
+}
+
+pub struct RegDecl<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> RegDecl<'a> {
+    pub fn get_keyword(&self) -> Option<&'a Token> {
+        child_token(self.node, |ty| matches!(ty, TokenTy::QReg | TokenTy::CReg))
+    }
+
+    pub fn get_name(&self) -> Option<&'a Token> {
+        child_token(self.node, is_id)
+    }
+
+    pub fn get_size(&self) -> Option<&'a Token> {
+        child_token(self.node, is_integer)
+    }
+
+    pub fn to_node(&self) -> Option<node::Stmt> {
+        let ty = match self.get_keyword()?.get_ty() {
+            TokenTy::QReg => node::RegTy::QReg,
+            TokenTy::CReg => node::RegTy::CReg,
+            _ => return None,
+        };
+        Some(factory::make_reg_dec(
+            self.node.get_loc()?,
+            id(self.get_name()?)?,
+            ty,
+            integer(self.get_size()?)?,
+        ))
+    }
+}
+
+pub struct GateDecl<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> GateDecl<'a> {
+    pub fn get_keyword(&self) -> Option<&'a Token> {
+        child_token(self.node, |ty| {
+            matches!(ty, TokenTy::Gate | TokenTy::Opaque)
+        })
+    }
+
+    pub fn get_name(&self) -> Option<&'a Token> {
+        child_token(self.node, is_id)
+    }
+
+    pub fn is_opaque(&self) -> bool {
+        self.get_keyword()
+            .is_some_and(|token| matches!(token.get_ty(), TokenTy::Opaque))
+    }
+
+    /// The parameter names, written between the parentheses of the signature.
+    pub fn pars(&self) -> Vec<&'a Token> {
+        child_tokens(self.node)
+            .skip_while(|token| !matches!(token.get_ty(), TokenTy::LPar))
+            .take_while(|token| !matches!(token.get_ty(), TokenTy::RPar))
+            .filter(|token| is_id(token.get_ty()))
+            .collect()
+    }
+
+    /// The qubit argument names, written after the parameters.
+    pub fn args(&self) -> Vec<&'a Token> {
+        let mut tokens = child_tokens(self.node).skip(2).peekable();
+        if tokens
+            .next_if(|token| matches!(token.get_ty(), TokenTy::LPar))
+            .is_some()
+        {
+            tokens.find(|token| matches!(token.get_ty(), TokenTy::RPar));
+        }
+        tokens
+            .take_while(|token| !matches!(token.get_ty(), TokenTy::LBrace | TokenTy::Semi))
+            .filter(|token| is_id(token.get_ty()))
+            .collect()
+    }
+
+    pub fn body(&self) -> Vec<Stmt<'a>> {
+        self.node.nodes().filter_map(Stmt::cast).collect()
+    }
+
+    pub fn to_node(&self) -> Option<node::Stmt> {
+        let ids = |tokens: Vec<&Token>| tokens.into_iter().map(id).collect::<Option<Vec<_>>>();
+        let body = match self.is_opaque() {
+            true => None,
+            false => Some(
+                self.body()
+                    .iter()
+                    .map(Stmt::to_node)
+                    .collect::<Option<Vec<_>>>()?,
+            ),
+        };
+        Some(factory::make_gate_dec(
+            self.node.get_loc()?,
+            id(self.get_name()?)?,
+            ids(self.pars())?,
+            ids(self.args())?,
+            body,
+        ))
+    }
+}
+
+pub struct GateStmt<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> GateStmt<'a> {
+    pub fn get_gate(&self) -> Option<&'a Token> {
+        child_token(self.node, |ty| {
+            matches!(ty, TokenTy::UGate | TokenTy::CXGate | TokenTy::Id(_))
+        })
+    }
+
+    pub fn pars(&self) -> Vec<Exp<'a>> {
+        match self
+            .node
+            .nodes()
+            .find(|node| matches!(node.get_kind(), SyntaxKind::ExpList))
+        {
+            Some(list) => list.nodes().filter_map(Exp::cast).collect(),
+            None => Vec::new(),
+        }
+    }
+
+    pub fn args(&self) -> Vec<Reg<'a>> {
+        self.node.nodes().filter_map(Reg::cast).collect()
+    }
+
+    pub fn to_node(&self) -> Option<node::Stmt> {
+        let gate = match self.get_gate()?.get_ty() {
+            TokenTy::UGate => String::from("U"),
+            TokenTy::CXGate => String::from("CX"),
+            TokenTy::Id(name) => name.clone(),
+            _ => return None,
+        };
+        Some(factory::make_gate_stmt(
+            self.node.get_loc()?,
+            gate,
+            self.pars()
+                .iter()
+                .map(Exp::to_node)
+                .collect::<Option<_>>()?,
+            self.args()
+                .iter()
+                .map(Reg::to_node)
+                .collect::<Option<_>>()?,
+        ))
+    }
+}
+
+pub struct MeasureStmt<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> MeasureStmt<'a> {
+    pub fn get_src(&self) -> Option<Reg<'a>> {
+        self.node.nodes().filter_map(Reg::cast).next()
+    }
+
+    pub fn get_dst(&self) -> Option<Reg<'a>> {
+        self.node.nodes().filter_map(Reg::cast).nth(1)
+    }
+
+    pub fn to_node(&self) -> Option<node::Stmt> {
+        Some(factory::make_measure_stmt(
+            self.node.get_loc()?,
+            self.get_src()?.to_node()?,
+            self.get_dst()?.to_node()?,
+        ))
+    }
+}
+
+pub struct ResetStmt<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> ResetStmt<'a> {
+    pub fn get_reg(&self) -> Option<Reg<'a>> {
+        self.node.nodes().filter_map(Reg::cast).next()
+    }
+
+    pub fn to_node(&self) -> Option<node::Stmt> {
+        Some(factory::make_reset_stmt(
+            self.node.get_loc()?,
+            self.get_reg()?.to_node()?,
+        ))
+    }
+}
+
+pub struct BarrierStmt<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> BarrierStmt<'a> {
+    pub fn args(&self) -> Vec<Reg<'a>> {
+        self.node.nodes().filter_map(Reg::cast).collect()
+    }
+
+    pub fn to_node(&self) -> Option<node::Stmt> {
+        Some(factory::make_barrier_stmt(
+            self.node.get_loc()?,
+            self.args()
+                .iter()
+                .map(Reg::to_node)
+                .collect::<Option<_>>()?,
+        ))
+    }
+}
+
+pub struct IfStmt<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> IfStmt<'a> {
+    pub fn get_reg(&self) -> Option<Reg<'a>> {
+        self.node.nodes().filter_map(Reg::cast).next()
+    }
+
+    pub fn get_value(&self) -> Option<&'a Token> {
+        child_token(self.node, is_integer)
+    }
+
+    pub fn get_stmt(&self) -> Option<Stmt<'a>> {
+        self.node.nodes().filter_map(Stmt::cast).next()
+    }
+
+    pub fn to_node(&self) -> Option<node::Stmt> {
+        Some(factory::make_if_stmt(
+            self.node.get_loc()?,
+            self.get_reg()?.to_node()?,
+            integer(self.get_value()?)?,
+            self.get_stmt()?.to_node()?,
+        ))
+    }
+}
+
+pub struct Reg<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> Reg<'a> {
+    pub fn cast(node: &'a SyntaxNode) -> Option<Reg<'a>> {
+        match node.get_kind() {
+            SyntaxKind::SimpleReg | SyntaxKind::SubscriptReg => Some(Reg { node }),
+            _ => None,
+        }
+    }
+
+    pub fn get_syntax(&self) -> &'a SyntaxNode {
+        self.node
+    }
+
+    pub fn get_name(&self) -> Option<&'a Token> {
+        child_token(self.node, is_id)
+    }
+
+    pub fn get_index(&self) -> Option<&'a Token> {
+        child_token(self.node, is_integer)
+    }
+
+    pub fn to_node(&self) -> Option<node::Reg> {
+        let loc = self.node.get_loc()?;
+        let name = id(self.get_name()?)?;
+        match self.node.get_kind() {
+            SyntaxKind::SubscriptReg => Some(factory::make_subscript_reg(
+                loc,
+                name,
+                integer(self.get_index()?)?,
+            )),
+            _ => Some(factory::make_simple_reg(loc, name)),
+        }
+    }
+}
+
+pub struct Exp<'a> {
+    node: &'a SyntaxNode,
+}
+
+impl<'a> Exp<'a> {
+    pub fn cast(node: &'a SyntaxNode) -> Option<Exp<'a>> {
+        match node.get_kind() {
+            SyntaxKind::IntExp
+            | SyntaxKind::RealExp
+            | SyntaxKind::PiExp
+            | SyntaxKind::IdExp
+            | SyntaxKind::ParenExp
+            | SyntaxKind::BinopExp
+            | SyntaxKind::UnaryExp => Some(Exp { node }),
+            _ => None,
+        }
+    }
+
+    pub fn get_syntax(&self) -> &'a SyntaxNode {
+        self.node
+    }
+
+    /// The literal, name or operator token, which parentheses do not have.
+    pub fn get_token(&self) -> Option<&'a Token> {
+        child_token(self.node, |ty| !matches!(ty, TokenTy::LPar | TokenTy::RPar))
+    }
+
+    pub fn operands(&self) -> Vec<Exp<'a>> {
+        self.node.nodes().filter_map(Exp::cast).collect()
+    }
+
+    /// Parentheses leave no node of their own: the expression inside takes
+    /// their location, as in the parser.
+    pub fn to_node(&self) -> Option<node::Exp> {
+        let loc = self.node.get_loc()?;
+        let operands = self.operands();
+        if let SyntaxKind::ParenExp = self.node.get_kind() {
+            return Some(factory::make_delimited_exp(
+                loc,
+                operands.first()?.to_node()?,
+            ));
+        }
+        let token = self.get_token()?;
+        match (self.node.get_kind(), token.get_ty()) {
+            (SyntaxKind::IntExp, TokenTy::Integer(value)) => Some(factory::make_int_exp(
+                loc,
+                *value,
+                token.get_text().to_string(),
+            )),
+            (SyntaxKind::RealExp, TokenTy::Real(value)) => Some(factory::make_real_exp(
+                loc,
+                *value,
+                token.get_text().to_string(),
+            )),
+            (SyntaxKind::PiExp, _) => Some(factory::make_pi_exp(loc)),
+            (SyntaxKind::IdExp, TokenTy::Id(name)) => Some(factory::make_id_exp(loc, name.clone())),
+            (SyntaxKind::BinopExp, ty) => {
+                let op = match ty {
+                    TokenTy::Plus => node::BinopTy::Add,
+                    TokenTy::Minus => node::BinopTy::Sub,
+                    TokenTy::Mul => node::BinopTy::Mul,
+                    TokenTy::Div => node::BinopTy::Div,
+                    TokenTy::Pow => node::BinopTy::Pow,
+                    _ => return None,
+                };
+                Some(factory::make_binop_exp(
+                    loc,
+                    operands.first()?.to_node()?,
+                    op,
+                    operands.get(1)?.to_node()?,
+                ))
+            }
+            (SyntaxKind::UnaryExp, ty) => {
+                let op = match ty {
+                    TokenTy::Minus => node::UnaryOp::Minus,
+                    TokenTy::Sin => node::UnaryOp::Sin,
+                    TokenTy::Cos => node::UnaryOp::Cos,
+                    TokenTy::Tan => node::UnaryOp::Tan,
+                    TokenTy::Exp => node::UnaryOp::Exp,
+                    TokenTy::Ln => node::UnaryOp::Ln,
+                    TokenTy::Sqrt => node::UnaryOp::Sqrt,
+                    _ => return None,
+                };
+                Some(factory::make_unary_exp(
+                    loc,
+                    op,
+                    operands.first()?.to_node()?,
+                ))
+            }
+            _ => None,
+        }
+    }
+}
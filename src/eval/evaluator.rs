@@ -0,0 +1,173 @@
+use crate::ast::node;
+use crate::ast::visitor::MutVisitor;
+
+use crate::eval::value::Value;
+
+use crate::utils::error::{CompoundError, ErrorTy, LocatedError};
+use crate::utils::location::Location;
+
+pub struct Evaluator {
+    error: CompoundError,
+}
+
+impl MutVisitor for Evaluator {
+    fn visit_dec(&mut self, _: &mut node::Dec) {}
+
+    fn visit_reg(&mut self, _: &mut node::Reg) {}
+
+    fn visit_stmt(&mut self, e: &mut node::Stmt) {
+        match e {
+            node::Stmt::GateStmt { pars, .. } => {
+                pars.iter_mut().for_each(|par| self.visit_exp(par))
+            }
+            node::Stmt::IfStmt { stmt, .. } => self.visit_stmt(stmt),
+            _ => {}
+        }
+    }
+
+    fn visit_exp(&mut self, e: &mut node::Exp) {
+        if let Some(value) = self.eval(e) {
+            *e = value.to_exp(e.get_loc());
+        }
+    }
+}
+
+impl Default for Evaluator {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Evaluator {
+    pub fn new() -> Evaluator {
+        Evaluator {
+            error: CompoundError::new(),
+        }
+    }
+
+    pub fn evaluate(&mut self, program: &node::Program) {
+        program
+            .stmts
+            .iter()
+            .for_each(|stmt| self.evaluate_stmt(stmt));
+    }
+
+    fn evaluate_stmt(&mut self, stmt: &node::Stmt) {
+        match stmt {
+            node::Stmt::GateStmt { pars, .. } => pars.iter().for_each(|par| {
+                self.eval(par);
+            }),
+            node::Stmt::IfStmt { stmt, .. } => self.evaluate_stmt(stmt),
+            _ => {}
+        }
+    }
+
+    pub fn fold(&mut self, program: &mut node::Program) {
+        program
+            .stmts
+            .iter_mut()
+            .for_each(|stmt| self.visit_stmt(stmt));
+    }
+
+    pub fn eval(&mut self, e: &node::Exp) -> Option<Value> {
+        match e {
+            node::Exp::IntExp { value, .. } => Some(Value::rational(*value as i128, 1)),
+            node::Exp::RealExp { value, .. } => Some(Value::Real(*value)),
+            node::Exp::PiExp { .. } => Some(Value::PiMultiple(1, 1)),
+            node::Exp::IdExp { .. } => None,
+            node::Exp::BinopExp {
+                loc,
+                left,
+                op,
+                right,
+            } => {
+                let left = self.eval(left);
+                let right = self.eval(right);
+                let value = self.eval_binop(&left?, op, &right?, loc)?;
+                self.finite(value, loc)
+            }
+            node::Exp::UnaryExp { loc, op, exp } => {
+                let value = self.eval(exp)?;
+                let value = self.eval_unary(op, &value, loc)?;
+                self.finite(value, loc)
+            }
+        }
+    }
+
+    fn eval_binop(
+        &mut self,
+        left: &Value,
+        op: &node::BinopTy,
+        right: &Value,
+        loc: &Location,
+    ) -> Option<Value> {
+        match op {
+            node::BinopTy::Add => Some(left.add(right)),
+            node::BinopTy::Sub => Some(left.sub(right)),
+            node::BinopTy::Mul => Some(left.mul(right)),
+            node::BinopTy::Div if right.is_zero() => self.division_by_zero(loc),
+            node::BinopTy::Div => Some(left.div(right)),
+            node::BinopTy::Pow if left.is_zero() && right.to_f64() < 0.0 => {
+                self.division_by_zero(loc)
+            }
+            node::BinopTy::Pow => Some(left.pow(right)),
+        }
+    }
+
+    fn eval_unary(&mut self, op: &node::UnaryOp, value: &Value, loc: &Location) -> Option<Value> {
+        match op {
+            node::UnaryOp::Minus => Some(value.neg()),
+            node::UnaryOp::Sin => Some(value.sin()),
+            node::UnaryOp::Cos => Some(value.cos()),
+            node::UnaryOp::Tan => Some(value.tan()),
+            node::UnaryOp::Exp => Some(value.exp()),
+            node::UnaryOp::Ln if value.to_f64() <= 0.0 => {
+                self.error.add(LocatedError::new(
+                    ErrorTy::Eval,
+                    format!("logarithm of non-positive number {}", value),
+                    loc.clone(),
+                ));
+                None
+            }
+            node::UnaryOp::Ln => Some(value.ln()),
+            node::UnaryOp::Sqrt if value.to_f64() < 0.0 => {
+                self.error.add(LocatedError::new(
+                    ErrorTy::Eval,
+                    format!("square root of negative number {}", value),
+                    loc.clone(),
+                ));
+                None
+            }
+            node::UnaryOp::Sqrt => Some(value.sqrt()),
+        }
+    }
+
+    fn finite(&mut self, value: Value, loc: &Location) -> Option<Value> {
+        if value.to_f64().is_finite() {
+            return Some(value);
+        }
+        self.error.add(LocatedError::new(
+            ErrorTy::Eval,
+            "result is not a finite number",
+            loc.clone(),
+        ));
+        None
+    }
+
+    fn division_by_zero(&mut self, loc: &Location) -> Option<Value> {
+        self.error.add(LocatedError::new(
+            ErrorTy::Eval,
+            "division by zero",
+            loc.clone(),
+        ));
+        None
+    }
+
+    pub fn get_error(&self) -> &CompoundError {
+        &self.error
+    }
+
+    pub fn get_error_mut(&mut self) -> &mut CompoundError {
+        &mut self.error
+    }
+}
@@ -0,0 +1,291 @@
+use std::f64::consts::PI;
+use std::fmt;
+
+use crate::ast::builder::real_text;
+use crate::ast::{factory, node};
+
+use crate::utils::location::Location;
+
+const MAX_EXACT_EXPONENT: i64 = 64;
+const MAX_PI_DENOMINATOR: i128 = 64;
+
+pub enum Value {
+    Rational(i64, i64),
+    PiMultiple(i64, i64),
+    Real(f64),
+}
+
+impl Clone for Value {
+    fn clone(&self) -> Value {
+        match self {
+            Value::Rational(num, den) => Value::Rational(*num, *den),
+            Value::PiMultiple(num, den) => Value::PiMultiple(*num, *den),
+            Value::Real(value) => Value::Real(*value),
+        }
+    }
+}
+
+impl fmt::Display for Value {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Value::Rational(num, 1) => write!(f, "{}", num),
+            Value::Rational(num, den) => write!(f, "{}/{}", num, den),
+            Value::PiMultiple(num, den) => {
+                match num {
+                    1 => f.write_str("pi")?,
+                    -1 => f.write_str("-pi")?,
+                    _ => write!(f, "{}*pi", num)?,
+                }
+                if *den != 1 {
+                    write!(f, "/{}", den)?;
+                }
+                Ok(())
+            }
+            Value::Real(value) => f.write_str(&real_text(*value)),
+        }
+    }
+}
+
+fn gcd(a: i128, b: i128) -> i128 {
+    let (mut a, mut b) = (a.abs(), b.abs());
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+fn ratio(num: i128, den: i128) -> Option<(i64, i64)> {
+    if den == 0 {
+        return None;
+    }
+    let sign = if den < 0 { -1 } else { 1 };
+    let divisor = gcd(num, den).max(1);
+    let num = i64::try_from(sign * num / divisor).ok()?;
+    let den = i64::try_from(sign * den / divisor).ok()?;
+    Some((num, den))
+}
+
+fn exact_sqrt(value: i64) -> Option<i64> {
+    let root = (value as f64).sqrt().round() as i64;
+    (root.checked_mul(root)? == value).then_some(root)
+}
+
+impl Value {
+    pub fn rational(num: i128, den: i128) -> Value {
+        match ratio(num, den) {
+            Some((num, den)) => Value::Rational(num, den),
+            None => Value::Real(num as f64 / den as f64),
+        }
+    }
+
+    pub fn pi_multiple(num: i128, den: i128) -> Value {
+        match ratio(num, den) {
+            Some((0, _)) => Value::Rational(0, 1),
+            Some((num, den)) => Value::PiMultiple(num, den),
+            None => Value::Real(num as f64 / den as f64 * PI),
+        }
+    }
+
+    /// Recovers a multiple of pi with a small denominator from a float, which
+    /// is how angles that went through floating point get printed exactly.
+    pub fn from_f64(value: f64) -> Value {
+        if value.is_finite() && value.abs() < 1e9 {
+            for den in 1..=MAX_PI_DENOMINATOR {
+                let num = (value / PI * den as f64).round() as i128;
+                let exact = Value::pi_multiple(num, den);
+                if (exact.to_f64() - value).abs() <= 4.0 * f64::EPSILON * value.abs() {
+                    return exact;
+                }
+            }
+        }
+        Value::Real(value)
+    }
+
+    pub fn to_f64(&self) -> f64 {
+        match self {
+            Value::Rational(num, den) => *num as f64 / *den as f64,
+            Value::PiMultiple(num, den) => *num as f64 / *den as f64 * PI,
+            Value::Real(value) => *value,
+        }
+    }
+
+    pub fn is_exact(&self) -> bool {
+        !matches!(self, Value::Real(_))
+    }
+
+    pub fn is_zero(&self) -> bool {
+        self.to_f64() == 0.0
+    }
+
+    pub fn neg(&self) -> Value {
+        match self {
+            Value::Rational(num, den) => Value::rational(-(*num as i128), *den as i128),
+            Value::PiMultiple(num, den) => Value::pi_multiple(-(*num as i128), *den as i128),
+            Value::Real(value) => Value::Real(-value),
+        }
+    }
+
+    pub fn add(&self, other: &Value) -> Value {
+        match (self, other) {
+            (Value::Rational(a, b), Value::Rational(c, d)) => Value::rational(
+                *a as i128 * *d as i128 + *c as i128 * *b as i128,
+                *b as i128 * *d as i128,
+            ),
+            (Value::PiMultiple(a, b), Value::PiMultiple(c, d)) => Value::pi_multiple(
+                *a as i128 * *d as i128 + *c as i128 * *b as i128,
+                *b as i128 * *d as i128,
+            ),
+            (Value::Rational(0, _), other) | (other, Value::Rational(0, _)) => other.clone(),
+            _ => Value::Real(self.to_f64() + other.to_f64()),
+        }
+    }
+
+    pub fn sub(&self, other: &Value) -> Value {
+        self.add(&other.neg())
+    }
+
+    pub fn mul(&self, other: &Value) -> Value {
+        match (self, other) {
+            (Value::Rational(a, b), Value::Rational(c, d)) => {
+                Value::rational(*a as i128 * *c as i128, *b as i128 * *d as i128)
+            }
+            (Value::Rational(a, b), Value::PiMultiple(c, d))
+            | (Value::PiMultiple(a, b), Value::Rational(c, d)) => {
+                Value::pi_multiple(*a as i128 * *c as i128, *b as i128 * *d as i128)
+            }
+            _ => Value::Real(self.to_f64() * other.to_f64()),
+        }
+    }
+
+    pub fn div(&self, other: &Value) -> Value {
+        match (self, other) {
+            (Value::Rational(a, b), Value::Rational(c, d)) => {
+                Value::rational(*a as i128 * *d as i128, *b as i128 * *c as i128)
+            }
+            (Value::PiMultiple(a, b), Value::Rational(c, d)) => {
+                Value::pi_multiple(*a as i128 * *d as i128, *b as i128 * *c as i128)
+            }
+            (Value::PiMultiple(a, b), Value::PiMultiple(c, d)) => {
+                Value::rational(*a as i128 * *d as i128, *b as i128 * *c as i128)
+            }
+            _ => Value::Real(self.to_f64() / other.to_f64()),
+        }
+    }
+
+    pub fn pow(&self, other: &Value) -> Value {
+        match (self, other) {
+            (_, Value::Rational(0, _)) => Value::Rational(1, 1),
+            (_, Value::Rational(1, 1)) => self.clone(),
+            (Value::Rational(num, den), Value::Rational(exp, 1))
+                if exp.unsigned_abs() <= MAX_EXACT_EXPONENT as u64 =>
+            {
+                let exp = exp.unsigned_abs() as u32;
+                match (
+                    (*num as i128).checked_pow(exp),
+                    (*den as i128).checked_pow(exp),
+                    other.to_f64() < 0.0,
+                ) {
+                    (Some(num), Some(den), false) => Value::rational(num, den),
+                    (Some(num), Some(den), true) => Value::rational(den, num),
+                    _ => Value::Real(self.to_f64().powf(other.to_f64())),
+                }
+            }
+            _ => Value::Real(self.to_f64().powf(other.to_f64())),
+        }
+    }
+
+    pub fn sin(&self) -> Value {
+        match self {
+            Value::Rational(0, _) => Value::Rational(0, 1),
+            Value::PiMultiple(_, 1) => Value::Rational(0, 1),
+            Value::PiMultiple(num, 2) => {
+                Value::Rational(if num.rem_euclid(4) == 1 { 1 } else { -1 }, 1)
+            }
+            _ => Value::Real(self.to_f64().sin()),
+        }
+    }
+
+    pub fn cos(&self) -> Value {
+        match self {
+            Value::Rational(0, _) => Value::Rational(1, 1),
+            Value::PiMultiple(num, 1) => {
+                Value::Rational(if num.rem_euclid(2) == 0 { 1 } else { -1 }, 1)
+            }
+            Value::PiMultiple(_, 2) => Value::Rational(0, 1),
+            _ => Value::Real(self.to_f64().cos()),
+        }
+    }
+
+    pub fn tan(&self) -> Value {
+        match self {
+            Value::Rational(0, _) | Value::PiMultiple(_, 1) => Value::Rational(0, 1),
+            _ => Value::Real(self.to_f64().tan()),
+        }
+    }
+
+    pub fn exp(&self) -> Value {
+        match self {
+            Value::Rational(0, _) => Value::Rational(1, 1),
+            _ => Value::Real(self.to_f64().exp()),
+        }
+    }
+
+    pub fn ln(&self) -> Value {
+        match self {
+            Value::Rational(1, 1) => Value::Rational(0, 1),
+            _ => Value::Real(self.to_f64().ln()),
+        }
+    }
+
+    pub fn sqrt(&self) -> Value {
+        match self {
+            Value::Rational(num, den) => match (exact_sqrt(*num), exact_sqrt(*den)) {
+                (Some(num), Some(den)) => Value::Rational(num, den),
+                _ => Value::Real(self.to_f64().sqrt()),
+            },
+            _ => Value::Real(self.to_f64().sqrt()),
+        }
+    }
+
+    pub fn to_exp(&self, loc: &Location) -> node::Exp {
+        let int = |value: i64| {
+            let exp = factory::make_int_exp(
+                loc.clone(),
+                value.unsigned_abs(),
+                value.unsigned_abs().to_string(),
+            );
+            if value < 0 {
+                factory::make_unary_exp(loc.clone(), node::UnaryOp::Minus, exp)
+            } else {
+                exp
+            }
+        };
+        let fraction = |exp: node::Exp, den: i64| {
+            if den == 1 {
+                exp
+            } else {
+                factory::make_binop_exp(loc.clone(), exp, node::BinopTy::Div, int(den))
+            }
+        };
+        match self {
+            Value::Rational(num, den) => fraction(int(*num), *den),
+            Value::PiMultiple(num, den) => {
+                let pi = factory::make_pi_exp(loc.clone());
+                let exp = match num {
+                    1 => pi,
+                    -1 => factory::make_unary_exp(loc.clone(), node::UnaryOp::Minus, pi),
+                    _ => factory::make_binop_exp(loc.clone(), int(*num), node::BinopTy::Mul, pi),
+                };
+                fraction(exp, *den)
+            }
+            Value::Real(value) => {
+                let exp = factory::make_real_exp(loc.clone(), value.abs(), real_text(value.abs()));
+                if *value < 0.0 {
+                    factory::make_unary_exp(loc.clone(), node::UnaryOp::Minus, exp)
+                } else {
+                    exp
+                }
+            }
+        }
+    }
+}
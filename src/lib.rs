@@ -0,0 +1,10 @@
+pub mod analysis;
+pub mod ast;
+pub mod bind;
+pub mod circuit;
+pub mod cst;
+pub mod eval;
+pub mod parse;
+pub mod transform;
+pub mod typing;
+pub mod utils;
@@ -1,43 +1,161 @@
-pub mod ast;
-pub mod bind;
-pub mod parse;
-pub mod typing;
-pub mod utils;
-
+use std::env;
+use std::fs;
 use std::process;
 
-use crate::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::analysis::stats::StatsCollector;
 
-use crate::bind::binder::Binder;
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
 
-use crate::parse::lexer::Lexer;
-use crate::parse::parser::Parser;
+use openqasm_parser::bind::binder::Binder;
 
-use crate::typing::type_checker::TypeChecker;
+use openqasm_parser::circuit::basis::{Basis, Translator};
+use openqasm_parser::circuit::dag::Dag;
+use openqasm_parser::circuit::lower::Lowerer;
+use openqasm_parser::circuit::optimizer::Optimizer;
+use openqasm_parser::circuit::routing::{CouplingMap, Router};
 
-use crate::utils::error::{CompoundError, Error};
+use openqasm_parser::eval::evaluator::Evaluator;
 
-fn main() {
-    let lexer = Lexer::new(String::from("<stdin>"), std::io::stdin());
-    let mut parser = Parser::new(lexer);
-    let mut binder = Binder::new();
-    let mut type_checker = TypeChecker::new();
+use openqasm_parser::parse::parser;
 
-    if let Some(program) = &mut parser.parse_input() {
-        binder.bind(program);
-        type_checker.check_types(program);
+use openqasm_parser::transform::inliner::Inliner;
 
-        PrettyPrinter.pretty_print(program);
-    }
+use openqasm_parser::typing::type_checker::TypeChecker;
 
-    let mut error = CompoundError::new();
-    error.consume(parser.get_error_mut());
-    error.consume(binder.get_error_mut());
-    error.consume(type_checker.get_error_mut());
+use openqasm_parser::utils::error::{CompoundError, Error, ErrorTy, SimpleError};
 
+fn exit(error: CompoundError) -> ! {
     if !error.empty() {
         eprintln!("{}", error.get_desc());
     }
 
     process::exit(error.get_exit_code());
 }
+
+fn format_layout(name: &str, layout: &[usize]) -> String {
+    let layout = layout
+        .iter()
+        .map(|qubit| qubit.to_string())
+        .collect::<Vec<String>>()
+        .join(" ");
+    format!("{} {}", name, layout)
+}
+
+fn main() {
+    let mut error = CompoundError::new();
+
+    let mut inline = false;
+    let mut stats: Option<&str> = None;
+    let mut optimize: Option<u32> = None;
+    let mut coupling: Option<CouplingMap> = None;
+    let mut basis: Option<Basis> = None;
+    for arg in env::args().skip(1) {
+        match arg.as_str() {
+            "--inline" => inline = true,
+            "--optimize" => optimize = Some(2),
+            _ if arg.starts_with("--optimize=") && arg[11..].parse::<u32>().is_ok() => {
+                optimize = arg[11..].parse::<u32>().ok()
+            }
+            _ if arg.starts_with("--coupling-map=") => match fs::read_to_string(&arg[15..]) {
+                Ok(text) => match CouplingMap::parse(&text) {
+                    Ok(it) => coupling = Some(it),
+                    Err(it) => error.add(it),
+                },
+                Err(_) => error.add(SimpleError::new(
+                    ErrorTy::Route,
+                    format!("cannot read coupling map '{}'", &arg[15..]),
+                )),
+            },
+            _ if arg.starts_with("--basis=") => match Basis::parse(&arg[8..]) {
+                Ok(it) => basis = Some(it),
+                Err(it) => error.add(it),
+            },
+            "--stats" | "--stats=text" => stats = Some("text"),
+            "--stats=json" => stats = Some("json"),
+            _ => error.add(SimpleError::new(
+                ErrorTy::Internal,
+                format!("unknown option '{}'", arg),
+            )),
+        }
+    }
+    if !error.empty() {
+        exit(error);
+    }
+
+    let mut binder = Binder::new();
+    let mut type_checker = TypeChecker::new();
+    let mut evaluator = Evaluator::new();
+    let mut inliner = Inliner::new();
+
+    match &mut parser::parse_stdin() {
+        Ok(program) => {
+            binder.bind(program);
+            type_checker.check_types(program);
+            evaluator.evaluate(program);
+
+            error.consume(binder.get_error_mut());
+            error.consume(type_checker.get_error_mut());
+            error.consume(evaluator.get_error_mut());
+
+            let compile = optimize.is_some() || coupling.is_some() || basis.is_some();
+            if (inline || compile) && error.empty() {
+                inliner.inline(program);
+                error.consume(inliner.get_error_mut());
+            }
+
+            if compile && error.empty() {
+                let mut lowerer = Lowerer::new();
+                if let Some(circuit) = lowerer.lower(program) {
+                    let mut dag = Dag::new(circuit);
+                    if let Some(level) = optimize {
+                        Optimizer::new(level).optimize(&mut dag);
+                    }
+                    let circuit = match coupling.take() {
+                        Some(coupling) => {
+                            let mut router = Router::new(coupling);
+                            let circuit = router.route(&dag).map(|routing| {
+                                let mut circuit = routing.circuit;
+                                circuit
+                                    .pragmas
+                                    .push(format_layout("initial_layout", &routing.initial_layout));
+                                circuit
+                                    .pragmas
+                                    .push(format_layout("final_layout", &routing.final_layout));
+                                circuit
+                            });
+                            error.consume(router.get_error_mut());
+                            circuit
+                        }
+                        None => Some(dag.to_circuit()),
+                    };
+                    let circuit = match (circuit, basis.take()) {
+                        (Some(circuit), Some(basis)) => {
+                            let mut translator = Translator::new(basis);
+                            let circuit = translator.translate(&circuit);
+                            error.consume(translator.get_error_mut());
+                            circuit
+                        }
+                        (circuit, _) => circuit,
+                    };
+                    if let Some(circuit) = circuit {
+                        *program = circuit.to_program();
+                        if stats.is_some() {
+                            Binder::new().bind(program);
+                        }
+                    }
+                }
+                error.consume(lowerer.get_error_mut());
+            }
+
+            match stats {
+                Some(_) if !error.empty() => {}
+                Some("json") => print!("{}", StatsCollector::new().collect(program).to_json()),
+                Some(_) => print!("{}", StatsCollector::new().collect(program).to_text()),
+                None => print!("{}", PrettyPrinter::new().pretty_print(program)),
+            }
+        }
+        Err(parse_error) => error.consume(parse_error),
+    }
+
+    exit(error);
+}
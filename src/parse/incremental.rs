@@ -0,0 +1,359 @@
+use std::ops::Range;
+
+use crate::ast::node;
+use crate::ast::visitor::MutVisitor;
+
+use crate::parse::keywords::Dialect;
+use crate::parse::lexer::Lexer;
+use crate::parse::parser::Parser;
+
+use crate::utils::error::{CompoundError, ErrorTy, SimpleError};
+use crate::utils::location::Location;
+use crate::utils::source_map::FileId;
+
+pub struct TextEdit {
+    pub start: u64,
+    pub end: u64,
+    pub text: String,
+}
+
+pub struct Document {
+    file: FileId,
+    text: String,
+    program: Option<node::Program>,
+    error: CompoundError,
+}
+
+struct Shift {
+    line: u64,
+    offset_delta: i64,
+    line_delta: i64,
+    column_delta: i64,
+}
+
+impl Shift {
+    fn shift(&self, loc: &mut Location) {
+        loc.shift(
+            self.line,
+            self.offset_delta,
+            self.line_delta,
+            self.column_delta,
+        );
+    }
+}
+
+impl MutVisitor for Shift {
+    fn visit_dec(&mut self, e: &mut node::Dec) {
+        match e {
+            node::Dec::RegDec { loc, .. } => self.shift(loc),
+            node::Dec::GateDec { loc, body, .. } => {
+                self.shift(loc);
+                if let Some(body) = body {
+                    body.iter_mut().for_each(|stmt| self.visit_stmt(stmt));
+                }
+            }
+        }
+    }
+
+    fn visit_reg(&mut self, e: &mut node::Reg) {
+        match e {
+            node::Reg::SimpleReg { loc, .. } => self.shift(loc),
+            node::Reg::SubscriptReg { loc, .. } => self.shift(loc),
+        }
+    }
+
+    fn visit_stmt(&mut self, e: &mut node::Stmt) {
+        match e {
+            node::Stmt::DecStmt(dec) => self.visit_dec(dec),
+            node::Stmt::GateStmt {
+                loc, pars, args, ..
+            } => {
+                self.shift(loc);
+                pars.iter_mut().for_each(|par| self.visit_exp(par));
+                args.iter_mut().for_each(|arg| self.visit_reg(arg));
+            }
+            node::Stmt::MeasureStmt { loc, src, dst } => {
+                self.shift(loc);
+                self.visit_reg(src);
+                self.visit_reg(dst);
+            }
+            node::Stmt::ResetStmt { loc, reg } => {
+                self.shift(loc);
+                self.visit_reg(reg);
+            }
+            node::Stmt::BarrierStmt { loc, args } => {
+                self.shift(loc);
+                args.iter_mut().for_each(|arg| self.visit_reg(arg));
+            }
+            node::Stmt::IfStmt { loc, reg, stmt, .. } => {
+                self.shift(loc);
+                self.visit_reg(reg);
+                self.visit_stmt(stmt);
+            }
+        }
+    }
+
+    fn visit_exp(&mut self, e: &mut node::Exp) {
+        match e {
+            node::Exp::IntExp { loc, .. } => self.shift(loc),
+            node::Exp::RealExp { loc, .. } => self.shift(loc),
+            node::Exp::PiExp { loc } => self.shift(loc),
+            node::Exp::IdExp { loc, .. } => self.shift(loc),
+            node::Exp::BinopExp {
+                loc, left, right, ..
+            } => {
+                self.shift(loc);
+                self.visit_exp(left);
+                self.visit_exp(right);
+            }
+            node::Exp::UnaryExp { loc, exp, .. } => {
+                self.shift(loc);
+                self.visit_exp(exp);
+            }
+        }
+    }
+}
+
+fn span(loc: &Location) -> (u64, u64) {
+    loc.get_span().unwrap_or((0, 0))
+}
+
+fn overlaps(loc: &Location, start: u64, end: u64) -> bool {
+    let (s, e) = span(loc);
+    s < end && e > start
+}
+
+fn line_start(text: &[u8], offset: usize) -> usize {
+    let mut offset = offset;
+    while offset > 0 && text[offset - 1] != b'\n' && text[offset - 1] != b'\r' {
+        offset -= 1;
+    }
+    offset
+}
+
+fn line_end(text: &[u8], offset: usize) -> usize {
+    let mut offset = offset;
+    while offset < text.len() && text[offset] != b'\n' && text[offset] != b'\r' {
+        offset += 1;
+    }
+    if text[offset..].starts_with(b"\r\n") {
+        offset + 2
+    } else {
+        (offset + 1).min(text.len())
+    }
+}
+
+fn has_block_delimiter(text: &[u8]) -> bool {
+    text.windows(2).any(|it| it == b"/*" || it == b"*/")
+}
+
+// Whether `offset` may be inside a block comment. A `/*` inside a line
+// comment also counts, which only costs a full parse.
+fn in_block_comment(text: &[u8], offset: usize) -> bool {
+    let before = &text[..offset];
+    let last = |delimiter: &[u8]| before.windows(2).rposition(|it| it == delimiter);
+    last(b"/*") > last(b"*/")
+}
+
+fn position(text: &str, offset: usize) -> (u64, u64) {
+    let mut line = 0;
+    let mut column = 0;
+    let mut chars = text[..offset].chars().peekable();
+    while let Some(c) = chars.next() {
+        match c {
+            '\r' if chars.peek() == Some(&'\n') => {}
+            '\n' | '\r' => {
+                line += 1;
+                column = 0;
+            }
+            _ => column += 1,
+        }
+    }
+    (line, column)
+}
+
+impl Document {
+    pub fn new(name: String, text: String) -> Document {
+        let file = FileId::new(name);
+        file.append(text.as_bytes());
+        let mut document = Document {
+            file,
+            text,
+            program: None,
+            error: CompoundError::new(),
+        };
+        document.reparse();
+        document
+    }
+
+    /// Applies `edit` and returns the range of `stmts` that was reparsed, or
+    /// `None` if the whole document had to be parsed again. Fails, leaving the
+    /// document untouched, if the edit does not select a range of the text
+    /// that starts and ends on character boundaries.
+    pub fn apply(&mut self, edit: TextEdit) -> Result<Option<Range<usize>>, SimpleError> {
+        if edit.start > edit.end
+            || !self.text.is_char_boundary(edit.start as usize)
+            || !self.text.is_char_boundary(edit.end as usize)
+        {
+            return Err(SimpleError::new(
+                ErrorTy::Internal,
+                format!(
+                    "invalid edit range {}..{} in a text of {} bytes",
+                    edit.start,
+                    edit.end,
+                    self.text.len()
+                ),
+            ));
+        }
+
+        let old_text = self.text.clone();
+        self.text
+            .replace_range(edit.start as usize..edit.end as usize, &edit.text);
+        self.file
+            .replace(edit.start, edit.end, edit.text.as_bytes());
+
+        let reparsed = self.reparse_incremental(&old_text, &edit);
+        if reparsed.is_none() {
+            self.reparse();
+        }
+        Ok(reparsed)
+    }
+
+    fn reparse(&mut self) {
+        let start = Location::new_point(self.file.clone(), 0, 0, 0);
+        let mut parser = match Lexer::resume(&start, self.text.as_bytes()) {
+            Some(lexer) => Parser::new(lexer),
+            None => return,
+        };
+        self.program = parser.parse_input();
+        self.error = CompoundError::new();
+        self.error.consume(parser.get_error_mut());
+    }
+
+    fn reparse_incremental(&mut self, old_text: &str, edit: &TextEdit) -> Option<Range<usize>> {
+        let program = self.program.as_mut()?;
+        let old_bytes = old_text.as_bytes();
+
+        let mut start = line_start(old_bytes, edit.start as usize) as u64;
+        let mut end = match edit.end.checked_sub(1).map(|last| old_bytes[last as usize]) {
+            Some(b'\n') if edit.end > edit.start => edit.end,
+            _ => line_end(old_bytes, edit.end as usize) as u64,
+        };
+        let first = program
+            .stmts
+            .iter()
+            .position(|stmt| span(stmt.get_loc()).1 > start)
+            .unwrap_or(program.stmts.len());
+        let last = program
+            .stmts
+            .iter()
+            .rposition(|stmt| span(stmt.get_loc()).0 < end)
+            .map_or(first, |last| (last + 1).max(first));
+        if let Some(stmt) = program.stmts[first..last].first() {
+            start = start.min(span(stmt.get_loc()).0);
+        }
+        if let Some(stmt) = program.stmts[first..last].last() {
+            end = end.max(span(stmt.get_loc()).1);
+        }
+
+        if overlaps(&program.loc, start, end)
+            || span(&program.loc).1 > start
+            || program
+                .includes
+                .iter()
+                .any(|it| overlaps(it.get_loc(), start, end))
+            || program
+                .pragmas
+                .iter()
+                .any(|it| overlaps(it.get_loc(), start, end))
+        {
+            return None;
+        }
+
+        let offset_delta = edit.text.len() as i64 - (edit.end - edit.start) as i64;
+        let new_end = end.checked_add_signed(offset_delta)? as usize;
+        // Statements only know their own spans, so edits that open, close or
+        // fall inside a block comment are left to a full parse.
+        if in_block_comment(old_bytes, start as usize)
+            || has_block_delimiter(&old_bytes[start as usize..end as usize])
+            || has_block_delimiter(&self.text.as_bytes()[start as usize..new_end])
+        {
+            return None;
+        }
+        let (line, column) = position(&self.text, start as usize);
+        let begin = Location::new_point(self.file.clone(), line, column, start);
+        let mut lexer = Lexer::resume(&begin, &self.text.as_bytes()[start as usize..new_end])?;
+        lexer.set_dialect(Dialect::from_version(
+            program.version.major,
+            program.version.minor,
+        )?);
+        let mut parser = Parser::new(lexer);
+        let stmts = parser.parse_stmts_input();
+        if !parser.get_error().empty() {
+            return None;
+        }
+        let stmts = stmts?;
+        if stmts.is_empty()
+            && program.stmts.len() == last - first
+            && program.includes.is_empty()
+            && program.pragmas.is_empty()
+        {
+            return None;
+        }
+
+        let (old_line, old_column) = position(old_text, end as usize);
+        let (new_line, new_column) = position(&self.text, new_end);
+        let mut shift = Shift {
+            line: old_line,
+            offset_delta,
+            line_delta: new_line as i64 - old_line as i64,
+            column_delta: new_column as i64 - old_column as i64,
+        };
+        program
+            .stmts
+            .iter_mut()
+            .skip(last)
+            .for_each(|stmt| shift.visit_stmt(stmt));
+        let count = stmts.len();
+        for include in program.includes.iter_mut() {
+            if span(&include.loc).0 >= end {
+                shift.shift(&mut include.loc);
+                include.index = include.index + count - (last - first);
+            }
+        }
+        for pragma in program.pragmas.iter_mut() {
+            if span(&pragma.loc).0 >= end {
+                shift.shift(&mut pragma.loc);
+                pragma.index = pragma.index + count - (last - first);
+            }
+        }
+
+        program.stmts.splice(first..last, stmts);
+        self.error = CompoundError::new();
+        Some(first..first + count)
+    }
+
+    pub fn get_text(&self) -> &str {
+        &self.text
+    }
+
+    pub fn get_file(&self) -> &FileId {
+        &self.file
+    }
+
+    pub fn get_program(&self) -> Option<&node::Program> {
+        self.program.as_ref()
+    }
+
+    pub fn get_program_mut(&mut self) -> Option<&mut node::Program> {
+        self.program.as_mut()
+    }
+
+    pub fn get_error(&self) -> &CompoundError {
+        &self.error
+    }
+
+    pub fn get_error_mut(&mut self) -> &mut CompoundError {
+        &mut self.error
+    }
+}
@@ -0,0 +1,124 @@
+use crate::parse::token::TokenTy;
+
+pub enum Dialect {
+    OpenQASM2,
+    OpenQASM3,
+}
+
+impl Dialect {
+    pub fn from_version(major: u32, minor: u32) -> Option<Dialect> {
+        match (major, minor) {
+            (2, 0) => Some(Dialect::OpenQASM2),
+            (3, _) => Some(Dialect::OpenQASM3),
+            _ => None,
+        }
+    }
+}
+
+impl Clone for Dialect {
+    fn clone(&self) -> Dialect {
+        match self {
+            Dialect::OpenQASM2 => Dialect::OpenQASM2,
+            Dialect::OpenQASM3 => Dialect::OpenQASM3,
+        }
+    }
+}
+
+pub fn parse_version(text: &str) -> Option<(u32, u32)> {
+    match text.split_once('.') {
+        Some((major, "")) => Some((major.parse().ok()?, 0)),
+        Some((major, minor)) => Some((major.parse().ok()?, minor.parse().ok()?)),
+        None => Some((text.parse().ok()?, 0)),
+    }
+}
+
+pub fn lookup(dialect: &Dialect, id: &str) -> Option<TokenTy> {
+    match dialect {
+        Dialect::OpenQASM2 => lookup_openqasm2(id),
+        Dialect::OpenQASM3 => lookup_openqasm3(id),
+    }
+}
+
+fn lookup_common(id: &str) -> Option<TokenTy> {
+    match id {
+        "OPENQASM" => Some(TokenTy::OpenQASM),
+        "include" => Some(TokenTy::Include),
+        "qreg" => Some(TokenTy::QReg),
+        "creg" => Some(TokenTy::CReg),
+        "U" => Some(TokenTy::UGate),
+        "measure" => Some(TokenTy::Measure),
+        "reset" => Some(TokenTy::Reset),
+        "gate" => Some(TokenTy::Gate),
+        "opaque" => Some(TokenTy::Opaque),
+        "barrier" => Some(TokenTy::Barrier),
+        "if" => Some(TokenTy::If),
+        "pi" => Some(TokenTy::Pi),
+        "sin" => Some(TokenTy::Sin),
+        "cos" => Some(TokenTy::Cos),
+        "tan" => Some(TokenTy::Tan),
+        "exp" => Some(TokenTy::Exp),
+        "ln" => Some(TokenTy::Ln),
+        "sqrt" => Some(TokenTy::Sqrt),
+        _ => None,
+    }
+}
+
+fn lookup_openqasm2(id: &str) -> Option<TokenTy> {
+    match id {
+        "CX" => Some(TokenTy::CXGate),
+        _ => lookup_common(id),
+    }
+}
+
+fn lookup_openqasm3(id: &str) -> Option<TokenTy> {
+    match id {
+        "qubit" => Some(TokenTy::Qubit),
+        "bit" => Some(TokenTy::Bit),
+        "int" => Some(TokenTy::Int),
+        "uint" => Some(TokenTy::UInt),
+        "float" => Some(TokenTy::Float),
+        "angle" => Some(TokenTy::Angle),
+        "bool" => Some(TokenTy::Bool),
+        "complex" => Some(TokenTy::Complex),
+        "duration" => Some(TokenTy::DurationTy),
+        "stretch" => Some(TokenTy::Stretch),
+        "array" => Some(TokenTy::Array),
+        "void" => Some(TokenTy::Void),
+        "const" => Some(TokenTy::Const),
+        "input" => Some(TokenTy::Input),
+        "output" => Some(TokenTy::Output),
+        "let" => Some(TokenTy::Let),
+        "mutable" => Some(TokenTy::Mutable),
+        "readonly" => Some(TokenTy::Readonly),
+        "def" => Some(TokenTy::Def),
+        "defcal" => Some(TokenTy::Defcal),
+        "defcalgrammar" => Some(TokenTy::Defcalgrammar),
+        "cal" => Some(TokenTy::Cal),
+        "extern" => Some(TokenTy::Extern),
+        "return" => Some(TokenTy::Return),
+        "else" => Some(TokenTy::Else),
+        "for" => Some(TokenTy::For),
+        "while" => Some(TokenTy::While),
+        "in" => Some(TokenTy::In),
+        "break" => Some(TokenTy::Break),
+        "continue" => Some(TokenTy::Continue),
+        "end" => Some(TokenTy::End),
+        "switch" => Some(TokenTy::Switch),
+        "case" => Some(TokenTy::Case),
+        "default" => Some(TokenTy::Default),
+        "true" => Some(TokenTy::True),
+        "false" => Some(TokenTy::False),
+        "delay" => Some(TokenTy::Delay),
+        "box" => Some(TokenTy::Box),
+        "durationof" => Some(TokenTy::Durationof),
+        "sizeof" => Some(TokenTy::Sizeof),
+        "ctrl" => Some(TokenTy::Ctrl),
+        "negctrl" => Some(TokenTy::NegCtrl),
+        "inv" => Some(TokenTy::Inv),
+        "pow" => Some(TokenTy::PowModifier),
+        "gphase" => Some(TokenTy::GPhase),
+        "tau" => Some(TokenTy::Tau),
+        "euler" => Some(TokenTy::Euler),
+        _ => lookup_common(id),
+    }
+}
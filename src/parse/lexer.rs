@@ -1,34 +1,127 @@
 use regex::Regex;
 
-use std::io::Read;
+use std::io::{ErrorKind, Read};
+use std::sync::LazyLock;
 
 use crate::utils::chars;
 use crate::utils::error::{CompoundError, ErrorTy, LocatedError, SimpleError};
 use crate::utils::location::Location;
+use crate::utils::source_map::FileId;
 
-use crate::parse::token::{Token, TokenTy};
+use crate::parse::keywords::{self, Dialect};
+use crate::parse::token::{TimeUnit, Token, TokenTy, Trivia, TriviaTy};
+
+const BUFFER_SIZE: usize = 8192;
+const DEFAULT_TAB_WIDTH: u64 = 1;
+
+static INTEGER_RE: LazyLock<Regex> =
+    LazyLock::new(|| Regex::new(r"^([1-9][0-9]*(_[0-9]+)*|0)$").unwrap());
+static RADIX_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(
+        r"^0([xX](?<hex>[0-9a-fA-F]+(_[0-9a-fA-F]+)*)|[oO](?<oct>[0-7]+(_[0-7]+)*)|[bB](?<bin>[01]+(_[01]+)*))$",
+    )
+    .unwrap()
+});
+static BIT_STRING_RE: LazyLock<Regex> = LazyLock::new(|| Regex::new(r"^[01]+(_[01]+)*$").unwrap());
+static REAL_RE: LazyLock<Regex> = LazyLock::new(|| {
+    Regex::new(r"^([0-9]+(_[0-9]+)*\.([0-9]+(_[0-9]+)*)?|([0-9]+(_[0-9]+)*)?\.[0-9]+(_[0-9]+)*)([eE][-+]?[0-9]+(_[0-9]+)*)?$")
+        .unwrap()
+});
 
 pub struct Lexer<Input: Read> {
+    file: FileId,
+    recording: bool,
     input: Input,
+    buffer: Box<[u8; BUFFER_SIZE]>,
+    position: usize,
+    length: usize,
     current: Option<char>,
+    newline: Option<&'static str>,
     location: Location,
+    tab_width: u64,
+    dialect: Dialect,
+    header: bool,
+    text: String,
+    trivia: Vec<Trivia>,
+    lossless: bool,
     token: Option<Token>,
+    finished: bool,
     error: CompoundError,
 }
 
+impl<'a> Lexer<&'a [u8]> {
+    pub fn from_str(name: String, input: &'a str) -> Lexer<&'a [u8]> {
+        Lexer::new(name, input.as_bytes())
+    }
+}
+
 impl<Input: Read> Lexer<Input> {
-    pub fn new(file: String, input: Input) -> Lexer<Input> {
+    pub fn new(name: String, input: Input) -> Lexer<Input> {
+        Lexer::with_file(FileId::new(name), input)
+    }
+
+    pub fn with_file(file: FileId, input: Input) -> Lexer<Input> {
         Lexer {
-            input: input,
+            file: file.clone(),
+            recording: true,
+            input,
+            buffer: Box::new([0; BUFFER_SIZE]),
+            position: 0,
+            length: 0,
             current: None,
-            location: Location::new(file, 0, 0, 0, 0),
+            newline: None,
+            location: Location::new_point(file, 0, 0, 0),
+            tab_width: DEFAULT_TAB_WIDTH,
+            dialect: Dialect::OpenQASM2,
+            header: false,
+            text: String::new(),
+            trivia: Vec::new(),
+            lossless: false,
             token: None,
+            finished: false,
             error: CompoundError::new(),
         }
     }
 
+    pub fn resume(start: &Location, input: Input) -> Option<Lexer<Input>> {
+        let mut lexer = Lexer::with_file(start.get_file()?.clone(), input);
+        lexer.recording = false;
+        lexer.location = start.clone();
+        lexer.location.end_to_start(start);
+        Some(lexer)
+    }
+
+    pub fn set_lossless(&mut self, lossless: bool) {
+        self.lossless = lossless;
+    }
+
+    pub fn set_tab_width(&mut self, tab_width: u64) {
+        self.tab_width = tab_width.max(1);
+    }
+
+    pub fn get_file(&self) -> &FileId {
+        &self.file
+    }
+
+    pub fn set_dialect(&mut self, dialect: Dialect) {
+        self.dialect = dialect;
+    }
+
     fn reset_char(&mut self) {
+        match (self.newline, self.current) {
+            (Some(newline), _) => self.text.push_str(newline),
+            (None, Some(c)) => self.text.push(c),
+            (None, None) => {}
+        }
         self.current = None;
+        self.newline = None;
+    }
+
+    fn push_trivia(&mut self, ty: TriviaTy, location: Location) {
+        let text = std::mem::take(&mut self.text);
+        if self.lossless && !text.is_empty() {
+            self.trivia.push(Trivia::new(ty, text, location));
+        }
     }
 
     fn next_char(&mut self) -> Option<char> {
@@ -38,27 +131,97 @@ impl<Input: Read> Lexer<Input> {
 
         self.location.start_to_end();
 
-        let mut buf = [0];
-        match self.input.read(&mut buf) {
-            Ok(0) => None,
-            Ok(1) => {
-                let c = buf[0] as char;
-                match c {
-                    '\n' => self.location.step_line(),
-                    _ => self.location.step_column(),
-                }
-                self.current = Some(c);
-                self.current
+        let (c, width) = self.decode_char()?;
+        let c = match c {
+            '\r' if self.peek_byte() == Some(b'\n') => {
+                self.next_byte();
+                self.newline = Some("\r\n");
+                self.location.step_line(width + 1);
+                '\n'
             }
-            Ok(2..) => {
-                self.error.add(SimpleError::new(
-                    ErrorTy::Internal,
-                    "read an unexpected number of characters",
-                ));
-                None
+            '\r' => {
+                self.newline = Some("\r");
+                self.location.step_line(width);
+                '\n'
+            }
+            '\n' => {
+                self.location.step_line(width);
+                c
+            }
+            '\t' => {
+                self.location.step_tab(width, self.tab_width);
+                c
             }
-            Err(err) => {
-                self.error.add(SimpleError::new(ErrorTy::Internal, err));
+            _ => {
+                self.location.step_column(width);
+                c
+            }
+        };
+        self.current = Some(c);
+        self.current
+    }
+
+    fn fill_buffer(&mut self) -> bool {
+        if self.position == self.length {
+            self.position = 0;
+            self.length = loop {
+                match self.input.read(self.buffer.as_mut_slice()) {
+                    Ok(n) => {
+                        if self.recording {
+                            self.file.append(&self.buffer[..n]);
+                        }
+                        break n;
+                    }
+                    Err(err) if err.kind() == ErrorKind::Interrupted => continue,
+                    Err(err) => {
+                        self.error.add(SimpleError::new(ErrorTy::Internal, err));
+                        break 0;
+                    }
+                }
+            };
+        }
+        self.length != 0
+    }
+
+    fn peek_byte(&mut self) -> Option<u8> {
+        if !self.fill_buffer() {
+            return None;
+        }
+        Some(self.buffer[self.position])
+    }
+
+    fn next_byte(&mut self) -> Option<u8> {
+        let byte = self.peek_byte()?;
+        self.position += 1;
+        Some(byte)
+    }
+
+    fn decode_char(&mut self) -> Option<(char, u64)> {
+        let first = self.next_byte()?;
+        let width = match first {
+            0x00..=0x7f => return Some((first as char, 1)),
+            0xc2..=0xdf => 2,
+            0xe0..=0xef => 3,
+            0xf0..=0xf4 => 4,
+            _ => 1,
+        };
+        let mut bytes = [first, 0, 0, 0];
+        let mut read = 1;
+        while read < width
+            && let Some(byte) = self.next_byte()
+        {
+            bytes[read] = byte;
+            read += 1;
+        }
+        match std::str::from_utf8(&bytes[..read]) {
+            Ok(s) if read == width => s.chars().next().map(|c| (c, width as u64)),
+            _ => {
+                self.location.step_column(read as u64);
+                self.error.add(LocatedError::new(
+                    ErrorTy::Lex,
+                    "invalid UTF-8 sequence",
+                    self.location.clone(),
+                ));
                 None
             }
         }
@@ -71,7 +234,6 @@ impl<Input: Read> Lexer<Input> {
                 _ => self.reset_char(),
             }
         }
-        self.reset_char();
     }
 
     fn handle_block_comment(&mut self) {
@@ -95,37 +257,18 @@ impl<Input: Read> Lexer<Input> {
         self.reset_char();
     }
 
-    fn process_identifier(&mut self) {
-        let mut location = self.location.clone();
-        let mut id = String::from("");
+    fn read_line(&mut self) -> String {
+        let mut line = String::from("");
         while let Some(c) = self.next_char()
-            && chars::is_id(c)
+            && c != '\n'
         {
-            id += &c.to_string();
+            line += &c.to_string();
             self.reset_char();
         }
-        if !self.error.empty() {
-            return;
-        }
-        let ty = match id.as_str() {
-            "creg" => TokenTy::CReg,
-            "qreg" => TokenTy::QReg,
-            "measure" => TokenTy::Measure,
-            "reset" => TokenTy::Reset,
-            "pi" => TokenTy::Pi,
-            "sin" => TokenTy::Sin,
-            "cos" => TokenTy::Cos,
-            "tan" => TokenTy::Tan,
-            "exp" => TokenTy::Exp,
-            "ln" => TokenTy::Ln,
-            "sqrt" => TokenTy::Sqrt,
-            _ => TokenTy::Id(id),
-        };
-        location.end_to_start(&self.location);
-        self.token = Some(Token::new(ty, location));
+        String::from(line.trim())
     }
 
-    fn process_reserved_identifier(&mut self) {
+    fn process_identifier(&mut self) {
         let mut location = self.location.clone();
         let mut id = String::from("");
         while let Some(c) = self.next_char()
@@ -137,28 +280,20 @@ impl<Input: Read> Lexer<Input> {
         if !self.error.empty() {
             return;
         }
-        if let Some(ty) = match id.as_str() {
-            "OPENQASM" => Some(TokenTy::OpenQASM),
-            "U" => Some(TokenTy::UGate),
-            "CX" => Some(TokenTy::CXGate),
-            _ => {
-                self.error.add(SimpleError::new(
-                    ErrorTy::Lex,
-                    format!("invalid identifier '{}'", id),
-                ));
-                None
-            }
-        } {
-            location.end_to_start(&self.location);
-            self.token = Some(Token::new(ty, location));
-        }
+        let ty = match keywords::lookup(&self.dialect, &id) {
+            Some(ty) => ty,
+            None if id == "pragma" => TokenTy::Pragma(self.read_line()),
+            None => TokenTy::Id(id),
+        };
+        location.end_to_start(&self.location);
+        self.token = Some(Token::new(ty, location));
     }
 
     fn process_number(&mut self) {
         let mut location = self.location.clone();
         let mut number = String::from("");
         while let Some(c) = self.next_char()
-            && chars::is_number(c)
+            && (chars::is_number(c) || c == '_')
         {
             number += &c.to_string();
             self.reset_char();
@@ -166,7 +301,20 @@ impl<Input: Read> Lexer<Input> {
         if !self.error.empty() {
             return;
         }
-        if let Some(c) = self.next_char()
+        if number == "0"
+            && let Some(c) = self.next_char()
+            && chars::is_radix(c)
+        {
+            while let Some(c) = self.next_char()
+                && chars::is_id(c)
+            {
+                number += &c.to_string();
+                self.reset_char();
+            }
+            if !self.error.empty() {
+                return;
+            }
+        } else if let Some(c) = self.next_char()
             && (c == 'e' || c == 'E')
         {
             number += &c.to_string();
@@ -181,7 +329,7 @@ impl<Input: Read> Lexer<Input> {
                 return;
             }
             while let Some(c) = self.next_char()
-                && chars::is_digit(c)
+                && (chars::is_digit(c) || c == '_')
             {
                 number += &c.to_string();
                 self.reset_char();
@@ -190,34 +338,139 @@ impl<Input: Read> Lexer<Input> {
                 return;
             }
         }
-        let integer_re = Regex::new(r"^([1-9]+[0-9]*|0)$").unwrap();
-        let real_re = Regex::new(r"^([0-9]+\.[0-9]*|[0-9]*\.[0-9]+)([eE][-+]?[0-9]+)?$").unwrap();
-        if let Some(ty) = if integer_re.is_match(number.as_str()) {
-            match number.parse::<u32>() {
-                Ok(n) => Some(TokenTy::Integer(n)),
-                Err(err) => {
-                    self.error.add(SimpleError::new(ErrorTy::Lex, err));
-                    None
+        let mut suffix = String::from("");
+        while let Some(c) = self.next_char()
+            && chars::is_unit(c)
+        {
+            suffix += &c.to_string();
+            self.reset_char();
+        }
+        if !self.error.empty() {
+            return;
+        }
+        self.next_char();
+        location.end_to_start(&self.location);
+        let lexeme = format!("{}{}", number, suffix);
+        match Lexer::<Input>::classify_number(&number, &suffix) {
+            Some(Ok(ty)) => self.token = Some(Token::new(ty, location)),
+            Some(Err(_)) => self.error.add(LocatedError::new(
+                ErrorTy::Lex,
+                format!("number '{}' is out of range", lexeme),
+                location,
+            )),
+            None => self.error.add(LocatedError::new(
+                ErrorTy::Lex,
+                format!("invalid number '{}'", lexeme),
+                location,
+            )),
+        }
+    }
+
+    fn classify_number(number: &str, suffix: &str) -> Option<Result<TokenTy, ()>> {
+        let digits = number.replace('_', "");
+        let decimal = INTEGER_RE.is_match(number) || REAL_RE.is_match(number);
+        if suffix.is_empty() && INTEGER_RE.is_match(number) {
+            Some(digits.parse::<u64>().map(TokenTy::Integer).map_err(|_| ()))
+        } else if let Some(captures) = RADIX_RE.captures(number)
+            && suffix.is_empty()
+        {
+            let (digits, radix) = if let Some(digits) = captures.name("hex") {
+                (digits.as_str(), 16)
+            } else if let Some(digits) = captures.name("oct") {
+                (digits.as_str(), 8)
+            } else {
+                (&captures["bin"], 2)
+            };
+            Some(
+                u64::from_str_radix(&digits.replace('_', ""), radix)
+                    .map(TokenTy::Integer)
+                    .map_err(|_| ()),
+            )
+        } else if suffix.is_empty() && REAL_RE.is_match(number) {
+            Some(Lexer::<Input>::parse_real(&digits).map(TokenTy::Real))
+        } else if decimal && let Some(unit) = TimeUnit::from_suffix(suffix) {
+            Some(Lexer::<Input>::parse_real(&digits).map(|value| TokenTy::Duration(value, unit)))
+        } else if decimal && suffix == "im" {
+            Some(Lexer::<Input>::parse_real(&digits).map(TokenTy::Imaginary))
+        } else {
+            None
+        }
+    }
+
+    fn parse_real(digits: &str) -> Result<f64, ()> {
+        match digits.parse::<f64>() {
+            Ok(value) if value.is_finite() => Ok(value),
+            _ => Err(()),
+        }
+    }
+
+    fn process_string(&mut self) {
+        let mut location = self.location.clone();
+        let quote = self.next_char();
+        self.reset_char();
+        let mut string = String::from("");
+        loop {
+            match self.next_char() {
+                c if c == quote => break,
+                Some('\n') | None => {
+                    location.end_to_start(&self.location);
+                    self.error.add(LocatedError::new(
+                        ErrorTy::Lex,
+                        "unterminated string",
+                        location,
+                    ));
+                    return;
                 }
-            }
-        } else if real_re.is_match(number.as_str()) {
-            match number.parse::<f32>() {
-                Ok(n) => Some(TokenTy::Real(n)),
-                Err(err) => {
-                    self.error.add(SimpleError::new(ErrorTy::Lex, err));
-                    None
+                Some(c) => {
+                    string += &c.to_string();
+                    self.reset_char();
                 }
             }
-        } else {
-            self.error.add(SimpleError::new(
+        }
+        self.reset_char();
+        self.next_char();
+        location.end_to_start(&self.location);
+        let ty = match self.dialect {
+            Dialect::OpenQASM3 if quote == Some('"') && BIT_STRING_RE.is_match(&string) => {
+                TokenTy::BitString(string)
+            }
+            _ => TokenTy::Str(string),
+        };
+        self.token = Some(Token::new(ty, location));
+    }
+
+    fn process_pragma(&mut self) {
+        let mut location = self.location.clone();
+        self.reset_char();
+        let mut id = String::from("");
+        while let Some(c) = self.next_char()
+            && chars::is_id(c)
+        {
+            id += &c.to_string();
+            self.reset_char();
+        }
+        if !self.error.empty() {
+            return;
+        }
+        if id != "pragma" {
+            self.error.add(LocatedError::new(
                 ErrorTy::Lex,
-                format!("invalid number '{}'", number),
+                format!("invalid directive '#{}'", id),
+                location,
             ));
-            None
-        } {
-            self.next_char();
-            location.end_to_start(&self.location);
-            self.token = Some(Token::new(ty, location));
+            return;
+        }
+        let text = self.read_line();
+        location.end_to_start(&self.location);
+        self.token = Some(Token::new(TokenTy::Pragma(text), location));
+    }
+
+    fn follow(&mut self, c: char, ty: TokenTy, otherwise: TokenTy) -> TokenTy {
+        if self.next_char() == Some(c) {
+            self.reset_char();
+            ty
+        } else {
+            otherwise
         }
     }
 
@@ -226,31 +479,79 @@ impl<Input: Read> Lexer<Input> {
         if let Some(c) = self.next_char() {
             self.reset_char();
             if let Some(ty) = match c {
-                '+' => Some(TokenTy::Plus),
+                '+' => match self.next_char() {
+                    Some('+') => {
+                        self.reset_char();
+                        Some(TokenTy::PlusPlus)
+                    }
+                    _ => Some(self.follow('=', TokenTy::PlusEq, TokenTy::Plus)),
+                },
                 '-' => match self.next_char() {
                     Some('>') => {
                         self.reset_char();
                         Some(TokenTy::Arrow)
                     }
-                    Some(_) => Some(TokenTy::Minus),
-                    None => None,
+                    _ => Some(self.follow('=', TokenTy::MinusEq, TokenTy::Minus)),
+                },
+                '*' => match self.next_char() {
+                    Some('*') => {
+                        self.reset_char();
+                        Some(self.follow('=', TokenTy::DoubleStarEq, TokenTy::DoubleStar))
+                    }
+                    _ => Some(self.follow('=', TokenTy::MulEq, TokenTy::Mul)),
                 },
-                '*' => Some(TokenTy::Mul),
                 '/' => match self.next_char() {
                     Some('/') => {
                         self.reset_char();
                         self.handle_line_comment();
-                        return self.process();
+                        location.end_to_start(&self.location);
+                        self.push_trivia(TriviaTy::LineComment, location);
+                        return;
                     }
                     Some('*') => {
                         self.reset_char();
                         self.handle_block_comment();
-                        return self.process();
+                        self.next_char();
+                        location.end_to_start(&self.location);
+                        self.push_trivia(TriviaTy::BlockComment, location);
+                        return;
+                    }
+                    _ => Some(self.follow('=', TokenTy::DivEq, TokenTy::Div)),
+                },
+                '%' => Some(self.follow('=', TokenTy::ModEq, TokenTy::Mod)),
+                '^' => Some(self.follow('=', TokenTy::PowEq, TokenTy::Pow)),
+                '~' => Some(self.follow('=', TokenTy::TildeEq, TokenTy::Tilde)),
+                '!' => Some(self.follow('=', TokenTy::NotEq, TokenTy::Bang)),
+                '=' => Some(self.follow('=', TokenTy::EqEq, TokenTy::Assign)),
+                '@' => Some(TokenTy::At),
+                '&' => match self.next_char() {
+                    Some('&') => {
+                        self.reset_char();
+                        Some(TokenTy::AmpAmp)
                     }
-                    Some(_) => Some(TokenTy::Div),
-                    None => None,
+                    _ => Some(self.follow('=', TokenTy::AmpEq, TokenTy::Amp)),
+                },
+                '|' => match self.next_char() {
+                    Some('|') => {
+                        self.reset_char();
+                        Some(TokenTy::PipePipe)
+                    }
+                    _ => Some(self.follow('=', TokenTy::PipeEq, TokenTy::Pipe)),
+                },
+                '<' => match self.next_char() {
+                    Some('<') => {
+                        self.reset_char();
+                        Some(self.follow('=', TokenTy::ShlEq, TokenTy::Shl))
+                    }
+                    _ => Some(self.follow('=', TokenTy::LtEq, TokenTy::Lt)),
+                },
+                '>' => match self.next_char() {
+                    Some('>') => {
+                        self.reset_char();
+                        Some(self.follow('=', TokenTy::ShrEq, TokenTy::Shr))
+                    }
+                    _ => Some(self.follow('=', TokenTy::GtEq, TokenTy::Gt)),
                 },
-                '^' => Some(TokenTy::Pow),
                 _ => {
                     self.error.add(SimpleError::new(
                         ErrorTy::Internal,
@@ -273,6 +574,7 @@ impl<Input: Read> Lexer<Input> {
             if let Some(ty) = match c {
                 ',' => Some(TokenTy::Comma),
                 ';' => Some(TokenTy::Semi),
+                ':' => Some(TokenTy::Colon),
                 _ => {
                     self.error.add(SimpleError::new(
                         ErrorTy::Internal,
@@ -297,6 +599,8 @@ impl<Input: Read> Lexer<Input> {
                 ']' => Some(TokenTy::RBrack),
                 '(' => Some(TokenTy::LPar),
                 ')' => Some(TokenTy::RPar),
+                '{' => Some(TokenTy::LBrace),
+                '}' => Some(TokenTy::RBrace),
                 _ => {
                     self.error.add(SimpleError::new(
                         ErrorTy::Internal,
@@ -313,6 +617,28 @@ impl<Input: Read> Lexer<Input> {
     }
 
     fn process(&mut self) {
+        while self.token.is_none() && self.error.empty() {
+            self.process_token();
+        }
+        if let Some(token) = &mut self.token {
+            token.attach(
+                std::mem::take(&mut self.text),
+                std::mem::take(&mut self.trivia),
+            );
+            if self.header
+                && let TokenTy::Integer(_) | TokenTy::Real(_) = token.get_ty()
+                && let Some((major, minor)) = keywords::parse_version(token.get_text())
+                && let Some(dialect) = Dialect::from_version(major, minor)
+            {
+                self.dialect = dialect;
+            }
+            self.header = matches!(token.get_ty(), TokenTy::OpenQASM);
+        }
+    }
+
+    fn process_token(&mut self) {
+        self.next_char();
+        let mut location = self.location.clone();
         while let Some(c) = self.next_char()
             && chars::is_space(c)
         {
@@ -321,11 +647,11 @@ impl<Input: Read> Lexer<Input> {
         if !self.error.empty() {
             return;
         }
+        location.end_to_start(&self.location);
+        self.push_trivia(TriviaTy::Whitespace, location);
         if let Some(c) = self.next_char() {
-            if chars::is_lower(c) {
+            if chars::is_id_start(c) {
                 self.process_identifier();
-            } else if chars::is_upper(c) {
-                self.process_reserved_identifier();
             } else if chars::is_number(c) {
                 self.process_number();
             } else if chars::is_operator(c) {
@@ -334,6 +660,10 @@ impl<Input: Read> Lexer<Input> {
                 self.process_punctuation();
             } else if chars::is_bracket(c) {
                 self.process_bracket();
+            } else if chars::is_quote(c) {
+                self.process_string();
+            } else if chars::is_pragma(c) {
+                self.process_pragma();
             } else {
                 self.error.add(LocatedError::new(
                     ErrorTy::Lex,
@@ -341,7 +671,7 @@ impl<Input: Read> Lexer<Input> {
                     self.location.clone(),
                 ));
             }
-        } else {
+        } else if self.error.empty() {
             self.token = Some(Token::new(TokenTy::Eof, self.location.clone()));
         }
     }
@@ -354,16 +684,21 @@ impl<Input: Read> Lexer<Input> {
         &self.token
     }
 
-    pub fn drop(&mut self) {
+    pub fn discard(&mut self) {
         self.token = None;
     }
 
+    pub fn take(&mut self) -> Option<Token> {
+        self.peek();
+        self.token.take()
+    }
+
     pub fn dump_chars(&mut self) {
         while let Some(c) = self.next_char() {
-            println!("{}@{}", c.escape_debug(), self.location.to_string());
+            println!("{}@{}", c.escape_debug(), self.location);
             self.reset_char();
         }
-        println!("eof@{}", self.location.to_string());
+        println!("eof@{}", self.location);
     }
 
     pub fn get_error(&self) -> &CompoundError {
@@ -374,3 +709,18 @@ impl<Input: Read> Lexer<Input> {
         &mut self.error
     }
 }
+
+impl<Input: Read> Iterator for Lexer<Input> {
+    type Item = Token;
+
+    fn next(&mut self) -> Option<Token> {
+        if self.finished {
+            return None;
+        }
+        let token = self.take()?;
+        if let TokenTy::Eof = token.get_ty() {
+            self.finished = true;
+        }
+        Some(token)
+    }
+}
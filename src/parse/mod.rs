@@ -1,3 +1,5 @@
+pub mod incremental;
+pub mod keywords;
 pub mod lexer;
 pub mod parser;
 pub mod token;
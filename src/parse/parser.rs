@@ -1,15 +1,26 @@
+use std::fs::File;
 use std::io::Read;
+use std::path::Path;
 
 use std::vec::Vec;
 
 use crate::ast::{factory, node};
 
+use crate::cst::syntax::{CstBuilder, SyntaxKind, SyntaxNode};
+use crate::cst::view;
+
+use crate::parse::keywords::{self, Dialect};
 use crate::parse::lexer::Lexer;
 use crate::parse::token::TokenTy;
-use crate::utils::error::{CompoundError, ErrorTy, LocatedError};
+use crate::utils::error::{CompoundError, ErrorTy, LocatedError, SimpleError};
+use crate::utils::location::Location;
+
+const MAX_EXP_DEPTH: u32 = 256;
 
 pub struct Parser<Input: Read> {
     lexer: Lexer<Input>,
+    depth: u32,
+    cst: CstBuilder,
     error: CompoundError,
 }
 
@@ -52,51 +63,181 @@ macro_rules! peek_token {
 impl<Input: Read> Parser<Input> {
     pub fn new(lexer: Lexer<Input>) -> Parser<Input> {
         Parser {
-            lexer: lexer,
+            lexer,
+            depth: 0,
+            cst: CstBuilder::new(),
             error: CompoundError::new(),
         }
     }
 
-    pub fn parse_input(&mut self) -> Option<Vec<node::Stmt>> {
-        peek_token!(self; in [TokenTy::OpenQASM])?;
-        self.lexer.drop();
+    pub fn set_cst(&mut self, enabled: bool) {
+        self.lexer.set_lossless(enabled);
+        self.cst.set_enabled(enabled);
+    }
+
+    pub fn take_cst(&mut self) -> Option<SyntaxNode> {
+        self.cst.finish()
+    }
+
+    fn bump(&mut self) {
+        if self.cst.is_enabled()
+            && let Some(token) = Lexer::take(&mut self.lexer)
+        {
+            self.cst.push_token(token);
+        } else {
+            self.lexer.discard();
+        }
+    }
+
+    pub fn parse_input(&mut self) -> Option<node::Program> {
+        self.cst.reset();
+        self.cst.start_node(SyntaxKind::Program);
+        self.cst.start_node(SyntaxKind::Header);
+        let begin = peek_token!(self; in [TokenTy::OpenQASM])?;
+        let mut loc = begin.get_loc().clone();
+        self.bump();
+
+        let token = peek_token!(self; in [TokenTy::Real(_), TokenTy::Integer(_)])?;
+        let version = match keywords::parse_version(token.get_text()) {
+            Some((major, minor)) if Dialect::from_version(major, minor).is_some() => {
+                node::Version { major, minor }
+            }
+            _ => {
+                self.error.add(LocatedError::new(
+                    ErrorTy::Parse,
+                    format!("unsupported version '{}'", token.get_text()),
+                    token.get_loc().clone(),
+                ));
+                return None;
+            }
+        };
+        self.bump();
 
-        peek_token!(self; in [TokenTy::Real(2.0)])?;
-        self.lexer.drop();
+        let end = peek_token!(self; in [TokenTy::Semi])?;
+        loc.end_to_end(end.get_loc());
+        self.bump();
+        self.cst.finish_node();
 
-        peek_token!(self; in [TokenTy::Semi])?;
-        self.lexer.drop();
+        let program = self.parse_program(loc, version)?;
+        self.bump();
+        self.cst.finish_node();
 
-        self.parse_program()
+        Some(program)
     }
 
-    fn parse_program(&mut self) -> Option<Vec<node::Stmt>> {
-        let mut program: Vec<node::Stmt> = Vec::new();
+    pub fn parse_exp_input(&mut self) -> Option<node::Exp> {
+        let exp = self.parse_exp()?;
+
+        peek_token!(self; in [TokenTy::Eof])?;
+
+        Some(exp)
+    }
+
+    pub fn parse_stmt_input(&mut self) -> Option<node::Stmt> {
+        let stmt = self.parse_statement()?;
+
+        peek_token!(self; in [TokenTy::Eof])?;
+
+        Some(stmt)
+    }
+
+    pub fn parse_stmts_input(&mut self) -> Option<Vec<node::Stmt>> {
+        let mut stmts: Vec<node::Stmt> = Vec::new();
+        while !is_token!(peek_token!(self)?; in [TokenTy::Eof]) {
+            stmts.push(self.parse_statement()?);
+        }
+
+        Some(stmts)
+    }
+
+    pub fn parse_gate_body_input(&mut self) -> Option<Vec<node::Stmt>> {
+        let (body, _) = self.parse_gate_body()?;
+
+        peek_token!(self; in [TokenTy::Eof])?;
+
+        Some(body)
+    }
+
+    fn parse_program(&mut self, loc: Location, version: node::Version) -> Option<node::Program> {
+        let mut includes: Vec<node::Include> = Vec::new();
+        let mut pragmas: Vec<node::Pragma> = Vec::new();
+        let mut stmts: Vec<node::Stmt> = Vec::new();
         loop {
-            program.push(self.parse_statement()?);
+            match peek_token!(self)?.get_ty() {
+                TokenTy::Include => includes.push(self.parse_include(stmts.len())?),
+                TokenTy::Pragma(_) => pragmas.push(self.parse_pragma(stmts.len())?),
+                _ => stmts.push(self.parse_statement()?),
+            }
             if is_token!(peek_token!(self)?; in [TokenTy::Eof]) {
-                return Some(program);
+                return Some(factory::make_program(
+                    loc, version, includes, pragmas, stmts,
+                ));
             }
         }
     }
 
+    fn parse_include(&mut self, index: usize) -> Option<node::Include> {
+        self.cst.start_node(SyntaxKind::Include);
+        let begin = peek_token!(self; in [TokenTy::Include])?;
+        let mut loc = begin.get_loc().clone();
+        self.bump();
+
+        let path = match peek_token!(self; in [TokenTy::Str(_)])?.get_ty() {
+            TokenTy::Str(it) => it.clone(),
+            _ => return None,
+        };
+        self.bump();
+
+        let end = peek_token!(self; in [TokenTy::Semi])?;
+        loc.end_to_end(end.get_loc());
+        self.bump();
+
+        self.cst.finish_node();
+
+        Some(factory::make_include(loc, path, index))
+    }
+
+    fn parse_pragma(&mut self, index: usize) -> Option<node::Pragma> {
+        self.cst.start_node(SyntaxKind::Pragma);
+        let token = peek_token!(self; in [TokenTy::Pragma(_)])?;
+        let loc = token.get_loc().clone();
+        let text = match token.get_ty() {
+            TokenTy::Pragma(it) => it.clone(),
+            _ => return None,
+        };
+        self.bump();
+
+        self.cst.finish_node();
+
+        Some(factory::make_pragma(loc, text, index))
+    }
+
     fn parse_statement(&mut self) -> Option<node::Stmt> {
         let token = peek_token!(self; in [
             TokenTy::QReg,
             TokenTy::CReg,
+            TokenTy::Gate,
+            TokenTy::Opaque,
             TokenTy::UGate,
             TokenTy::CXGate,
+            TokenTy::Id(_),
             TokenTy::Measure,
-            TokenTy::Reset
+            TokenTy::Reset,
+            TokenTy::Barrier,
+            TokenTy::If
         ])?;
-        if is_token!(token; in [TokenTy::QReg, TokenTy::CReg]) {
-            self.parse_decl()
-        } else {
-            self.parse_qop()
+        match token.get_ty() {
+            TokenTy::QReg | TokenTy::CReg => self.parse_decl(),
+            TokenTy::Gate => self.parse_gatedecl(),
+            TokenTy::Opaque => self.parse_opaque(),
+            TokenTy::Barrier => self.parse_barrier(),
+            TokenTy::If => self.parse_if(),
+            _ => self.parse_qop(),
         }
     }
 
     fn parse_decl(&mut self) -> Option<node::Stmt> {
+        self.cst.start_node(SyntaxKind::RegDecl);
         let begin = peek_token!(self; in [TokenTy::QReg, TokenTy::CReg])?;
         let mut loc = begin.get_loc().clone();
         let ty = match begin.get_ty() {
@@ -104,74 +245,202 @@ impl<Input: Read> Parser<Input> {
             TokenTy::CReg => node::RegTy::CReg,
             _ => return None,
         };
-        self.lexer.drop();
+        self.bump();
 
         let name = match peek_token!(self; in [TokenTy::Id(_)])?.get_ty() {
             TokenTy::Id(it) => it.clone(),
             _ => return None,
         };
-        self.lexer.drop();
+        self.bump();
 
         peek_token!(self; in [TokenTy::LBrack])?;
-        self.lexer.drop();
+        self.bump();
 
         let size = match peek_token!(self; in [TokenTy::Integer(_)])?.get_ty() {
             TokenTy::Integer(it) => *it,
             _ => return None,
         };
-        self.lexer.drop();
+        self.bump();
 
         peek_token!(self; in [TokenTy::RBrack])?;
-        self.lexer.drop();
+        self.bump();
 
         let end = peek_token!(self; in [TokenTy::Semi])?;
         loc.end_to_end(end.get_loc());
-        self.lexer.drop();
+        self.bump();
+
+        self.cst.finish_node();
 
         Some(factory::make_reg_dec(loc, name, ty, size))
     }
 
+    fn parse_gatedecl(&mut self) -> Option<node::Stmt> {
+        self.cst.start_node(SyntaxKind::GateDecl);
+        let begin = peek_token!(self; in [TokenTy::Gate])?;
+        let mut loc = begin.get_loc().clone();
+        self.bump();
+
+        let (name, pars, args) = self.parse_gate_signature()?;
+
+        let (body, end) = self.parse_gate_body()?;
+        loc.end_to_end(&end);
+
+        self.cst.finish_node();
+
+        Some(factory::make_gate_dec(loc, name, pars, args, Some(body)))
+    }
+
+    fn parse_opaque(&mut self) -> Option<node::Stmt> {
+        self.cst.start_node(SyntaxKind::GateDecl);
+        let begin = peek_token!(self; in [TokenTy::Opaque])?;
+        let mut loc = begin.get_loc().clone();
+        self.bump();
+
+        let (name, pars, args) = self.parse_gate_signature()?;
+
+        let end = peek_token!(self; in [TokenTy::Semi])?;
+        loc.end_to_end(end.get_loc());
+        self.bump();
+
+        self.cst.finish_node();
+
+        Some(factory::make_gate_dec(loc, name, pars, args, None))
+    }
+
+    fn parse_gate_signature(&mut self) -> Option<(String, Vec<String>, Vec<String>)> {
+        let name = match peek_token!(self; in [TokenTy::Id(_)])?.get_ty() {
+            TokenTy::Id(it) => it.clone(),
+            _ => return None,
+        };
+        self.bump();
+
+        let mut pars: Vec<String> = Vec::new();
+        if is_token!(peek_token!(self)?; in [TokenTy::LPar]) {
+            self.bump();
+            if !is_token!(peek_token!(self)?; in [TokenTy::RPar]) {
+                pars = self.parse_idlist()?;
+            }
+            peek_token!(self; in [TokenTy::RPar])?;
+            self.bump();
+        }
+
+        let args = self.parse_idlist()?;
+
+        Some((name, pars, args))
+    }
+
+    fn parse_idlist(&mut self) -> Option<Vec<String>> {
+        let mut ids: Vec<String> = Vec::new();
+        loop {
+            match peek_token!(self; in [TokenTy::Id(_)])?.get_ty() {
+                TokenTy::Id(it) => ids.push(it.clone()),
+                _ => return None,
+            }
+            self.bump();
+            if !is_token!(peek_token!(self)?; in [TokenTy::Comma]) {
+                return Some(ids);
+            }
+            self.bump();
+        }
+    }
+
     fn parse_qop(&mut self) -> Option<node::Stmt> {
         let token = peek_token!(self; in [
             TokenTy::UGate,
             TokenTy::CXGate,
+            TokenTy::Id(_),
             TokenTy::Measure,
             TokenTy::Reset
         ])?;
         match token.get_ty() {
-            TokenTy::UGate | TokenTy::CXGate => self.parse_uop(),
+            TokenTy::UGate | TokenTy::CXGate | TokenTy::Id(_) => self.parse_uop(),
             TokenTy::Measure => self.parse_measure(),
             TokenTy::Reset => self.parse_reset(),
-            _ => return None,
+            _ => None,
+        }
+    }
+
+    fn parse_gate_body(&mut self) -> Option<(Vec<node::Stmt>, Location)> {
+        peek_token!(self; in [TokenTy::LBrace])?;
+        self.bump();
+
+        let mut body: Vec<node::Stmt> = Vec::new();
+        while !is_token!(peek_token!(self)?; in [TokenTy::RBrace]) {
+            if is_token!(peek_token!(self)?; in [TokenTy::Barrier]) {
+                body.push(self.parse_barrier()?);
+            } else {
+                body.push(self.parse_uop()?);
+            }
         }
+        let end = peek_token!(self)?.get_loc().clone();
+        self.bump();
+
+        Some((body, end))
     }
 
     fn parse_uop(&mut self) -> Option<node::Stmt> {
-        match peek_token!(self; in [TokenTy::UGate, TokenTy::CXGate])?.get_ty() {
+        match peek_token!(self; in [TokenTy::UGate, TokenTy::CXGate, TokenTy::Id(_)])?.get_ty() {
             TokenTy::UGate => self.parse_ugate(),
             TokenTy::CXGate => self.parse_cxgate(),
+            TokenTy::Id(_) => self.parse_gate_call(),
+            _ => None,
+        }
+    }
+
+    fn parse_gate_call(&mut self) -> Option<node::Stmt> {
+        self.cst.start_node(SyntaxKind::GateStmt);
+        let begin = peek_token!(self; in [TokenTy::Id(_)])?;
+        let mut loc = begin.get_loc().clone();
+        let gate = match begin.get_ty() {
+            TokenTy::Id(it) => it.clone(),
             _ => return None,
+        };
+        self.bump();
+
+        let mut pars: Vec<node::Exp> = Vec::new();
+        if is_token!(peek_token!(self)?; in [TokenTy::LPar]) {
+            self.bump();
+            if !is_token!(peek_token!(self)?; in [TokenTy::RPar]) {
+                pars = self.parse_explist()?;
+            }
+            peek_token!(self; in [TokenTy::RPar])?;
+            self.bump();
+        }
+
+        let mut args = vec![self.parse_argument()?];
+        while is_token!(peek_token!(self)?; in [TokenTy::Comma]) {
+            self.bump();
+            args.push(self.parse_argument()?);
         }
+
+        let end = peek_token!(self; in [TokenTy::Semi])?;
+        loc.end_to_end(end.get_loc());
+        self.bump();
+        self.cst.finish_node();
+
+        Some(factory::make_gate_stmt(loc, gate, pars, args))
     }
 
     fn parse_ugate(&mut self) -> Option<node::Stmt> {
+        self.cst.start_node(SyntaxKind::GateStmt);
         let begin = peek_token!(self; in [TokenTy::UGate])?;
         let mut loc = begin.get_loc().clone();
-        self.lexer.drop();
+        self.bump();
 
         peek_token!(self; in [TokenTy::LPar])?;
-        self.lexer.drop();
+        self.bump();
 
         let pars = self.parse_explist()?;
 
         peek_token!(self; in [TokenTy::RPar])?;
-        self.lexer.drop();
+        self.bump();
 
         let arg = self.parse_argument()?;
 
         let end = peek_token!(self; in [TokenTy::Semi])?;
         loc.end_to_end(end.get_loc());
-        self.lexer.drop();
+        self.bump();
+        self.cst.finish_node();
 
         Some(factory::make_gate_stmt(
             loc,
@@ -182,20 +451,22 @@ impl<Input: Read> Parser<Input> {
     }
 
     fn parse_cxgate(&mut self) -> Option<node::Stmt> {
+        self.cst.start_node(SyntaxKind::GateStmt);
         let begin = peek_token!(self; in [TokenTy::CXGate])?;
         let mut loc = begin.get_loc().clone();
-        self.lexer.drop();
+        self.bump();
 
         let control = self.parse_argument()?;
 
         peek_token!(self; in [TokenTy::Comma])?;
-        self.lexer.drop();
+        self.bump();
 
         let target = self.parse_argument()?;
 
         let end = peek_token!(self; in [TokenTy::Semi])?;
         loc.end_to_end(end.get_loc());
-        self.lexer.drop();
+        self.bump();
+        self.cst.finish_node();
 
         Some(factory::make_gate_stmt(
             loc,
@@ -206,73 +477,152 @@ impl<Input: Read> Parser<Input> {
     }
 
     fn parse_measure(&mut self) -> Option<node::Stmt> {
+        self.cst.start_node(SyntaxKind::MeasureStmt);
         let begin = peek_token!(self; in [TokenTy::Measure])?;
         let mut loc = begin.get_loc().clone();
-        self.lexer.drop();
+        self.bump();
 
         let src = self.parse_argument()?;
 
         peek_token!(self; in [TokenTy::Arrow])?;
-        self.lexer.drop();
+        self.bump();
 
         let dst = self.parse_argument()?;
 
         let end = peek_token!(self; in [TokenTy::Semi])?;
         loc.end_to_end(end.get_loc());
-        self.lexer.drop();
+        self.bump();
+
+        self.cst.finish_node();
 
         Some(factory::make_measure_stmt(loc, src, dst))
     }
 
     fn parse_reset(&mut self) -> Option<node::Stmt> {
+        self.cst.start_node(SyntaxKind::ResetStmt);
         let begin = peek_token!(self; in [TokenTy::Reset])?;
         let mut loc = begin.get_loc().clone();
-        self.lexer.drop();
+        self.bump();
 
         let reg = self.parse_argument()?;
 
         let end = peek_token!(self; in [TokenTy::Semi])?;
         loc.end_to_end(end.get_loc());
-        self.lexer.drop();
+        self.bump();
+
+        self.cst.finish_node();
 
         Some(factory::make_reset_stmt(loc, reg))
     }
 
+    fn parse_barrier(&mut self) -> Option<node::Stmt> {
+        self.cst.start_node(SyntaxKind::BarrierStmt);
+        let begin = peek_token!(self; in [TokenTy::Barrier])?;
+        let mut loc = begin.get_loc().clone();
+        self.bump();
+
+        let mut args = vec![self.parse_argument()?];
+        while is_token!(peek_token!(self)?; in [TokenTy::Comma]) {
+            self.bump();
+            args.push(self.parse_argument()?);
+        }
+
+        let end = peek_token!(self; in [TokenTy::Semi])?;
+        loc.end_to_end(end.get_loc());
+        self.bump();
+
+        self.cst.finish_node();
+
+        Some(factory::make_barrier_stmt(loc, args))
+    }
+
+    fn parse_if(&mut self) -> Option<node::Stmt> {
+        self.cst.start_node(SyntaxKind::IfStmt);
+        let begin = peek_token!(self; in [TokenTy::If])?;
+        let mut loc = begin.get_loc().clone();
+        self.bump();
+
+        peek_token!(self; in [TokenTy::LPar])?;
+        self.bump();
+
+        self.cst.start_node(SyntaxKind::SimpleReg);
+        let token = peek_token!(self; in [TokenTy::Id(_)])?;
+        let reg_loc = token.get_loc().clone();
+        let name = match token.get_ty() {
+            TokenTy::Id(it) => it.clone(),
+            _ => return None,
+        };
+        self.bump();
+        self.cst.finish_node();
+
+        peek_token!(self; in [TokenTy::EqEq])?;
+        self.bump();
+
+        let value = match peek_token!(self; in [TokenTy::Integer(_)])?.get_ty() {
+            TokenTy::Integer(it) => *it,
+            _ => return None,
+        };
+        self.bump();
+
+        peek_token!(self; in [TokenTy::RPar])?;
+        self.bump();
+
+        let stmt = self.parse_qop()?;
+        loc.end_to_end(stmt.get_loc());
+
+        self.cst.finish_node();
+
+        Some(factory::make_if_stmt(
+            loc,
+            factory::make_simple_reg(reg_loc, name),
+            value,
+            stmt,
+        ))
+    }
+
     fn parse_argument(&mut self) -> Option<node::Reg> {
+        let checkpoint = self.cst.checkpoint();
         let begin = peek_token!(self; in [TokenTy::Id(_)])?;
         let mut loc = begin.get_loc().clone();
         let name = match begin.get_ty() {
             TokenTy::Id(it) => it.clone(),
             _ => return None,
         };
-        self.lexer.drop();
+        self.bump();
 
         if !is_token!(peek_token!(self)?; in [TokenTy::LBrack]) {
+            self.cst.start_node_at(checkpoint, SyntaxKind::SimpleReg);
+            self.cst.finish_node();
             return Some(factory::make_simple_reg(loc, name));
         }
-        self.lexer.drop();
+        self.bump();
 
         let index = match peek_token!(self; in [TokenTy::Integer(_)])?.get_ty() {
             TokenTy::Integer(it) => *it,
             _ => return None,
         };
-        self.lexer.drop();
+        self.bump();
 
         let end = peek_token!(self; in [TokenTy::RBrack])?;
         loc.end_to_end(end.get_loc());
-        self.lexer.drop();
+        self.bump();
+
+        self.cst.start_node_at(checkpoint, SyntaxKind::SubscriptReg);
+        self.cst.finish_node();
 
         Some(factory::make_subscript_reg(loc, name, index))
     }
 
     fn parse_explist(&mut self) -> Option<Vec<node::Exp>> {
+        self.cst.start_node(SyntaxKind::ExpList);
         let mut exps: Vec<node::Exp> = Vec::new();
         loop {
             exps.push(self.parse_exp()?);
             if !is_token!(peek_token!(self)?; in [TokenTy::Comma]) {
+                self.cst.finish_node();
                 return Some(exps);
             }
-            self.lexer.drop();
+            self.bump();
         }
     }
 
@@ -280,59 +630,62 @@ impl<Input: Read> Parser<Input> {
         let token = peek_token!(self; in [
             TokenTy::Real(_),
             TokenTy::Integer(_),
-            TokenTy::Pi
+            TokenTy::Pi,
+            TokenTy::Id(_)
         ])?;
         let loc = token.get_loc().clone();
-        let exp = match token.get_ty() {
-            TokenTy::Real(value) => factory::make_real_exp(loc, *value),
-            TokenTy::Integer(value) => factory::make_int_exp(loc, *value),
-            TokenTy::Pi => factory::make_pi_exp(loc),
+        let (kind, exp) = match token.get_ty() {
+            TokenTy::Real(value) => (
+                SyntaxKind::RealExp,
+                factory::make_real_exp(loc, *value, token.get_text().to_string()),
+            ),
+            TokenTy::Integer(value) => (
+                SyntaxKind::IntExp,
+                factory::make_int_exp(loc, *value, token.get_text().to_string()),
+            ),
+            TokenTy::Pi => (SyntaxKind::PiExp, factory::make_pi_exp(loc)),
+            TokenTy::Id(name) => (SyntaxKind::IdExp, factory::make_id_exp(loc, name.clone())),
             _ => return None,
         };
-        self.lexer.drop();
+        self.cst.start_node(kind);
+        self.bump();
+        self.cst.finish_node();
 
         Some(exp)
     }
 
     fn parse_delimited_term_exp(&mut self) -> Option<node::Exp> {
+        self.cst.start_node(SyntaxKind::ParenExp);
         let begin = peek_token!(self; in [TokenTy::LPar])?;
         let mut loc = begin.get_loc().clone();
-        self.lexer.drop();
+        self.bump();
 
         let exp = self.parse_exp()?;
 
         let end = peek_token!(self; in [TokenTy::RPar])?;
         loc.end_to_end(end.get_loc());
-        self.lexer.drop();
-
-        let res = match exp {
-            node::Exp::IntExp { loc: _, value } => factory::make_int_exp(loc, value),
-            node::Exp::RealExp { loc: _, value } => factory::make_real_exp(loc, value),
-            node::Exp::PiExp { loc: _ } => factory::make_pi_exp(loc),
-            node::Exp::BinopExp {
-                loc: _,
-                left,
-                op,
-                right,
-            } => factory::make_binop_exp(loc, *left, op, *right),
-            node::Exp::UnaryExp { loc: _, op, exp } => factory::make_unary_exp(loc, op, *exp),
-        };
+        self.bump();
 
-        Some(res)
+        self.cst.finish_node();
+
+        Some(factory::make_delimited_exp(loc, exp))
     }
 
     fn parse_negative_term_exp(&mut self) -> Option<node::Exp> {
+        self.cst.start_node(SyntaxKind::UnaryExp);
         let begin = peek_token!(self; in [TokenTy::Minus])?;
         let mut loc = begin.get_loc().clone();
-        self.lexer.drop();
+        self.bump();
 
         let exp = self.parse_term_exp()?;
         loc.end_to_end(exp.get_loc());
+        self.cst.finish_node();
 
         Some(factory::make_unary_exp(loc, node::UnaryOp::Minus, exp))
     }
 
     fn parse_unary_term_exp(&mut self) -> Option<node::Exp> {
+        self.cst.start_node(SyntaxKind::UnaryExp);
         let begin = peek_token!(self; in [
             TokenTy::Sin,
             TokenTy::Cos,
@@ -351,25 +704,69 @@ impl<Input: Read> Parser<Input> {
             TokenTy::Sqrt => node::UnaryOp::Sqrt,
             _ => return None,
         };
-        self.lexer.drop();
+        self.bump();
 
         peek_token!(self; in [TokenTy::LPar])?;
-        self.lexer.drop();
+        self.bump();
 
         let exp = self.parse_exp()?;
 
         let end = peek_token!(self; in [TokenTy::RPar])?;
         loc.end_to_end(end.get_loc());
-        self.lexer.drop();
+        self.bump();
+        self.cst.finish_node();
 
         Some(factory::make_unary_exp(loc, op, exp))
     }
 
+    /// Counts one more level of nesting in the expression being parsed, or
+    /// reports an error once `MAX_EXP_DEPTH` is reached.
+    fn enter_exp(&mut self) -> Option<()> {
+        if self.depth >= MAX_EXP_DEPTH {
+            let token = peek_token!(self)?;
+            self.error.add(LocatedError::new(
+                ErrorTy::Parse,
+                "expression is nested too deeply",
+                token.get_loc().clone(),
+            ));
+            return None;
+        }
+        self.depth += 1;
+        Some(())
+    }
+
+    fn parse_nested_exp(
+        &mut self,
+        parse: fn(&mut Parser<Input>) -> Option<node::Exp>,
+    ) -> Option<node::Exp> {
+        let depth = self.depth;
+        let exp = self.enter_exp().and_then(|_| parse(self));
+        self.depth = depth;
+        exp
+    }
+
+    /// Runs `parse` and restores the nesting depth it had before, whether or
+    /// not it succeeds.
+    fn parse_chain_exp(
+        &mut self,
+        parse: fn(&mut Parser<Input>) -> Option<node::Exp>,
+    ) -> Option<node::Exp> {
+        let depth = self.depth;
+        let exp = parse(self);
+        self.depth = depth;
+        exp
+    }
+
     fn parse_term_exp(&mut self) -> Option<node::Exp> {
+        self.parse_nested_exp(Parser::parse_any_term_exp)
+    }
+
+    fn parse_any_term_exp(&mut self) -> Option<node::Exp> {
         let token = peek_token!(self; in [
             TokenTy::Real(_),
             TokenTy::Integer(_),
             TokenTy::Pi,
+            TokenTy::Id(_),
             TokenTy::LPar,
             TokenTy::Minus,
             TokenTy::Sin,
@@ -380,7 +777,9 @@ impl<Input: Read> Parser<Input> {
             TokenTy::Sqrt
         ])?;
         match token.get_ty() {
-            TokenTy::Real(_) | TokenTy::Integer(_) | TokenTy::Pi => self.parse_simple_term_exp(),
+            TokenTy::Real(_) | TokenTy::Integer(_) | TokenTy::Pi | TokenTy::Id(_) => {
+                self.parse_simple_term_exp()
+            }
             TokenTy::LPar => self.parse_delimited_term_exp(),
             TokenTy::Minus => self.parse_negative_term_exp(),
             TokenTy::Sin
@@ -389,21 +788,28 @@ impl<Input: Read> Parser<Input> {
             | TokenTy::Exp
             | TokenTy::Ln
             | TokenTy::Sqrt => self.parse_unary_term_exp(),
-            _ => return None,
+            _ => None,
         }
     }
 
     fn parse_exponential_exp(&mut self) -> Option<node::Exp> {
+        self.parse_nested_exp(Parser::parse_power_exp)
+    }
+
+    fn parse_power_exp(&mut self) -> Option<node::Exp> {
+        let checkpoint = self.cst.checkpoint();
         let left = self.parse_term_exp()?;
         let mut loc = left.get_loc().clone();
 
         if !is_token!(peek_token!(self)?; in [TokenTy::Pow]) {
             return Some(left);
         }
-        self.lexer.drop();
+        self.cst.start_node_at(checkpoint, SyntaxKind::BinopExp);
+        self.bump();
 
         let right = self.parse_exponential_exp()?;
         loc.end_to_end(right.get_loc());
+        self.cst.finish_node();
 
         Some(factory::make_binop_exp(
             loc,
@@ -414,6 +820,13 @@ impl<Input: Read> Parser<Input> {
     }
 
     fn parse_multiplicative_exp(&mut self) -> Option<node::Exp> {
+        self.parse_chain_exp(Parser::parse_product_exp)
+    }
+
+    /// Each operator of a left-associative chain adds a level to the tree, so
+    /// it counts towards the nesting depth like a parenthesis does.
+    fn parse_product_exp(&mut self) -> Option<node::Exp> {
+        let checkpoint = self.cst.checkpoint();
         let mut left = self.parse_exponential_exp()?;
         let mut loc = left.get_loc().clone();
         loop {
@@ -422,10 +835,13 @@ impl<Input: Read> Parser<Input> {
                 TokenTy::Div => node::BinopTy::Div,
                 _ => break,
             };
-            self.lexer.drop();
+            self.enter_exp()?;
+            self.cst.start_node_at(checkpoint, SyntaxKind::BinopExp);
+            self.bump();
 
             let right = self.parse_exponential_exp()?;
             loc.end_to_end(right.get_loc());
+            self.cst.finish_node();
 
             left = factory::make_binop_exp(loc.clone(), left, op, right)
         }
@@ -433,6 +849,11 @@ impl<Input: Read> Parser<Input> {
     }
 
     fn parse_additive_exp(&mut self) -> Option<node::Exp> {
+        self.parse_chain_exp(Parser::parse_sum_exp)
+    }
+
+    fn parse_sum_exp(&mut self) -> Option<node::Exp> {
+        let checkpoint = self.cst.checkpoint();
         let mut left = self.parse_multiplicative_exp()?;
         let mut loc = left.get_loc().clone();
         loop {
@@ -441,10 +862,13 @@ impl<Input: Read> Parser<Input> {
                 TokenTy::Minus => node::BinopTy::Sub,
                 _ => break,
             };
-            self.lexer.drop();
+            self.enter_exp()?;
+            self.cst.start_node_at(checkpoint, SyntaxKind::BinopExp);
+            self.bump();
 
             let right = self.parse_multiplicative_exp()?;
             loc.end_to_end(right.get_loc());
+            self.cst.finish_node();
 
             left = factory::make_binop_exp(loc.clone(), left, op, right)
         }
@@ -463,3 +887,65 @@ impl<Input: Read> Parser<Input> {
         &mut self.error
     }
 }
+
+pub fn parse_reader<Input: Read>(
+    name: String,
+    input: Input,
+) -> Result<node::Program, CompoundError> {
+    let mut parser = Parser::new(Lexer::new(name, input));
+    let program = parser.parse_input();
+
+    let mut error = CompoundError::new();
+    error.consume(parser.get_error_mut());
+    match program {
+        Some(program) if error.empty() => Ok(program),
+        _ => Err(error),
+    }
+}
+
+pub fn parse_str(name: &str, input: &str) -> Result<node::Program, CompoundError> {
+    parse_reader(String::from(name), input.as_bytes())
+}
+
+pub fn parse_file(path: &Path) -> Result<node::Program, CompoundError> {
+    match File::open(path) {
+        Ok(file) => parse_reader(path.display().to_string(), file),
+        Err(err) => {
+            let mut error = CompoundError::new();
+            error.add(SimpleError::new(
+                ErrorTy::Internal,
+                format!("cannot open '{}': {}", path.display(), err),
+            ));
+            Err(error)
+        }
+    }
+}
+
+pub fn parse_stdin() -> Result<node::Program, CompoundError> {
+    parse_reader(String::from("<stdin>"), std::io::stdin())
+}
+
+pub fn parse_cst(name: &str, input: &str) -> Result<(node::Program, SyntaxNode), CompoundError> {
+    let mut parser = Parser::new(Lexer::new(String::from(name), input.as_bytes()));
+    parser.set_cst(true);
+    let program = parser.parse_input();
+    let cst = parser.take_cst();
+
+    let mut error = CompoundError::new();
+    error.consume(parser.get_error_mut());
+    match (program, cst) {
+        (Some(_), Some(cst)) if error.empty() => {
+            match view::Program::cast(&cst).and_then(|program| program.to_node()) {
+                Some(program) => Ok((program, cst)),
+                None => {
+                    error.add(SimpleError::new(
+                        ErrorTy::Internal,
+                        "cannot read the program off its syntax tree",
+                    ));
+                    Err(error)
+                }
+            }
+        }
+        _ => Err(error),
+    }
+}
@@ -1,11 +1,53 @@
+use std::fmt;
+
 use crate::utils::location::Location;
 
+pub enum TimeUnit {
+    Dt,
+    Ns,
+    Us,
+    Ms,
+    S,
+}
+
+impl fmt::Display for TimeUnit {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TimeUnit::Dt => f.write_str("dt"),
+            TimeUnit::Ns => f.write_str("ns"),
+            TimeUnit::Us => f.write_str("us"),
+            TimeUnit::Ms => f.write_str("ms"),
+            TimeUnit::S => f.write_str("s"),
+        }
+    }
+}
+
+impl TimeUnit {
+    pub fn from_suffix(suffix: &str) -> Option<TimeUnit> {
+        match suffix {
+            "dt" => Some(TimeUnit::Dt),
+            "ns" => Some(TimeUnit::Ns),
+            "us" | "µs" => Some(TimeUnit::Us),
+            "ms" => Some(TimeUnit::Ms),
+            "s" => Some(TimeUnit::S),
+            _ => None,
+        }
+    }
+}
+
 pub enum TokenTy {
     OpenQASM,
 
-    Integer(u32),
-    Real(f32),
+    Integer(u64),
+    Real(f64),
+    Imaginary(f64),
+    Duration(f64, TimeUnit),
     Id(String),
+    Str(String),
+    BitString(String),
+    Pragma(String),
+
+    Include,
 
     QReg,
     CReg,
@@ -16,6 +58,59 @@ pub enum TokenTy {
     Measure,
     Reset,
 
+    Gate,
+    Opaque,
+    Barrier,
+    If,
+
+    Qubit,
+    Bit,
+    Int,
+    UInt,
+    Float,
+    Angle,
+    Bool,
+    Complex,
+    DurationTy,
+    Stretch,
+    Array,
+    Void,
+    Const,
+    Input,
+    Output,
+    Let,
+    Mutable,
+    Readonly,
+    Def,
+    Defcal,
+    Defcalgrammar,
+    Cal,
+    Extern,
+    Return,
+    Else,
+    For,
+    While,
+    In,
+    Break,
+    Continue,
+    End,
+    Switch,
+    Case,
+    Default,
+    True,
+    False,
+    Delay,
+    Box,
+    Durationof,
+    Sizeof,
+    Ctrl,
+    NegCtrl,
+    Inv,
+    PowModifier,
+    GPhase,
+    Tau,
+    Euler,
+
     Pi,
     Sin,
     Cos,
@@ -28,9 +123,12 @@ pub enum TokenTy {
     RBrack,
     LPar,
     RPar,
+    LBrace,
+    RBrace,
 
     Semi,
     Comma,
+    Colon,
 
     Arrow,
 
@@ -39,61 +137,241 @@ pub enum TokenTy {
     Mul,
     Div,
     Pow,
+    Mod,
+    DoubleStar,
+    Tilde,
+    Bang,
+    At,
+    Assign,
+    EqEq,
+    NotEq,
+    Lt,
+    LtEq,
+    Gt,
+    GtEq,
+    Shl,
+    Shr,
+    Amp,
+    AmpAmp,
+    Pipe,
+    PipePipe,
+    PlusPlus,
+    PlusEq,
+    MinusEq,
+    MulEq,
+    DivEq,
+    ModEq,
+    DoubleStarEq,
+    PowEq,
+    AmpEq,
+    PipeEq,
+    ShlEq,
+    ShrEq,
+    TildeEq,
 
     Eof,
 }
 
-impl ToString for TokenTy {
-    fn to_string(&self) -> String {
+impl fmt::Display for TokenTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            TokenTy::OpenQASM => f.write_str("OPENQASM"),
+            TokenTy::Integer(n) => write!(f, "INTEGER({})", n),
+            TokenTy::Real(x) => write!(f, "REAL({})", x),
+            TokenTy::Imaginary(x) => write!(f, "IMAGINARY({})", x),
+            TokenTy::Duration(x, unit) => write!(f, "DURATION({}{})", x, unit),
+            TokenTy::Id(s) => write!(f, "ID({})", s),
+            TokenTy::Str(s) => write!(f, "STRING({})", s.escape_debug()),
+            TokenTy::BitString(s) => write!(f, "BIT_STRING({})", s),
+            TokenTy::Pragma(s) => write!(f, "PRAGMA({})", s.escape_debug()),
+            TokenTy::Include => f.write_str("INCLUDE"),
+            TokenTy::QReg => f.write_str("QREG"),
+            TokenTy::CReg => f.write_str("CREG"),
+            TokenTy::UGate => f.write_str("UGATE"),
+            TokenTy::CXGate => f.write_str("CXGATE"),
+            TokenTy::Measure => f.write_str("MEASURE"),
+            TokenTy::Reset => f.write_str("RESET"),
+            TokenTy::Gate => f.write_str("GATE"),
+            TokenTy::Opaque => f.write_str("OPAQUE"),
+            TokenTy::Barrier => f.write_str("BARRIER"),
+            TokenTy::If => f.write_str("IF"),
+            TokenTy::Qubit => f.write_str("QUBIT"),
+            TokenTy::Bit => f.write_str("BIT"),
+            TokenTy::Int => f.write_str("INT"),
+            TokenTy::UInt => f.write_str("UINT"),
+            TokenTy::Float => f.write_str("FLOAT"),
+            TokenTy::Angle => f.write_str("ANGLE"),
+            TokenTy::Bool => f.write_str("BOOL"),
+            TokenTy::Complex => f.write_str("COMPLEX"),
+            TokenTy::DurationTy => f.write_str("DURATION_TY"),
+            TokenTy::Stretch => f.write_str("STRETCH"),
+            TokenTy::Array => f.write_str("ARRAY"),
+            TokenTy::Void => f.write_str("VOID"),
+            TokenTy::Const => f.write_str("CONST"),
+            TokenTy::Input => f.write_str("INPUT"),
+            TokenTy::Output => f.write_str("OUTPUT"),
+            TokenTy::Let => f.write_str("LET"),
+            TokenTy::Mutable => f.write_str("MUTABLE"),
+            TokenTy::Readonly => f.write_str("READONLY"),
+            TokenTy::Def => f.write_str("DEF"),
+            TokenTy::Defcal => f.write_str("DEFCAL"),
+            TokenTy::Defcalgrammar => f.write_str("DEFCALGRAMMAR"),
+            TokenTy::Cal => f.write_str("CAL"),
+            TokenTy::Extern => f.write_str("EXTERN"),
+            TokenTy::Return => f.write_str("RETURN"),
+            TokenTy::Else => f.write_str("ELSE"),
+            TokenTy::For => f.write_str("FOR"),
+            TokenTy::While => f.write_str("WHILE"),
+            TokenTy::In => f.write_str("IN"),
+            TokenTy::Break => f.write_str("BREAK"),
+            TokenTy::Continue => f.write_str("CONTINUE"),
+            TokenTy::End => f.write_str("END"),
+            TokenTy::Switch => f.write_str("SWITCH"),
+            TokenTy::Case => f.write_str("CASE"),
+            TokenTy::Default => f.write_str("DEFAULT"),
+            TokenTy::True => f.write_str("TRUE"),
+            TokenTy::False => f.write_str("FALSE"),
+            TokenTy::Delay => f.write_str("DELAY"),
+            TokenTy::Box => f.write_str("BOX"),
+            TokenTy::Durationof => f.write_str("DURATIONOF"),
+            TokenTy::Sizeof => f.write_str("SIZEOF"),
+            TokenTy::Ctrl => f.write_str("CTRL"),
+            TokenTy::NegCtrl => f.write_str("NEGCTRL"),
+            TokenTy::Inv => f.write_str("INV"),
+            TokenTy::PowModifier => f.write_str("POW_MODIFIER"),
+            TokenTy::GPhase => f.write_str("GPHASE"),
+            TokenTy::Tau => f.write_str("TAU"),
+            TokenTy::Euler => f.write_str("EULER"),
+            TokenTy::Pi => f.write_str("PI"),
+            TokenTy::Sin => f.write_str("SIN"),
+            TokenTy::Cos => f.write_str("COS"),
+            TokenTy::Tan => f.write_str("TAN"),
+            TokenTy::Exp => f.write_str("EXP"),
+            TokenTy::Ln => f.write_str("LN"),
+            TokenTy::Sqrt => f.write_str("SQRT"),
+            TokenTy::LBrack => f.write_str("LBRACK"),
+            TokenTy::RBrack => f.write_str("RBRACK"),
+            TokenTy::LPar => f.write_str("LPAR"),
+            TokenTy::RPar => f.write_str("RPAR"),
+            TokenTy::LBrace => f.write_str("LBRACE"),
+            TokenTy::RBrace => f.write_str("RBRACE"),
+            TokenTy::Semi => f.write_str("SEMI"),
+            TokenTy::Comma => f.write_str("COMMA"),
+            TokenTy::Colon => f.write_str("COLON"),
+            TokenTy::Arrow => f.write_str("ARROW"),
+            TokenTy::Plus => f.write_str("PLUS"),
+            TokenTy::Minus => f.write_str("MINUS"),
+            TokenTy::Mul => f.write_str("MUL"),
+            TokenTy::Div => f.write_str("DIV"),
+            TokenTy::Pow => f.write_str("POW"),
+            TokenTy::Mod => f.write_str("MOD"),
+            TokenTy::DoubleStar => f.write_str("DOUBLE_STAR"),
+            TokenTy::Tilde => f.write_str("TILDE"),
+            TokenTy::Bang => f.write_str("BANG"),
+            TokenTy::At => f.write_str("AT"),
+            TokenTy::Assign => f.write_str("ASSIGN"),
+            TokenTy::EqEq => f.write_str("EQ_EQ"),
+            TokenTy::NotEq => f.write_str("NOT_EQ"),
+            TokenTy::Lt => f.write_str("LT"),
+            TokenTy::LtEq => f.write_str("LT_EQ"),
+            TokenTy::Gt => f.write_str("GT"),
+            TokenTy::GtEq => f.write_str("GT_EQ"),
+            TokenTy::Shl => f.write_str("SHL"),
+            TokenTy::Shr => f.write_str("SHR"),
+            TokenTy::Amp => f.write_str("AMP"),
+            TokenTy::AmpAmp => f.write_str("AMP_AMP"),
+            TokenTy::Pipe => f.write_str("PIPE"),
+            TokenTy::PipePipe => f.write_str("PIPE_PIPE"),
+            TokenTy::PlusPlus => f.write_str("PLUS_PLUS"),
+            TokenTy::PlusEq => f.write_str("PLUS_EQ"),
+            TokenTy::MinusEq => f.write_str("MINUS_EQ"),
+            TokenTy::MulEq => f.write_str("MUL_EQ"),
+            TokenTy::DivEq => f.write_str("DIV_EQ"),
+            TokenTy::ModEq => f.write_str("MOD_EQ"),
+            TokenTy::DoubleStarEq => f.write_str("DOUBLE_STAR_EQ"),
+            TokenTy::PowEq => f.write_str("POW_EQ"),
+            TokenTy::AmpEq => f.write_str("AMP_EQ"),
+            TokenTy::PipeEq => f.write_str("PIPE_EQ"),
+            TokenTy::ShlEq => f.write_str("SHL_EQ"),
+            TokenTy::ShrEq => f.write_str("SHR_EQ"),
+            TokenTy::TildeEq => f.write_str("TILDE_EQ"),
+            TokenTy::Eof => f.write_str("EOF"),
+        }
+    }
+}
+
+pub enum TriviaTy {
+    Whitespace,
+    LineComment,
+    BlockComment,
+}
+
+impl fmt::Display for TriviaTy {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            TokenTy::OpenQASM => String::from("OPENQASM"),
-            TokenTy::Integer(n) => format!("INTEGER({})", n),
-            TokenTy::Real(x) => format!("REAL({})", x),
-            TokenTy::Id(s) => format!("ID({})", s),
-            TokenTy::QReg => String::from("QREG"),
-            TokenTy::CReg => String::from("CREG"),
-            TokenTy::UGate => String::from("UGATE"),
-            TokenTy::CXGate => String::from("CXGATE"),
-            TokenTy::Measure => String::from("MEASURE"),
-            TokenTy::Reset => String::from("RESET"),
-            TokenTy::Pi => String::from("PI"),
-            TokenTy::Sin => String::from("SIN"),
-            TokenTy::Cos => String::from("COS"),
-            TokenTy::Tan => String::from("TAN"),
-            TokenTy::Exp => String::from("EXP"),
-            TokenTy::Ln => String::from("LN"),
-            TokenTy::Sqrt => String::from("SQRT"),
-            TokenTy::LBrack => String::from("LBRACK"),
-            TokenTy::RBrack => String::from("RBRACK"),
-            TokenTy::LPar => String::from("LPAR"),
-            TokenTy::RPar => String::from("RPAR"),
-            TokenTy::Semi => String::from("SEMI"),
-            TokenTy::Comma => String::from("COMMA"),
-            TokenTy::Arrow => String::from("ARROW"),
-            TokenTy::Plus => String::from("PLUS"),
-            TokenTy::Minus => String::from("MINUS"),
-            TokenTy::Mul => String::from("MUL"),
-            TokenTy::Div => String::from("DIV"),
-            TokenTy::Pow => String::from("POW"),
-            TokenTy::Eof => String::from("EOF"),
+            TriviaTy::Whitespace => f.write_str("WHITESPACE"),
+            TriviaTy::LineComment => f.write_str("LINE_COMMENT"),
+            TriviaTy::BlockComment => f.write_str("BLOCK_COMMENT"),
         }
     }
 }
 
+pub struct Trivia {
+    ty: TriviaTy,
+    text: String,
+    loc: Location,
+}
+
+impl fmt::Display for Trivia {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}({})@{}", self.ty, self.text.escape_debug(), self.loc)
+    }
+}
+
+impl Trivia {
+    pub fn new(ty: TriviaTy, text: String, loc: Location) -> Trivia {
+        Trivia { ty, text, loc }
+    }
+
+    pub fn get_ty(&self) -> &TriviaTy {
+        &self.ty
+    }
+
+    pub fn get_text(&self) -> &String {
+        &self.text
+    }
+
+    pub fn get_loc(&self) -> &Location {
+        &self.loc
+    }
+}
+
 pub struct Token {
     ty: TokenTy,
     loc: Location,
+    text: String,
+    leading: Vec<Trivia>,
 }
 
-impl ToString for Token {
-    fn to_string(&self) -> String {
-        self.ty.to_string() + "@" + &self.loc.to_string()
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}@{}", self.ty, self.loc)
     }
 }
 
 impl Token {
     pub fn new(ty: TokenTy, loc: Location) -> Token {
-        Token { ty: ty, loc: loc }
+        Token {
+            ty,
+            loc,
+            text: String::new(),
+            leading: Vec::new(),
+        }
+    }
+
+    pub(crate) fn attach(&mut self, text: String, leading: Vec<Trivia>) {
+        self.text = text;
+        self.leading = leading;
     }
 
     pub fn get_ty(&self) -> &TokenTy {
@@ -103,4 +381,12 @@ impl Token {
     pub fn get_loc(&self) -> &Location {
         &self.loc
     }
+
+    pub fn get_text(&self) -> &String {
+        &self.text
+    }
+
+    pub fn get_leading(&self) -> &Vec<Trivia> {
+        &self.leading
+    }
 }
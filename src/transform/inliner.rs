@@ -0,0 +1,247 @@
+use std::collections::HashMap;
+use std::vec::Vec;
+
+use crate::ast::node;
+
+use crate::typing::ty::Ty;
+
+use crate::utils::error::{CompoundError, ErrorTy, LocatedError};
+use crate::utils::location::Location;
+
+struct GateBody {
+    pars: Vec<String>,
+    args: Vec<String>,
+    body: Vec<node::Stmt>,
+}
+
+pub struct Inliner {
+    gates: HashMap<String, GateBody>,
+    error: CompoundError,
+}
+
+fn subst_exp(e: &node::Exp, pars: &HashMap<&str, &node::Exp>) -> node::Exp {
+    match e {
+        node::Exp::IdExp { name, .. } if pars.contains_key(name.as_str()) => {
+            pars[name.as_str()].clone()
+        }
+        node::Exp::BinopExp {
+            loc,
+            left,
+            op,
+            right,
+        } => node::Exp::BinopExp {
+            loc: loc.clone(),
+            left: Box::new(subst_exp(left, pars)),
+            op: op.clone(),
+            right: Box::new(subst_exp(right, pars)),
+        },
+        node::Exp::UnaryExp { loc, op, exp } => node::Exp::UnaryExp {
+            loc: loc.clone(),
+            op: op.clone(),
+            exp: Box::new(subst_exp(exp, pars)),
+        },
+        _ => e.clone(),
+    }
+}
+
+fn subst_reg(e: &node::Reg, args: &HashMap<&str, &node::Reg>) -> node::Reg {
+    match e {
+        node::Reg::SimpleReg { name, .. } if args.contains_key(name.as_str()) => {
+            args[name.as_str()].clone()
+        }
+        _ => e.clone(),
+    }
+}
+
+fn register_size(e: &node::Reg) -> Option<u64> {
+    match e {
+        node::Reg::SimpleReg { dec: Some(dec), .. } => match dec.get_ty() {
+            Ty::QRegTy(size) => Some(*size),
+            _ => None,
+        },
+        _ => None,
+    }
+}
+
+fn element(e: &node::Reg, index: u64) -> node::Reg {
+    match e {
+        node::Reg::SimpleReg { loc, name, dec } if register_size(e).is_some() => {
+            node::Reg::SubscriptReg {
+                loc: loc.clone(),
+                name: name.clone(),
+                index,
+                dec: dec.clone(),
+            }
+        }
+        _ => e.clone(),
+    }
+}
+
+impl Default for Inliner {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Inliner {
+    pub fn new() -> Inliner {
+        Inliner {
+            gates: HashMap::new(),
+            error: CompoundError::new(),
+        }
+    }
+
+    pub fn inline(&mut self, program: &mut node::Program) {
+        let mut starts: Vec<usize> = Vec::new();
+        for stmt in std::mem::take(&mut program.stmts) {
+            starts.push(program.stmts.len());
+            match stmt {
+                node::Stmt::DecStmt(node::Dec::GateDec {
+                    loc: _,
+                    name,
+                    pars,
+                    args,
+                    body: Some(body),
+                    dec: _,
+                }) => {
+                    let mut expanded: Vec<node::Stmt> = Vec::new();
+                    for stmt in body {
+                        self.expand(stmt, &mut expanded, Some(&name));
+                    }
+                    self.gates.insert(
+                        name,
+                        GateBody {
+                            pars,
+                            args,
+                            body: expanded,
+                        },
+                    );
+                }
+                stmt => self.expand(stmt, &mut program.stmts, None),
+            }
+        }
+
+        starts.push(program.stmts.len());
+        for include in program.includes.iter_mut() {
+            include.index = starts[include.index];
+        }
+        for pragma in program.pragmas.iter_mut() {
+            pragma.index = starts[pragma.index];
+        }
+    }
+
+    fn expand(&mut self, stmt: node::Stmt, out: &mut Vec<node::Stmt>, current: Option<&str>) {
+        let (loc, gate, pars, args, dec) = match &stmt {
+            node::Stmt::GateStmt {
+                loc,
+                gate,
+                pars,
+                args,
+                dec,
+            } => (loc, gate, pars, args, dec),
+            node::Stmt::IfStmt { .. } => return self.expand_if(stmt, out, current),
+            _ => return out.push(stmt),
+        };
+        if current == Some(gate.as_str()) {
+            self.error.add(LocatedError::new(
+                ErrorTy::Bind,
+                format!("recursive gate '{}'", gate),
+                loc.clone(),
+            ));
+            return;
+        }
+        let body = match self.gates.get(gate) {
+            Some(body) if body.pars.len() == pars.len() && body.args.len() == args.len() => body,
+            _ => return out.push(stmt),
+        };
+
+        // Whole registers are split into one call per element first, the
+        // way the lowerer broadcasts them, so the body runs once per index.
+        let sizes = args.iter().filter_map(register_size).collect::<Vec<u64>>();
+        if let Some(&width) = sizes.first() {
+            if sizes.iter().any(|size| *size != width) {
+                return out.push(stmt);
+            }
+            for index in 0..width {
+                let call = node::Stmt::GateStmt {
+                    loc: loc.clone(),
+                    gate: gate.clone(),
+                    pars: pars.clone(),
+                    args: args.iter().map(|arg| element(arg, index)).collect(),
+                    dec: dec.clone(),
+                };
+                self.expand(call, out, current);
+            }
+            return;
+        }
+
+        let pars = body
+            .pars
+            .iter()
+            .map(|par| par.as_str())
+            .zip(pars.iter())
+            .collect::<HashMap<&str, &node::Exp>>();
+        let args = body
+            .args
+            .iter()
+            .map(|arg| arg.as_str())
+            .zip(args.iter())
+            .collect::<HashMap<&str, &node::Reg>>();
+        let loc = Location::new_derived(format!("gate {}", gate), loc);
+        for it in body.body.iter() {
+            match it {
+                node::Stmt::GateStmt {
+                    loc: _,
+                    gate,
+                    pars: formal_pars,
+                    args: formal_args,
+                    dec,
+                } => out.push(node::Stmt::GateStmt {
+                    loc: loc.clone(),
+                    gate: gate.clone(),
+                    pars: formal_pars.iter().map(|e| subst_exp(e, &pars)).collect(),
+                    args: formal_args.iter().map(|e| subst_reg(e, &args)).collect(),
+                    dec: dec.clone(),
+                }),
+                node::Stmt::BarrierStmt {
+                    loc: _,
+                    args: formal_args,
+                } => out.push(node::Stmt::BarrierStmt {
+                    loc: loc.clone(),
+                    args: formal_args.iter().map(|e| subst_reg(e, &args)).collect(),
+                }),
+                _ => {}
+            }
+        }
+    }
+
+    fn expand_if(&mut self, stmt: node::Stmt, out: &mut Vec<node::Stmt>, current: Option<&str>) {
+        let (loc, reg, value, stmt) = match stmt {
+            node::Stmt::IfStmt {
+                loc,
+                reg,
+                value,
+                stmt,
+            } => (loc, reg, value, stmt),
+            stmt => return out.push(stmt),
+        };
+        let mut expanded: Vec<node::Stmt> = Vec::new();
+        self.expand(*stmt, &mut expanded, current);
+        for stmt in expanded {
+            out.push(node::Stmt::IfStmt {
+                loc: loc.clone(),
+                reg: reg.clone(),
+                value,
+                stmt: Box::new(stmt),
+            });
+        }
+    }
+
+    pub fn get_error(&self) -> &CompoundError {
+        &self.error
+    }
+
+    pub fn get_error_mut(&mut self) -> &mut CompoundError {
+        &mut self.error
+    }
+}
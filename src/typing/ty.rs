@@ -1,9 +1,14 @@
+use std::fmt;
+
 pub enum Ty {
     QubitTy,
-    QRegTy(u32),
+    QRegTy(u64),
     BitTy,
-    CRegTy(u32),
+    CRegTy(u64),
     GateTy(u32, u32),
+    IntTy,
+    RealTy,
+    AngleTy,
 }
 
 impl Clone for Ty {
@@ -14,18 +19,30 @@ impl Clone for Ty {
             Ty::BitTy => Ty::BitTy,
             Ty::CRegTy(size) => Ty::CRegTy(*size),
             Ty::GateTy(pars, args) => Ty::GateTy(*pars, *args),
+            Ty::IntTy => Ty::IntTy,
+            Ty::RealTy => Ty::RealTy,
+            Ty::AngleTy => Ty::AngleTy,
         }
     }
 }
 
-impl ToString for Ty {
-    fn to_string(&self) -> String {
+impl fmt::Display for Ty {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
         match self {
-            Ty::QubitTy => String::from("qubit_ty"),
-            Ty::QRegTy(size) => format!("qreg_ty[{}]", size),
-            Ty::BitTy => String::from("bit_ty"),
-            Ty::CRegTy(size) => format!("creg_ty[{}]", size),
-            Ty::GateTy(pars, args) => format!("gate_ty[{},{}]", pars, args),
+            Ty::QubitTy => f.write_str("qubit_ty"),
+            Ty::QRegTy(size) => write!(f, "qreg_ty[{}]", size),
+            Ty::BitTy => f.write_str("bit_ty"),
+            Ty::CRegTy(size) => write!(f, "creg_ty[{}]", size),
+            Ty::GateTy(pars, args) => write!(f, "gate_ty[{},{}]", pars, args),
+            Ty::IntTy => f.write_str("int_ty"),
+            Ty::RealTy => f.write_str("real_ty"),
+            Ty::AngleTy => f.write_str("angle_ty"),
         }
     }
 }
+
+impl Ty {
+    pub fn is_numeric(&self) -> bool {
+        matches!(self, Ty::IntTy | Ty::RealTy | Ty::AngleTy)
+    }
+}
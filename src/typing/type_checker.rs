@@ -1,9 +1,14 @@
+use std::rc::Rc;
+
 use crate::ast::node;
 use crate::ast::visitor::Visitor;
 
 use crate::bind::gate::GateDec;
 use crate::bind::reg::RegDec;
 
+use crate::eval::evaluator::Evaluator;
+use crate::eval::value::Value;
+
 use crate::typing::ty::Ty;
 
 use crate::utils::error::{CompoundError, ErrorTy, LocatedError, SimpleError};
@@ -15,7 +20,14 @@ pub struct TypeChecker {
 }
 
 impl Visitor for TypeChecker {
-    fn visit_dec(&mut self, _: &node::Dec) {}
+    fn visit_dec(&mut self, e: &node::Dec) {
+        if let node::Dec::GateDec {
+            body: Some(body), ..
+        } = e
+        {
+            body.iter().for_each(|stmt| self.visit_stmt(stmt));
+        }
+    }
 
     fn visit_reg(&mut self, e: &node::Reg) {
         match e {
@@ -45,7 +57,7 @@ impl Visitor for TypeChecker {
 
     fn visit_stmt(&mut self, e: &node::Stmt) {
         match e {
-            node::Stmt::DecStmt(_) => {}
+            node::Stmt::DecStmt(dec) => self.visit_dec(dec),
             node::Stmt::GateStmt {
                 loc,
                 gate: _,
@@ -53,17 +65,112 @@ impl Visitor for TypeChecker {
                 args,
                 dec,
             } => {
+                self.check_pars(dec, pars, loc);
                 if let Some(dec) = dec {
-                    self.check_gate(dec, pars, args, loc);
+                    self.check_gate(dec, args, loc);
                 }
                 self.check_args(args);
+                self.check_overlap(args);
             }
             node::Stmt::MeasureStmt { loc: _, src, dst } => self.check_measure(src, dst),
-            node::Stmt::ResetStmt { loc: _, reg } => self.check_reset(reg),
+            node::Stmt::ResetStmt { loc: _, reg } => self.check_quantum(reg),
+            node::Stmt::BarrierStmt { loc: _, args } => {
+                args.iter().for_each(|arg| self.check_quantum(arg));
+            }
+            node::Stmt::IfStmt {
+                loc: _,
+                reg,
+                value: _,
+                stmt,
+            } => {
+                self.check_condition(reg);
+                self.visit_stmt(stmt);
+            }
+        }
+    }
+
+    fn visit_exp(&mut self, e: &node::Exp) {
+        match e {
+            node::Exp::IntExp { .. } => self.ty = Ty::IntTy,
+            node::Exp::RealExp { .. } => self.ty = Ty::RealTy,
+            node::Exp::PiExp { .. } => self.ty = Ty::AngleTy,
+            node::Exp::IdExp { dec, .. } => {
+                self.ty = match dec {
+                    Some(dec) => dec.get_ty().clone(),
+                    None => Ty::AngleTy,
+                }
+            }
+            node::Exp::BinopExp {
+                loc: _,
+                left,
+                op,
+                right,
+            } => {
+                let left_ty = self.type_operand(left);
+                let right_ty = self.type_operand(right);
+                self.ty = match (left_ty, op, right_ty) {
+                    (Ty::IntTy, node::BinopTy::Div, Ty::IntTy) => Ty::RealTy,
+                    (Ty::IntTy, node::BinopTy::Pow, Ty::IntTy) if !is_natural(right) => Ty::RealTy,
+                    (Ty::IntTy, _, Ty::IntTy) => Ty::IntTy,
+                    (_, node::BinopTy::Pow, _) => Ty::RealTy,
+                    (Ty::AngleTy, node::BinopTy::Mul | node::BinopTy::Div, Ty::AngleTy) => {
+                        Ty::RealTy
+                    }
+                    (_, node::BinopTy::Div, Ty::AngleTy) => Ty::RealTy,
+                    (Ty::AngleTy, _, _) | (_, _, Ty::AngleTy) => Ty::AngleTy,
+                    _ => Ty::RealTy,
+                }
+            }
+            node::Exp::UnaryExp { loc: _, op, exp } => {
+                let ty = self.type_operand(exp);
+                self.ty = match op {
+                    node::UnaryOp::Minus => ty,
+                    _ => Ty::RealTy,
+                }
+            }
         }
     }
+}
 
-    fn visit_exp(&mut self, _: &node::Exp) {}
+/// Whether an integer expression is known to be non-negative, which is what
+/// keeps an integer raised to it an integer: `2^-1` is a real.
+fn is_natural(exp: &node::Exp) -> bool {
+    matches!(
+        Evaluator::new().eval(exp),
+        Some(Value::Rational(num, 1)) if num >= 0
+    )
+}
+
+fn operand_name(reg: &node::Reg) -> String {
+    match reg {
+        node::Reg::SimpleReg { name, .. } => name.clone(),
+        node::Reg::SubscriptReg { name, index, .. } => format!("{}[{}]", name, index),
+    }
+}
+
+fn overlaps(left: &node::Reg, right: &node::Reg) -> bool {
+    let dec = match (left.get_dec(), right.get_dec()) {
+        (Some(left), Some(right)) if Rc::ptr_eq(left, right) => left,
+        _ => return false,
+    };
+    match dec.get_ty() {
+        Ty::QubitTy => return true,
+        Ty::QRegTy(_) => {}
+        _ => return false,
+    }
+    match (left, right) {
+        (
+            node::Reg::SubscriptReg { index: left, .. },
+            node::Reg::SubscriptReg { index: right, .. },
+        ) => left == right,
+        _ => true,
+    }
+}
+
+impl Default for TypeChecker {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl TypeChecker {
@@ -74,7 +181,7 @@ impl TypeChecker {
         }
     }
 
-    fn access_register(&mut self, dec: &RegDec, index: u32, loc: &Location) -> Ty {
+    fn access_register(&mut self, dec: &RegDec, index: u64, loc: &Location) -> Ty {
         match dec.get_ty() {
             Ty::QubitTy => {
                 self.error.add(LocatedError::new(
@@ -112,7 +219,7 @@ impl TypeChecker {
                 }
                 Ty::BitTy
             }
-            Ty::GateTy(_, _) => {
+            Ty::GateTy(_, _) | Ty::IntTy | Ty::RealTy | Ty::AngleTy => {
                 self.error
                     .add(SimpleError::new(ErrorTy::Internal, "incoherent type"));
                 Ty::QubitTy
@@ -120,30 +227,19 @@ impl TypeChecker {
         }
     }
 
-    fn check_gate(
-        &mut self,
-        dec: &GateDec,
-        pars: &Vec<node::Exp>,
-        args: &Vec<node::Reg>,
-        loc: &Location,
-    ) {
+    fn check_gate(&mut self, dec: &GateDec, args: &[node::Reg], loc: &Location) {
         match dec.get_ty() {
-            Ty::QubitTy | Ty::QRegTy(_) | Ty::BitTy | Ty::CRegTy(_) => {
+            Ty::QubitTy
+            | Ty::QRegTy(_)
+            | Ty::BitTy
+            | Ty::CRegTy(_)
+            | Ty::IntTy
+            | Ty::RealTy
+            | Ty::AngleTy => {
                 self.error
                     .add(SimpleError::new(ErrorTy::Internal, "incoherent type"));
             }
-            Ty::GateTy(npars, nargs) => {
-                if *npars != pars.len() as u32 {
-                    self.error.add(LocatedError::new(
-                        ErrorTy::Type,
-                        format!(
-                            "invalid number of parameters, expected {} got {}",
-                            npars,
-                            pars.len()
-                        ),
-                        loc.clone(),
-                    ));
-                }
+            Ty::GateTy(_, nargs) => {
                 if *nargs != args.len() as u32 {
                     self.error.add(LocatedError::new(
                         ErrorTy::Type,
@@ -160,7 +256,7 @@ impl TypeChecker {
     }
 
     fn check_args(&mut self, args: &Vec<node::Reg>) {
-        let mut reg_size: Option<u32> = None;
+        let mut reg_size: Option<u64> = None;
         for arg in args {
             let ty = self.type_reg(arg);
             match ty {
@@ -183,7 +279,7 @@ impl TypeChecker {
                         arg.get_loc().clone(),
                     ));
                 }
-                Ty::GateTy(_, _) => {
+                Ty::GateTy(_, _) | Ty::IntTy | Ty::RealTy | Ty::AngleTy => {
                     self.error
                         .add(SimpleError::new(ErrorTy::Internal, "incoherent type"));
                 }
@@ -191,8 +287,24 @@ impl TypeChecker {
         }
     }
 
+    fn check_overlap(&mut self, args: &[node::Reg]) {
+        for (i, arg) in args.iter().enumerate() {
+            if let Some(other) = args[..i].iter().find(|other| overlaps(other, arg)) {
+                self.error.add(LocatedError::new(
+                    ErrorTy::Type,
+                    format!(
+                        "operand {} overlaps with operand {}",
+                        operand_name(arg),
+                        operand_name(other)
+                    ),
+                    arg.get_loc().clone(),
+                ));
+            }
+        }
+    }
+
     fn check_measure(&mut self, src: &node::Reg, dst: &node::Reg) {
-        let mut reg_size: Option<u32> = None;
+        let mut reg_size: Option<u64> = None;
         match self.type_reg(src) {
             Ty::QubitTy => {}
             Ty::QRegTy(size) => reg_size = Some(size),
@@ -203,7 +315,7 @@ impl TypeChecker {
                     src.get_loc().clone(),
                 ));
             }
-            Ty::GateTy(_, _) => {
+            Ty::GateTy(_, _) | Ty::IntTy | Ty::RealTy | Ty::AngleTy => {
                 self.error
                     .add(SimpleError::new(ErrorTy::Internal, "incoherent type"));
             }
@@ -242,14 +354,14 @@ impl TypeChecker {
                     ));
                 }
             },
-            Ty::GateTy(_, _) => {
+            Ty::GateTy(_, _) | Ty::IntTy | Ty::RealTy | Ty::AngleTy => {
                 self.error
                     .add(SimpleError::new(ErrorTy::Internal, "incoherent type"));
             }
         }
     }
 
-    fn check_reset(&mut self, reg: &node::Reg) {
+    fn check_quantum(&mut self, reg: &node::Reg) {
         match self.type_reg(reg) {
             Ty::QubitTy | Ty::QRegTy(_) => {}
             Ty::BitTy | Ty::CRegTy(_) => {
@@ -259,20 +371,85 @@ impl TypeChecker {
                     reg.get_loc().clone(),
                 ));
             }
-            Ty::GateTy(_, _) => {
+            Ty::GateTy(_, _) | Ty::IntTy | Ty::RealTy | Ty::AngleTy => {
                 self.error
                     .add(SimpleError::new(ErrorTy::Internal, "incoherent type"));
             }
         }
     }
 
+    fn check_pars(&mut self, dec: &Option<Rc<GateDec>>, pars: &[node::Exp], loc: &Location) {
+        if let Some(Ty::GateTy(npars, _)) = dec.as_ref().map(|dec| dec.get_ty())
+            && *npars != pars.len() as u32
+        {
+            self.error.add(LocatedError::new(
+                ErrorTy::Type,
+                format!(
+                    "invalid number of parameters, expected {} got {}",
+                    npars,
+                    pars.len()
+                ),
+                loc.clone(),
+            ));
+        }
+        for par in pars {
+            let ty = self.type_exp(par);
+            if !ty.is_numeric() {
+                self.error.add(LocatedError::new(
+                    ErrorTy::Type,
+                    format!("expected a numeric parameter got {}", ty),
+                    par.get_loc().clone(),
+                ));
+            }
+        }
+    }
+
+    fn check_condition(&mut self, reg: &node::Reg) {
+        match self.type_reg(reg) {
+            Ty::CRegTy(_) => {}
+            _ => {
+                self.error.add(LocatedError::new(
+                    ErrorTy::Type,
+                    "expected a creg",
+                    reg.get_loc().clone(),
+                ));
+            }
+        }
+    }
+
+    fn type_operand(&mut self, e: &node::Exp) -> Ty {
+        let ty = self.type_exp(e);
+        if ty.is_numeric() {
+            return ty;
+        }
+        self.error.add(LocatedError::new(
+            ErrorTy::Type,
+            format!("expected a numeric expression got {}", ty),
+            e.get_loc().clone(),
+        ));
+        Ty::RealTy
+    }
+
     fn type_reg(&mut self, e: &node::Reg) -> Ty {
         self.visit_reg(e);
         self.ty.clone()
     }
 
-    pub fn check_types(&mut self, program: &Vec<node::Stmt>) {
-        program.iter().for_each(|x| self.visit_stmt(x));
+    pub fn check_types(&mut self, program: &node::Program) {
+        program.stmts.iter().for_each(|x| self.visit_stmt(x));
+    }
+
+    pub fn check_stmt(&mut self, stmt: &node::Stmt) {
+        self.visit_stmt(stmt);
+    }
+
+    pub fn check_exp(&mut self, exp: &node::Exp) {
+        self.visit_exp(exp);
+    }
+
+    pub fn type_exp(&mut self, e: &node::Exp) -> Ty {
+        self.visit_exp(e);
+        self.ty.clone()
     }
 
     pub fn get_error(&self) -> &CompoundError {
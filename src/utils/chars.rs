@@ -7,31 +7,23 @@ pub fn is_alpha(c: char) -> bool {
 }
 
 pub fn is_bracket(c: char) -> bool {
-    match c {
-        '[' => true,
-        ']' => true,
-        '(' => true,
-        ')' => true,
-        _ => false,
-    }
+    matches!(c, '[' | ']' | '(' | ')' | '{' | '}')
 }
 
 pub fn is_digit(c: char) -> bool {
-    match c {
-        '0'..='9' => true,
-        _ => false,
-    }
+    c.is_ascii_digit()
 }
 
 pub fn is_id(c: char) -> bool {
     is_alnum(c) || c == '_'
 }
 
+pub fn is_id_start(c: char) -> bool {
+    is_alpha(c) || c == '_'
+}
+
 pub fn is_lower(c: char) -> bool {
-    match c {
-        'a'..='z' => true,
-        _ => false,
-    }
+    c.is_ascii_lowercase()
 }
 
 pub fn is_number(c: char) -> bool {
@@ -39,36 +31,36 @@ pub fn is_number(c: char) -> bool {
 }
 
 pub fn is_operator(c: char) -> bool {
-    match c {
-        '+' => true,
-        '-' => true,
-        '*' => true,
-        '/' => true,
-        '^' => true,
-        _ => false,
-    }
+    matches!(
+        c,
+        '+' | '-' | '*' | '/' | '^' | '%' | '~' | '!' | '=' | '@' | '&' | '|' | '<' | '>'
+    )
+}
+
+pub fn is_pragma(c: char) -> bool {
+    c == '#'
 }
 
 pub fn is_punct(c: char) -> bool {
-    match c {
-        ',' => true,
-        ';' => true,
-        _ => false,
-    }
+    matches!(c, ',' | ';' | ':')
+}
+
+pub fn is_quote(c: char) -> bool {
+    c == '"' || c == '\''
+}
+
+pub fn is_radix(c: char) -> bool {
+    matches!(c, 'x' | 'X' | 'o' | 'O' | 'b' | 'B')
 }
 
 pub fn is_space(c: char) -> bool {
-    match c {
-        ' ' => true,
-        '\n' => true,
-        '\t' => true,
-        _ => false,
-    }
+    matches!(c, ' ' | '\n' | '\r' | '\t' | '\x0b' | '\x0c')
+}
+
+pub fn is_unit(c: char) -> bool {
+    is_alpha(c) || c == 'µ'
 }
 
 pub fn is_upper(c: char) -> bool {
-    match c {
-        'A'..='Z' => true,
-        _ => false,
-    }
+    c.is_ascii_uppercase()
 }
@@ -7,6 +7,9 @@ pub enum ErrorTy {
     Parse,
     Bind,
     Type,
+    Eval,
+    Route,
+    Translate,
 }
 
 impl Clone for ErrorTy {
@@ -18,6 +21,9 @@ impl Clone for ErrorTy {
             Self::Parse => Self::Parse,
             Self::Bind => Self::Bind,
             Self::Type => Self::Type,
+            Self::Eval => Self::Eval,
+            Self::Route => Self::Route,
+            Self::Translate => Self::Translate,
         }
     }
 }
@@ -31,6 +37,9 @@ impl ErrorTy {
             ErrorTy::Parse => 3,
             ErrorTy::Bind => 4,
             ErrorTy::Type => 5,
+            ErrorTy::Eval => 6,
+            ErrorTy::Route => 7,
+            ErrorTy::Translate => 8,
         }
     }
 }
@@ -62,7 +71,7 @@ impl Error for SimpleError {
 impl SimpleError {
     pub fn new<T: ToString>(ty: ErrorTy, desc: T) -> SimpleError {
         SimpleError {
-            ty: ty,
+            ty,
             desc: desc.to_string(),
         }
     }
@@ -80,16 +89,16 @@ impl Error for LocatedError {
     }
 
     fn get_desc(&self) -> String {
-        format!("{} at {}", self.desc, self.loc.to_string())
+        format!("{} at {}", self.desc, self.loc)
     }
 }
 
 impl LocatedError {
     pub fn new<T: ToString>(ty: ErrorTy, desc: T, loc: Location) -> LocatedError {
         LocatedError {
-            ty: ty,
+            ty,
             desc: desc.to_string(),
-            loc: loc,
+            loc,
         }
     }
 }
@@ -116,6 +125,12 @@ impl Error for CompoundError {
     }
 }
 
+impl Default for CompoundError {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
 impl CompoundError {
     pub fn new() -> CompoundError {
         CompoundError { errors: Vec::new() }
@@ -1,93 +1,282 @@
-pub struct Location {
-    file: String,
-    line_start: u64,
-    column_start: u64,
-    line_end: u64,
-    column_end: u64,
+use std::fmt;
+
+use crate::utils::source_map::FileId;
+
+pub enum Location {
+    Source {
+        file: FileId,
+        line_start: u64,
+        column_start: u64,
+        line_end: u64,
+        column_end: u64,
+        offset_start: u64,
+        offset_end: u64,
+    },
+    Synthetic {
+        origin: Option<(String, Box<Location>)>,
+    },
 }
 
-impl ToString for Location {
-    fn to_string(&self) -> String {
-        if self.line_start == self.line_end {
-            if self.column_start == self.column_end {
-                self.file.clone()
-                    + ":"
-                    + &self.line_start.to_string()
-                    + ":"
-                    + &self.column_start.to_string()
-            } else {
-                self.file.clone()
-                    + ":"
-                    + &self.line_start.to_string()
-                    + ":"
-                    + &self.column_start.to_string()
-                    + "-"
-                    + &self.column_end.to_string()
+impl fmt::Display for Location {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            Location::Source {
+                file,
+                line_start,
+                column_start,
+                line_end,
+                column_end,
+                ..
+            } => {
+                if line_start == line_end {
+                    if column_start == column_end {
+                        write!(f, "{}:{}:{}", file, line_start, column_start)
+                    } else {
+                        write!(f, "{}:{}:{}-{}", file, line_start, column_start, column_end)
+                    }
+                } else {
+                    write!(
+                        f,
+                        "{}:{}:{}-{}:{}",
+                        file, line_start, column_start, line_end, column_end
+                    )
+                }
             }
-        } else {
-            self.file.clone()
-                + ":"
-                + &self.line_start.to_string()
-                + ":"
-                + &self.column_start.to_string()
-                + "-"
-                + &self.line_end.to_string()
-                + ":"
-                + &self.column_end.to_string()
+            Location::Synthetic { origin: None } => f.write_str("<synthetic>"),
+            Location::Synthetic {
+                origin: Some((what, loc)),
+            } => write!(f, "<synthetic>, expanded from {} at {}", what, loc),
         }
     }
 }
 
 impl Clone for Location {
     fn clone(&self) -> Location {
-        Location {
-            file: self.file.clone(),
-            line_start: self.line_start,
-            column_start: self.column_start,
-            line_end: self.line_end,
-            column_end: self.column_end,
+        match self {
+            Location::Source {
+                file,
+                line_start,
+                column_start,
+                line_end,
+                column_end,
+                offset_start,
+                offset_end,
+            } => Location::Source {
+                file: file.clone(),
+                line_start: *line_start,
+                column_start: *column_start,
+                line_end: *line_end,
+                column_end: *column_end,
+                offset_start: *offset_start,
+                offset_end: *offset_end,
+            },
+            Location::Synthetic { origin } => Location::Synthetic {
+                origin: origin
+                    .as_ref()
+                    .map(|(what, loc)| (what.clone(), loc.clone())),
+            },
         }
     }
 }
 
 impl Location {
     pub fn new(
-        file: String,
+        file: FileId,
         line_start: u64,
         column_start: u64,
+        offset_start: u64,
         line_end: u64,
         column_end: u64,
+        offset_end: u64,
     ) -> Location {
-        Location {
-            file: file,
-            line_start: line_start,
-            column_start: column_start,
-            line_end: line_end,
-            column_end: column_end,
+        Location::Source {
+            file,
+            line_start,
+            column_start,
+            line_end,
+            column_end,
+            offset_start,
+            offset_end,
+        }
+    }
+
+    pub fn new_point(file: FileId, line: u64, column: u64, offset: u64) -> Location {
+        Location::Source {
+            file,
+            line_start: line,
+            column_start: column,
+            line_end: line,
+            column_end: column,
+            offset_start: offset,
+            offset_end: offset,
+        }
+    }
+
+    pub fn new_synthetic() -> Location {
+        Location::Synthetic { origin: None }
+    }
+
+    pub fn new_derived<T: ToString>(what: T, origin: &Location) -> Location {
+        Location::Synthetic {
+            origin: Some((what.to_string(), Box::new(origin.clone()))),
+        }
+    }
+
+    pub fn is_synthetic(&self) -> bool {
+        match self {
+            Location::Source { .. } => false,
+            Location::Synthetic { .. } => true,
+        }
+    }
+
+    pub fn get_source(&self) -> Option<&Location> {
+        match self {
+            Location::Source { .. } => Some(self),
+            Location::Synthetic { origin: None } => None,
+            Location::Synthetic {
+                origin: Some((_, loc)),
+            } => loc.get_source(),
+        }
+    }
+
+    pub fn get_file(&self) -> Option<&FileId> {
+        match self {
+            Location::Source { file, .. } => Some(file),
+            Location::Synthetic { .. } => None,
+        }
+    }
+
+    pub fn get_span(&self) -> Option<(u64, u64)> {
+        match self {
+            Location::Source {
+                offset_start,
+                offset_end,
+                ..
+            } => Some((*offset_start, *offset_end)),
+            Location::Synthetic { .. } => None,
+        }
+    }
+
+    pub fn shift(&mut self, line: u64, offset_delta: i64, line_delta: i64, column_delta: i64) {
+        if let Location::Source {
+            line_start,
+            column_start,
+            line_end,
+            column_end,
+            offset_start,
+            offset_end,
+            ..
+        } = self
+        {
+            if *line_start == line {
+                *column_start = column_start.saturating_add_signed(column_delta);
+            }
+            if *line_end == line {
+                *column_end = column_end.saturating_add_signed(column_delta);
+            }
+            *line_start = line_start.saturating_add_signed(line_delta);
+            *line_end = line_end.saturating_add_signed(line_delta);
+            *offset_start = offset_start.saturating_add_signed(offset_delta);
+            *offset_end = offset_end.saturating_add_signed(offset_delta);
         }
     }
 
     pub fn start_to_end(&mut self) {
-        self.line_start = self.line_end;
-        self.column_start = self.column_end;
+        if let Location::Source {
+            line_start,
+            column_start,
+            line_end,
+            column_end,
+            offset_start,
+            offset_end,
+            ..
+        } = self
+        {
+            *line_start = *line_end;
+            *column_start = *column_end;
+            *offset_start = *offset_end;
+        }
     }
 
-    pub fn step_line(&mut self) {
-        self.line_end += 1;
-        self.column_end = 0;
+    pub fn step_line(&mut self, width: u64) {
+        if let Location::Source {
+            line_end,
+            column_end,
+            offset_end,
+            ..
+        } = self
+        {
+            *line_end += 1;
+            *column_end = 0;
+            *offset_end += width;
+        }
     }
 
-    pub fn step_column(&mut self) {
-        self.column_end += 1;
+    pub fn step_column(&mut self, width: u64) {
+        if let Location::Source {
+            column_end,
+            offset_end,
+            ..
+        } = self
+        {
+            *column_end += 1;
+            *offset_end += width;
+        }
+    }
+
+    pub fn step_tab(&mut self, width: u64, tab_width: u64) {
+        if let Location::Source {
+            column_end,
+            offset_end,
+            ..
+        } = self
+        {
+            *column_end = (*column_end / tab_width + 1) * tab_width;
+            *offset_end += width;
+        }
     }
 
     pub fn end_to_start(&mut self, next: &Location) {
-        self.line_end = next.line_start;
-        self.column_end = next.column_start;
+        if let (
+            Location::Source {
+                line_end,
+                column_end,
+                offset_end,
+                ..
+            },
+            Location::Source {
+                line_start,
+                column_start,
+                offset_start,
+                ..
+            },
+        ) = (self, next)
+        {
+            *line_end = *line_start;
+            *column_end = *column_start;
+            *offset_end = *offset_start;
+        }
     }
 
     pub fn end_to_end(&mut self, next: &Location) {
-        self.line_end = next.line_end;
-        self.column_end = next.column_end;
+        if let (
+            Location::Source {
+                line_end,
+                column_end,
+                offset_end,
+                ..
+            },
+            Location::Source {
+                line_end: next_line_end,
+                column_end: next_column_end,
+                offset_end: next_offset_end,
+                ..
+            },
+        ) = (self, next)
+        {
+            *line_end = *next_line_end;
+            *column_end = *next_column_end;
+            *offset_end = *next_offset_end;
+        }
     }
 }
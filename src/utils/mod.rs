@@ -1,3 +1,4 @@
 pub mod chars;
 pub mod error;
 pub mod location;
+pub mod source_map;
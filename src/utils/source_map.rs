@@ -0,0 +1,104 @@
+use std::cell::RefCell;
+use std::fmt;
+use std::rc::Rc;
+
+struct SourceFile {
+    name: String,
+    text: RefCell<Vec<u8>>,
+}
+
+/// A handle on a loaded file. Every `Location` in the file holds one, so the
+/// name and the text of the file live exactly as long as something refers to
+/// them.
+pub struct FileId(Rc<SourceFile>);
+
+impl Clone for FileId {
+    fn clone(&self) -> FileId {
+        FileId(Rc::clone(&self.0))
+    }
+}
+
+impl PartialEq for FileId {
+    fn eq(&self, other: &FileId) -> bool {
+        Rc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+impl fmt::Display for FileId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(&self.0.name)
+    }
+}
+
+impl FileId {
+    pub fn new(name: String) -> FileId {
+        FileId(Rc::new(SourceFile {
+            name,
+            text: RefCell::new(Vec::new()),
+        }))
+    }
+
+    pub(crate) fn append(&self, bytes: &[u8]) {
+        self.0.text.borrow_mut().extend_from_slice(bytes);
+    }
+
+    pub(crate) fn replace(&self, start: u64, end: u64, bytes: &[u8]) {
+        self.0
+            .text
+            .borrow_mut()
+            .splice(start as usize..end as usize, bytes.iter().copied());
+    }
+
+    pub fn get_name(&self) -> &str {
+        &self.0.name
+    }
+
+    pub fn get_text(&self) -> String {
+        String::from_utf8_lossy(&self.0.text.borrow()).into_owned()
+    }
+
+    pub fn get_slice(&self, start: u64, end: u64) -> Option<Vec<u8>> {
+        self.0
+            .text
+            .borrow()
+            .get(start as usize..end as usize)
+            .map(|slice| slice.to_vec())
+    }
+}
+
+/// The files loaded by a tool that works on several of them at once, such as
+/// the documents open in an editor. Dropping a file from the map releases it
+/// once no location refers to it any more.
+pub struct SourceMap {
+    files: Vec<FileId>,
+}
+
+impl Default for SourceMap {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl SourceMap {
+    pub fn new() -> SourceMap {
+        SourceMap { files: Vec::new() }
+    }
+
+    pub fn add(&mut self, file: FileId) {
+        if !self.files.contains(&file) {
+            self.files.push(file);
+        }
+    }
+
+    pub fn remove(&mut self, file: &FileId) {
+        self.files.retain(|it| it != file);
+    }
+
+    pub fn find(&self, name: &str) -> Option<&FileId> {
+        self.files.iter().find(|file| file.get_name() == name)
+    }
+
+    pub fn get_files(&self) -> &[FileId] {
+        &self.files
+    }
+}
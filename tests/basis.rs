@@ -0,0 +1,158 @@
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::bind::binder::Binder;
+use openqasm_parser::circuit::basis::{Basis, Translator};
+use openqasm_parser::circuit::ir::{Circuit, Operation};
+use openqasm_parser::circuit::lower::Lowerer;
+use openqasm_parser::circuit::unitary::{self, Matrix};
+use openqasm_parser::parse::parser;
+use openqasm_parser::transform::inliner::Inliner;
+use openqasm_parser::utils::error::Error;
+
+const BASES: [&str; 3] = ["rz,sx,x,cx", "rx,ry,rz,cz", "u3,cx"];
+
+fn lower(input: &str) -> Circuit {
+    let mut program = parser::parse_str("<test>", input).ok().unwrap();
+    Inliner::new().inline(&mut program);
+    Lowerer::new().lower(&program).unwrap()
+}
+
+fn translate(input: &str, basis: &str) -> Result<String, String> {
+    let mut translator = Translator::new(Basis::parse(basis).ok().unwrap());
+    match translator.translate(&lower(input)) {
+        Some(circuit) => Ok(PrettyPrinter::new().pretty_print(&circuit.to_program())),
+        None => Err(translator.get_error().get_desc()),
+    }
+}
+
+fn unitary_of(circuit: &Circuit) -> Matrix {
+    let mut matrix = Matrix::identity(1 << circuit.num_qubits());
+    for op in circuit.ops.iter() {
+        matrix = match op {
+            Operation::Gate {
+                name,
+                params,
+                qubits,
+            } if name == "U" => {
+                matrix.apply(&unitary::u_matrix(params[0], params[1], params[2]), qubits)
+            }
+            Operation::Gate { name, qubits, .. } if name == "CX" => {
+                matrix.apply(&unitary::cx_matrix(), qubits)
+            }
+            _ => panic!("unexpected operation"),
+        }
+    }
+    matrix
+}
+
+fn assert_equivalent(input: &str) {
+    let expected = unitary_of(&lower(input));
+    for basis in BASES {
+        let output = translate(input, basis).unwrap();
+        let mut program = parser::parse_str("<output>", &output).ok().unwrap();
+        let mut binder = Binder::new();
+        binder.bind(&mut program);
+        assert!(binder.get_error().empty(), "{}", output);
+        assert!(
+            unitary_of(&lower(&output)).equivalent(&expected),
+            "{}",
+            output
+        );
+    }
+}
+
+#[test]
+fn single_qubit_gates_are_decomposed() {
+    assert_equivalent(
+        "OPENQASM 2.0;\n\
+         qreg q[1];\n\
+         U(0.3, -1.2, 2.5) q[0];\n\
+         U(pi/2, 0.4, 0.9) q[0];\n\
+         U(pi, 1.1, -0.2) q[0];\n\
+         U(0, 0.7, 0.1) q[0];\n\
+         U(-2.2, 3.0, 4.0) q[0];\n",
+    );
+}
+
+#[test]
+fn entanglers_are_decomposed() {
+    assert_equivalent(
+        "OPENQASM 2.0;\n\
+         gate h a { U(pi/2, 0, pi) a; }\n\
+         qreg q[3];\n\
+         h q[0];\n\
+         CX q[0], q[1];\n\
+         U(1.0, 2.0, 3.0) q[2];\n\
+         CX q[2], q[0];\n\
+         CX q[1], q[2];\n",
+    );
+}
+
+#[test]
+fn output_declares_the_basis() {
+    assert_eq!(
+        translate(
+            "OPENQASM 2.0;\n\
+             qreg q[1];\n\
+             creg c[1];\n\
+             U(0, 0, pi/4) q[0];\n\
+             measure q[0] -> c[0];\n\
+             if (c == 1) U(pi, 0, pi) q[0];\n",
+            "rz,sx,x,cx",
+        )
+        .unwrap(),
+        "OPENQASM 2.0;\n\
+         gate rz(phi) a {\n  U (0, 0, phi) a;\n}\n\
+         gate sx a {\n  U ((pi / 2), (-(pi) / 2), (pi / 2)) a;\n}\n\
+         gate x a {\n  U (pi, 0, pi) a;\n}\n\
+         gate cx c, t {\n  CX c, t;\n}\n\
+         qreg q[1];\n\
+         creg c[1];\n\
+         rz ((pi / 4)) q[0];\n\
+         measure q[0] -> c[0];\n\
+         if (c == 1) x q[0];\n"
+    );
+}
+
+#[test]
+fn includes_are_dropped() {
+    let output = translate(
+        "OPENQASM 2.0;\n\
+         include \"qelib1.inc\";\n\
+         qreg q[2];\n\
+         CX q[0], q[1];\n",
+        "rz,sx,x,cx",
+    )
+    .unwrap();
+    assert!(!output.contains("include"), "{}", output);
+    assert!(output.contains("gate cx c, t {"), "{}", output);
+}
+
+#[test]
+fn bases_are_validated() {
+    assert!(Basis::parse(" u3 , cx ").is_ok());
+    assert_eq!(
+        Basis::parse("rz,sx,ccx").err().unwrap().get_desc(),
+        "unknown basis gate 'ccx'"
+    );
+    assert_eq!(
+        Basis::parse("rz,x,cx").err().unwrap().get_desc(),
+        "the basis cannot express every single-qubit gate"
+    );
+    assert_eq!(
+        Basis::parse("u3").err().unwrap().get_desc(),
+        "the basis has no two-qubit entangling gate"
+    );
+}
+
+#[test]
+fn opaque_gates_are_reported() {
+    assert_eq!(
+        translate(
+            "OPENQASM 2.0;\nopaque magic a;\nqreg q[1];\nmagic q[0];\n",
+            "u3,cx"
+        )
+        .err()
+        .unwrap(),
+        "cannot translate gate 'magic' to the basis"
+    );
+}
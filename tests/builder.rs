@@ -0,0 +1,57 @@
+use openqasm_parser::ast::builder::{Circuit, IntoExp, bit, pi};
+use openqasm_parser::ast::node;
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::eval::evaluator::Evaluator;
+use openqasm_parser::utils::error::Error;
+
+use openqasm_parser_macros::{exp, qasm};
+
+fn value(exp: &node::Exp) -> f64 {
+    let mut evaluator = Evaluator::new();
+    evaluator.eval(exp).unwrap().to_f64()
+}
+
+#[test]
+fn circuits_build_bound_programs() {
+    let program = Circuit::new()
+        .qreg("q", 2)
+        .creg("c", 2)
+        .u(exp!(pi / 2), 0, pi, ("q", 0))
+        .cx(("q", 0), bit("q", 1))
+        .measure("q", "c")
+        .build()
+        .ok()
+        .unwrap();
+    assert_eq!(program.stmts.len(), 5);
+    let text = PrettyPrinter::new().pretty_print(&program);
+    assert!(text.contains("((pi / 2), 0, pi) q"), "{}", text);
+}
+
+#[test]
+fn circuits_report_binding_and_type_errors() {
+    let error = Circuit::new().qreg("q", 1).cx(("q", 0), ("r", 0)).build();
+    assert_eq!(error.err().unwrap().get_exit_code(), 4);
+
+    let error = Circuit::new().qreg("q", 1).cx(("q", 0), ("q", 1)).build();
+    assert_eq!(error.err().unwrap().get_exit_code(), 5);
+}
+
+#[test]
+fn numbers_convert_to_expressions() {
+    assert_eq!(value(&exp!(pi / 4)), std::f64::consts::FRAC_PI_4);
+    assert_eq!(value(&(-3).into_exp()), -3.0);
+    assert_eq!(value(&0.5f32.into_exp()), 0.5);
+    assert_eq!(value(&(-1.25f64).into_exp()), -1.25);
+}
+
+#[test]
+fn programs_are_checked_when_the_macro_expands() {
+    let program = qasm! {
+        OPENQASM 2.0;
+        qreg q[2];
+        creg c[2];
+        CX q[0], q[1];
+        measure q -> c;
+    };
+    assert_eq!(program.stmts.len(), 4);
+}
@@ -0,0 +1,186 @@
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::bind::binder::Binder;
+use openqasm_parser::circuit::ir::{Circuit, Operation};
+use openqasm_parser::circuit::lower::Lowerer;
+use openqasm_parser::parse::parser;
+use openqasm_parser::typing::type_checker::TypeChecker;
+use openqasm_parser::utils::error::Error;
+
+fn check(input: &str) -> openqasm_parser::ast::node::Program {
+    let mut program = parser::parse_str("<test>", input).ok().unwrap();
+    let mut binder = Binder::new();
+    binder.bind(&mut program);
+    assert!(
+        binder.get_error().empty(),
+        "{}",
+        binder.get_error().get_desc()
+    );
+    let mut type_checker = TypeChecker::new();
+    type_checker.check_types(&program);
+    assert!(
+        type_checker.get_error().empty(),
+        "{}",
+        type_checker.get_error().get_desc()
+    );
+    program
+}
+
+fn lower(input: &str) -> Circuit {
+    let program = check(input);
+    let mut lowerer = Lowerer::new();
+    let circuit = lowerer.lower(&program);
+    assert!(
+        lowerer.get_error().empty(),
+        "{}",
+        lowerer.get_error().get_desc()
+    );
+    circuit.unwrap()
+}
+
+fn print(circuit: &Circuit) -> String {
+    let output = PrettyPrinter::new().pretty_print(&circuit.to_program());
+    check(&output);
+    output
+}
+
+#[test]
+fn registers_are_laid_out_by_offset() {
+    let circuit = lower(
+        "OPENQASM 2.0;\n\
+         qreg a[2];\n\
+         creg c[3];\n\
+         qreg b[3];\n\
+         CX a[1], b[2];\n\
+         measure b[0] -> c[2];\n",
+    );
+    assert_eq!(circuit.num_qubits(), 5);
+    assert_eq!(circuit.num_clbits(), 3);
+    assert_eq!(circuit.qregs[1].offset, 2);
+    assert_eq!(circuit.cregs[0].offset, 0);
+    match &circuit.ops[..] {
+        [
+            Operation::Gate { name, qubits, .. },
+            Operation::Measure { qubit: 2, clbit: 2 },
+        ] => {
+            assert_eq!(name, "CX");
+            assert_eq!(qubits, &vec![1, 4]);
+        }
+        _ => panic!("unexpected operations"),
+    }
+}
+
+#[test]
+fn whole_registers_are_broadcast() {
+    let circuit = lower(
+        "OPENQASM 2.0;\n\
+         qreg q[2];\n\
+         qreg r[2];\n\
+         creg c[2];\n\
+         CX q[0], r;\n\
+         reset q;\n\
+         measure r -> c;\n",
+    );
+    assert_eq!(
+        print(&circuit),
+        "OPENQASM 2.0;\n\
+         qreg q[2];\n\
+         qreg r[2];\n\
+         creg c[2];\n\
+         CX q[0], r[0];\n\
+         CX q[0], r[1];\n\
+         reset q[0];\n\
+         reset q[1];\n\
+         measure r[0] -> c[0];\n\
+         measure r[1] -> c[1];\n"
+    );
+}
+
+#[test]
+fn parameters_are_evaluated() {
+    let circuit = lower(
+        "OPENQASM 2.0;\n\
+         qreg q[1];\n\
+         U(pi/2, -1/4, 2*3) q[0];\n",
+    );
+    match &circuit.ops[0] {
+        Operation::Gate { params, .. } => {
+            assert_eq!(params, &vec![std::f64::consts::FRAC_PI_2, -0.25, 6.0])
+        }
+        _ => panic!("expected a gate"),
+    }
+    assert_eq!(
+        print(&circuit),
+        "OPENQASM 2.0;\n\
+         qreg q[1];\n\
+         U ((pi / 2), -(0.25), 6.0) q[0];\n"
+    );
+}
+
+#[test]
+fn user_gates_keep_their_bodies() {
+    let circuit = lower(
+        "OPENQASM 2.0;\n\
+         gate h a { U(pi/2, 0, pi) a; }\n\
+         qreg q[1];\n\
+         h q[0];\n",
+    );
+    assert_eq!(
+        circuit.gates[0].body.as_ref().map(|body| body.len()),
+        Some(1)
+    );
+    let output = print(&circuit);
+    assert!(output.contains("gate h a {\n  U"), "{}", output);
+}
+
+#[test]
+fn barriers_and_conditionals_are_kept() {
+    let circuit = lower(
+        "OPENQASM 2.0;\n\
+         include \"qelib1.inc\";\n\
+         opaque h a;\n\
+         qreg q[2];\n\
+         creg c[2];\n\
+         barrier q[1], q;\n\
+         if (c == 2) h q;\n",
+    );
+    match &circuit.ops[..] {
+        [
+            Operation::Barrier { qubits },
+            Operation::Conditional {
+                creg: 0,
+                value: 2,
+                op,
+            },
+            _,
+        ] => {
+            assert_eq!(qubits, &vec![1, 0]);
+            assert_eq!(op.get_qubits(), vec![0]);
+            assert_eq!(circuit.get_clbits(&circuit.ops[1]), vec![0, 1]);
+        }
+        _ => panic!("unexpected operations"),
+    }
+    assert_eq!(
+        print(&circuit),
+        "OPENQASM 2.0;\n\
+         include \"qelib1.inc\";\n\
+         opaque h a;\n\
+         qreg q[2];\n\
+         creg c[2];\n\
+         barrier q[1], q[0];\n\
+         if (c == 2) h q[0];\n\
+         if (c == 2) h q[1];\n"
+    );
+}
+
+#[test]
+fn undeclared_registers_are_reported() {
+    let program = parser::parse_str("<test>", "OPENQASM 2.0;\nqreg q[1];\nreset r;\n")
+        .ok()
+        .unwrap();
+    let mut lowerer = Lowerer::new();
+    assert!(lowerer.lower(&program).is_none());
+    assert_eq!(
+        lowerer.get_error().get_desc(),
+        "undeclared register 'r' at <test>:2:6-7"
+    );
+}
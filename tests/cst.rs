@@ -0,0 +1,312 @@
+use std::fs;
+use std::path::Path;
+
+use openqasm_parser::ast::node;
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::ast::visitor::Visitor;
+use openqasm_parser::cst::rewrite;
+use openqasm_parser::cst::syntax::{SyntaxKind, SyntaxNode};
+use openqasm_parser::cst::view::{self, Stmt};
+use openqasm_parser::parse::parser;
+use openqasm_parser::parse::token::Token;
+
+#[test]
+fn concrete_syntax_tree_regenerates_the_source() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testsuite/files");
+    for category in ["good", "measure", "bind", "type", "eval"] {
+        for entry in fs::read_dir(root.join(category)).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "qasm") {
+                continue;
+            }
+            let input = fs::read_to_string(&path).unwrap();
+            let (program, cst) = parser::parse_cst(&path.to_string_lossy(), &input)
+                .ok()
+                .unwrap();
+            assert_eq!(cst.to_string(), input, "{}", path.display());
+
+            let stmts = view::Program::cast(&cst).unwrap().stmts();
+            assert_eq!(stmts.len(), program.stmts.len(), "{}", path.display());
+        }
+    }
+}
+
+#[test]
+fn parentheses_and_commas_are_kept() {
+    let input = "OPENQASM 2.0;\nqreg q[1];\nU((pi), 0, -(1.5)) q[0];\n";
+    let (_, cst) = parser::parse_cst("<test>", input).ok().unwrap();
+
+    let program = view::Program::cast(&cst).unwrap();
+    let stmts = program.stmts();
+    let gate = match &stmts[1] {
+        Stmt::GateStmt(gate) => gate,
+        _ => panic!("expected a gate statement"),
+    };
+    assert_eq!(gate.get_gate().unwrap().get_text(), "U");
+
+    let pars = gate.pars();
+    assert_eq!(pars.len(), 3);
+    assert!(matches!(
+        pars[0].get_syntax().get_kind(),
+        SyntaxKind::ParenExp
+    ));
+    assert_eq!(pars[0].get_syntax().to_string(), "(pi)");
+    assert_eq!(pars[2].get_syntax().to_string(), " -(1.5)");
+    assert_eq!(gate.args()[0].get_name().unwrap().get_text(), "q");
+}
+
+#[test]
+fn renaming_a_register_keeps_the_formatting() {
+    let input = "OPENQASM 2.0;\n\
+                 qreg q[2];   // data\n\
+                 creg qc[2];\n\
+                 CX q[0],q[1];\n\
+                 /* keep */ measure q -> qc;\n";
+    let (_, cst) = parser::parse_cst("<test>", input).ok().unwrap();
+
+    let edits = rewrite::rename_register(&cst, "q", "data");
+    assert_eq!(edits.len(), 4);
+    assert_eq!(
+        rewrite::apply_edits(input, edits),
+        "OPENQASM 2.0;\n\
+         qreg data[2];   // data\n\
+         creg qc[2];\n\
+         CX data[0],data[1];\n\
+         /* keep */ measure data -> qc;\n"
+    );
+}
+
+#[test]
+fn renaming_a_register_skips_gate_formals() {
+    let input = "OPENQASM 2.0;\ngate h a { U(pi/2, 0, pi) a; }\nqreg a[1];\nh a[0];\n";
+    let (_, cst) = parser::parse_cst("<test>", input).ok().unwrap();
+
+    let stmts = view::Program::cast(&cst).unwrap().stmts();
+    match &stmts[0] {
+        Stmt::GateDecl(gate) => {
+            assert_eq!(gate.get_name().unwrap().get_text(), "h");
+            assert_eq!(gate.body().len(), 1);
+        }
+        _ => panic!("expected a gate declaration"),
+    }
+
+    let edits = rewrite::rename_register(&cst, "a", "b");
+    assert_eq!(
+        rewrite::apply_edits(input, edits),
+        "OPENQASM 2.0;\ngate h a { U(pi/2, 0, pi) a; }\nqreg b[1];\nh b[0];\n"
+    );
+}
+
+fn assert_exp_has_syntax(cst: &SyntaxNode, exp: &node::Exp) {
+    let syntax = cst.find(exp.get_loc()).unwrap();
+    assert!(view::Exp::cast(syntax).is_some(), "{}", syntax.dump());
+    match exp {
+        node::Exp::BinopExp { left, right, .. } => {
+            assert_exp_has_syntax(cst, left);
+            assert_exp_has_syntax(cst, right);
+        }
+        node::Exp::UnaryExp { exp, .. } => assert_exp_has_syntax(cst, exp),
+        _ => {}
+    }
+}
+
+fn assert_stmt_has_syntax(cst: &SyntaxNode, stmt: &node::Stmt) {
+    let syntax = cst.find(stmt.get_loc()).unwrap();
+    let kind = syntax.get_kind().to_string();
+    match stmt {
+        node::Stmt::DecStmt(node::Dec::RegDec { .. }) => assert_eq!(kind, "REG_DECL"),
+        node::Stmt::DecStmt(node::Dec::GateDec { body, .. }) => {
+            assert_eq!(kind, "GATE_DECL");
+            for stmt in body.iter().flatten() {
+                assert_stmt_has_syntax(cst, stmt);
+            }
+        }
+        node::Stmt::GateStmt { pars, .. } => {
+            assert_eq!(kind, "GATE_STMT");
+            pars.iter().for_each(|par| assert_exp_has_syntax(cst, par));
+        }
+        node::Stmt::MeasureStmt { .. } => assert_eq!(kind, "MEASURE_STMT"),
+        node::Stmt::ResetStmt { .. } => assert_eq!(kind, "RESET_STMT"),
+        node::Stmt::BarrierStmt { .. } => assert_eq!(kind, "BARRIER_STMT"),
+        node::Stmt::IfStmt { stmt, .. } => {
+            assert_eq!(kind, "IF_STMT");
+            assert_stmt_has_syntax(cst, stmt);
+        }
+    }
+}
+
+#[test]
+fn every_ast_node_has_matching_syntax() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testsuite/files");
+    for category in ["good", "measure"] {
+        for entry in fs::read_dir(root.join(category)).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "qasm") {
+                continue;
+            }
+            let input = fs::read_to_string(&path).unwrap();
+            let (program, cst) = parser::parse_cst(&path.to_string_lossy(), &input)
+                .ok()
+                .unwrap();
+            for stmt in program.stmts.iter() {
+                assert_stmt_has_syntax(&cst, stmt);
+            }
+        }
+    }
+}
+
+struct Locations(Vec<String>);
+
+impl Visitor for Locations {
+    fn visit_dec(&mut self, e: &node::Dec) {
+        if let node::Dec::GateDec {
+            loc,
+            pars,
+            args,
+            body,
+            ..
+        } = e
+        {
+            self.0.push(format!("{} {:?} {:?}", loc, pars, args));
+            body.iter().flatten().for_each(|stmt| self.visit_stmt(stmt));
+        }
+    }
+
+    fn visit_reg(&mut self, e: &node::Reg) {
+        self.0.push(e.get_loc().to_string());
+    }
+
+    fn visit_stmt(&mut self, e: &node::Stmt) {
+        self.0.push(e.get_loc().to_string());
+        match e {
+            node::Stmt::DecStmt(dec) => self.visit_dec(dec),
+            node::Stmt::GateStmt { pars, args, .. } => {
+                pars.iter().for_each(|par| self.visit_exp(par));
+                args.iter().for_each(|arg| self.visit_reg(arg));
+            }
+            node::Stmt::MeasureStmt { src, dst, .. } => {
+                self.visit_reg(src);
+                self.visit_reg(dst);
+            }
+            node::Stmt::ResetStmt { reg, .. } => self.visit_reg(reg),
+            node::Stmt::BarrierStmt { args, .. } => args.iter().for_each(|arg| self.visit_reg(arg)),
+            node::Stmt::IfStmt { reg, stmt, .. } => {
+                self.visit_reg(reg);
+                self.visit_stmt(stmt);
+            }
+        }
+    }
+
+    fn visit_exp(&mut self, e: &node::Exp) {
+        self.0.push(e.get_loc().to_string());
+        match e {
+            node::Exp::BinopExp { left, right, .. } => {
+                self.visit_exp(left);
+                self.visit_exp(right);
+            }
+            node::Exp::UnaryExp { exp, .. } => self.visit_exp(exp),
+            _ => {}
+        }
+    }
+}
+
+fn locations(program: &node::Program) -> Vec<String> {
+    let mut locations = Locations(vec![program.loc.to_string()]);
+    program
+        .stmts
+        .iter()
+        .for_each(|stmt| locations.visit_stmt(stmt));
+    locations.0.extend(
+        program
+            .includes
+            .iter()
+            .map(|include| format!("{} {}", include.loc, include.index)),
+    );
+    locations.0.extend(
+        program
+            .pragmas
+            .iter()
+            .map(|pragma| format!("{} {}", pragma.loc, pragma.index)),
+    );
+    locations.0
+}
+
+#[test]
+fn the_ast_is_read_off_the_syntax_tree() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testsuite/files");
+    for category in ["good", "measure", "bind", "type", "eval"] {
+        for entry in fs::read_dir(root.join(category)).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "qasm") {
+                continue;
+            }
+            let name = path.to_string_lossy();
+            let input = fs::read_to_string(&path).unwrap();
+            let (program, cst) = parser::parse_cst(&name, &input).ok().unwrap();
+            let expected = parser::parse_str(&name, &input).ok().unwrap();
+            assert_eq!(
+                PrettyPrinter::new().pretty_print(&program),
+                PrettyPrinter::new().pretty_print(&expected),
+                "{}",
+                path.display()
+            );
+            assert_eq!(
+                locations(&program),
+                locations(&expected),
+                "{}",
+                path.display()
+            );
+
+            let view = view::Program::cast(&cst).unwrap();
+            assert_eq!(view.includes().len(), program.includes.len());
+            assert_eq!(view.pragmas().len(), program.pragmas.len());
+        }
+    }
+}
+
+#[test]
+fn gate_signatures_are_typed() {
+    let input = "OPENQASM 2.0;\n\
+                 pragma note\n\
+                 gate rot(theta, phi) a, b { U(theta, phi, (-pi)) a; CX a, b; }\n\
+                 opaque magic c;\n";
+    let (program, cst) = parser::parse_cst("<test>", input).ok().unwrap();
+    let view = view::Program::cast(&cst).unwrap();
+    assert_eq!(
+        view.get_header().unwrap().get_version().unwrap().get_text(),
+        "2.0"
+    );
+    assert_eq!(
+        view.pragmas()[0].get_pragma().unwrap().get_text(),
+        "pragma note"
+    );
+
+    let stmts = view.stmts();
+    let texts = |tokens: Vec<&Token>| {
+        tokens
+            .iter()
+            .map(|token| token.get_text().to_string())
+            .collect::<Vec<_>>()
+    };
+    match (&stmts[0], &stmts[1]) {
+        (Stmt::GateDecl(gate), Stmt::GateDecl(opaque)) => {
+            assert!(!gate.is_opaque());
+            assert_eq!(texts(gate.pars()), ["theta", "phi"]);
+            assert_eq!(texts(gate.args()), ["a", "b"]);
+            assert!(opaque.is_opaque());
+            assert!(opaque.pars().is_empty());
+            assert_eq!(texts(opaque.args()), ["c"]);
+        }
+        _ => panic!("expected gate declarations"),
+    }
+
+    match &program.stmts[0] {
+        node::Stmt::DecStmt(node::Dec::GateDec { body, .. }) => match &body.as_ref().unwrap()[0] {
+            node::Stmt::GateStmt { pars, .. } => {
+                assert_eq!(pars[2].get_loc().to_string(), "<test>:2:42-47");
+            }
+            _ => panic!("expected a gate statement"),
+        },
+        _ => panic!("expected a gate declaration"),
+    }
+}
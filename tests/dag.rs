@@ -0,0 +1,96 @@
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::circuit::dag::{Dag, Wire};
+use openqasm_parser::circuit::ir::Operation;
+use openqasm_parser::circuit::lower::Lowerer;
+use openqasm_parser::parse::parser;
+
+fn dag(input: &str) -> Dag {
+    let program = parser::parse_str("<test>", input).ok().unwrap();
+    Dag::new(Lowerer::new().lower(&program).unwrap())
+}
+
+fn print(dag: &Dag) -> String {
+    PrettyPrinter::new().pretty_print(&dag.to_circuit().to_program())
+}
+
+const BELL: &str = "OPENQASM 2.0;\n\
+                    qreg q[3];\n\
+                    creg c[2];\n\
+                    U(0, 0, 0) q[0];\n\
+                    U(0, 0, 0) q[2];\n\
+                    CX q[0], q[1];\n\
+                    measure q[1] -> c[0];\n\
+                    if (c == 1) U(0, 0, 0) q[2];\n";
+
+#[test]
+fn wires_link_neighbouring_operations() {
+    let dag = dag(BELL);
+    assert_eq!(dag.len(), 5);
+    assert_eq!(dag.predecessor(2, Wire::Qubit(0)), Some(0));
+    assert_eq!(dag.predecessor(2, Wire::Qubit(1)), None);
+    assert_eq!(dag.successor(2, Wire::Qubit(1)), Some(3));
+    assert_eq!(dag.successor(2, Wire::Qubit(2)), None);
+    assert_eq!(dag.predecessors(4), vec![1, 3]);
+    assert_eq!(dag.successors(0), vec![2]);
+    assert_eq!(dag.first(Wire::Clbit(1)), Some(4));
+    assert_eq!(dag.last(Wire::Qubit(2)), Some(4));
+}
+
+#[test]
+fn layers_follow_dependencies() {
+    let dag = dag(BELL);
+    assert_eq!(dag.front_layer(), vec![0, 1]);
+    assert_eq!(dag.layers(), vec![vec![0, 1], vec![2], vec![3], vec![4]]);
+    assert_eq!(dag.topological_order(), vec![0, 1, 2, 3, 4]);
+}
+
+#[test]
+fn removal_relinks_wires() {
+    let mut dag = dag(BELL);
+    assert!(matches!(dag.remove(2), Some(Operation::Gate { .. })));
+    assert!(dag.remove(2).is_none());
+    assert_eq!(dag.successor(0, Wire::Qubit(0)), None);
+    assert_eq!(dag.first(Wire::Qubit(1)), Some(3));
+    assert_eq!(dag.front_layer(), vec![0, 1, 3]);
+    assert_eq!(
+        print(&dag),
+        "OPENQASM 2.0;\n\
+         qreg q[3];\n\
+         creg c[2];\n\
+         U (0, 0, 0) q[0];\n\
+         U (0, 0, 0) q[2];\n\
+         measure q[1] -> c[0];\n\
+         if (c == 1) U (0, 0, 0) q[2];\n"
+    );
+}
+
+#[test]
+fn substitution_stays_on_the_same_wires() {
+    let mut dag = dag(BELL);
+    let swap = |a: usize, b: usize| Operation::Gate {
+        name: String::from("CX"),
+        params: Vec::new(),
+        qubits: vec![a, b],
+    };
+    assert!(dag.substitute(2, vec![swap(0, 2)]).is_none());
+    let ids = dag
+        .substitute(2, vec![swap(0, 1), swap(1, 0), swap(0, 1)])
+        .unwrap();
+    assert_eq!(ids, vec![5, 6, 7]);
+    assert_eq!(dag.predecessor(5, Wire::Qubit(0)), Some(0));
+    assert_eq!(dag.predecessor(3, Wire::Qubit(1)), Some(7));
+    assert_eq!(dag.topological_order(), vec![0, 1, 5, 6, 7, 3, 4]);
+    assert_eq!(
+        print(&dag),
+        "OPENQASM 2.0;\n\
+         qreg q[3];\n\
+         creg c[2];\n\
+         U (0, 0, 0) q[0];\n\
+         U (0, 0, 0) q[2];\n\
+         CX q[0], q[1];\n\
+         CX q[1], q[0];\n\
+         CX q[0], q[1];\n\
+         measure q[1] -> c[0];\n\
+         if (c == 1) U (0, 0, 0) q[2];\n"
+    );
+}
@@ -0,0 +1,99 @@
+use openqasm_parser::ast::fragment;
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::eval::evaluator::Evaluator;
+use openqasm_parser::parse::parser;
+use openqasm_parser::utils::error::Error;
+
+fn eval(input: &str) -> String {
+    let exp = fragment::parse_exp("<test>", input).ok().unwrap();
+    let mut evaluator = Evaluator::new();
+    let value = evaluator.eval(&exp).unwrap();
+    assert!(evaluator.get_error().empty());
+    value.to_string()
+}
+
+fn eval_error(input: &str) -> String {
+    let exp = fragment::parse_exp("<test>", input).ok().unwrap();
+    let mut evaluator = Evaluator::new();
+    assert!(evaluator.eval(&exp).is_none());
+    assert_eq!(evaluator.get_error().get_exit_code(), 6);
+    evaluator.get_error().get_desc()
+}
+
+#[test]
+fn multiples_of_pi_stay_exact() {
+    assert_eq!(eval("pi/2"), "pi/2");
+    assert_eq!(eval("3*pi/4 - pi/4"), "pi/2");
+    assert_eq!(eval("-pi/2 * 3"), "-3*pi/2");
+    assert_eq!(eval("(pi + pi) / pi"), "2");
+    assert_eq!(eval("2^-3"), "1/8");
+    assert_eq!(eval("sqrt(9/4)"), "3/2");
+    assert_eq!(eval("cos(pi) + sin(pi/2)"), "0");
+    assert_eq!(eval("pi * 0.5"), "1.5707963267948966");
+    assert_eq!(eval("pi * pi"), "9.869604401089358");
+}
+
+#[test]
+fn extreme_exponents_fall_back_to_reals() {
+    assert_eq!(eval("2^(-4294967296*2147483648)"), "0.0");
+    assert_eq!(eval("1^(-4294967296*2147483648)"), "1.0");
+}
+
+#[test]
+fn invalid_operations_are_located() {
+    assert_eq!(
+        eval_error("1 / (pi - pi)"),
+        "division by zero at <test>:0:0-13"
+    );
+    assert_eq!(eval_error("0^-1"), "division by zero at <test>:0:0-4");
+    assert_eq!(
+        eval_error("ln(0)"),
+        "logarithm of non-positive number 0 at <test>:0:0-5"
+    );
+    assert_eq!(
+        eval_error("sqrt(-pi)"),
+        "square root of negative number -pi at <test>:0:0-9"
+    );
+}
+
+#[test]
+fn results_that_are_not_finite_are_located() {
+    assert_eq!(
+        eval_error("exp(1000)"),
+        "result is not a finite number at <test>:0:0-9"
+    );
+    assert_eq!(
+        eval_error("1.0e308 * 10"),
+        "result is not a finite number at <test>:0:0-12"
+    );
+    assert_eq!(
+        eval_error("2^(4294967296*2147483648)"),
+        "result is not a finite number at <test>:0:0-25"
+    );
+    assert_eq!(
+        eval_error("(-2)^0.5"),
+        "result is not a finite number at <test>:0:0-8"
+    );
+    assert_eq!(
+        eval_error("ln(0) + exp(1000)"),
+        "logarithm of non-positive number 0 at <test>:0:0-5\n\
+         result is not a finite number at <test>:0:8-17"
+    );
+}
+
+#[test]
+fn folding_rewrites_gate_parameters() {
+    let mut program = parser::parse_str(
+        "<test>",
+        "OPENQASM 2.0;\nqreg q[1];\nU(pi/4 + pi/4, 2*(1-1), ln(1)) q[0];\n",
+    )
+    .ok()
+    .unwrap();
+    let mut evaluator = Evaluator::new();
+    evaluator.fold(&mut program);
+    assert!(evaluator.get_error().empty());
+    assert_eq!(
+        PrettyPrinter::new().pretty_print(&program),
+        "OPENQASM 2.0;\nqreg q[1];\nU ((pi / 2), 0, 0) q[0];\n"
+    );
+}
@@ -0,0 +1,120 @@
+use std::rc::Rc;
+
+use openqasm_parser::ast::fragment;
+use openqasm_parser::bind::binder::Binder;
+use openqasm_parser::bind::par::ParDec;
+use openqasm_parser::parse::parser;
+use openqasm_parser::typing::ty::Ty;
+use openqasm_parser::typing::type_checker::TypeChecker;
+use openqasm_parser::utils::error::Error;
+
+fn type_of(input: &str) -> String {
+    let exp = fragment::parse_exp("<test>", input).ok().unwrap();
+    let mut type_checker = TypeChecker::new();
+    let ty = type_checker.type_exp(&exp);
+    assert!(type_checker.get_error().empty());
+    ty.to_string()
+}
+
+#[test]
+fn literals_have_their_own_types() {
+    assert_eq!(type_of("1"), "int_ty");
+    assert_eq!(type_of("1.5"), "real_ty");
+    assert_eq!(type_of("pi"), "angle_ty");
+}
+
+#[test]
+fn operators_combine_operand_types() {
+    assert_eq!(type_of("1 + 2 * 3"), "int_ty");
+    assert_eq!(type_of("-1"), "int_ty");
+    assert_eq!(type_of("1 / 2"), "real_ty");
+    assert_eq!(type_of("1 + 0.5"), "real_ty");
+    assert_eq!(type_of("2 ^ 3"), "int_ty");
+    assert_eq!(type_of("2 ^ 0"), "int_ty");
+    assert_eq!(type_of("2 ^ -1"), "real_ty");
+    assert_eq!(type_of("2 ^ (1 - 2)"), "real_ty");
+    assert_eq!(type_of("2 ^ (3 / 3)"), "real_ty");
+    assert_eq!(type_of("2 ^ 0.5"), "real_ty");
+    assert_eq!(type_of("pi / 2"), "angle_ty");
+    assert_eq!(type_of("-pi + 0.1"), "angle_ty");
+    assert_eq!(type_of("pi / pi"), "real_ty");
+    assert_eq!(type_of("1 / pi"), "real_ty");
+    assert_eq!(type_of("sin(pi / 2)"), "real_ty");
+    assert_eq!(type_of("sqrt(4)"), "real_ty");
+}
+
+fn type_errors(input: &str) -> String {
+    let mut program = parser::parse_str("<test>", input).ok().unwrap();
+    let mut binder = Binder::new();
+    binder.bind(&mut program);
+    assert!(binder.get_error().empty());
+    let mut type_checker = TypeChecker::new();
+    type_checker.check_types(&program);
+    type_checker.get_error().get_desc()
+}
+
+#[test]
+fn exponents_of_unknown_sign_are_real() {
+    assert_eq!(
+        type_errors("OPENQASM 2.0;\ngate g(x) a { U(2 ^ x, 0, 0) a; }\nqreg q[1];\ng(2) q[0];\n"),
+        ""
+    );
+    assert_eq!(type_of("2 ^ sin(1)"), "real_ty");
+}
+
+#[test]
+fn parameter_lists_are_checked_against_the_gate() {
+    assert_eq!(
+        type_errors("OPENQASM 2.0;\nqreg q[1];\nU(pi) q[0];\n"),
+        "invalid number of parameters, expected 3 got 1 at <test>:2:0-11"
+    );
+    assert_eq!(
+        type_errors(
+            "OPENQASM 2.0;\ngate g(x, y) a { U(x, y, 0) a; }\nqreg q[1];\ng(1, 2, 3) q[0];\n"
+        ),
+        "invalid number of parameters, expected 2 got 3 at <test>:3:0-16"
+    );
+    assert_eq!(
+        type_errors("OPENQASM 2.0;\nqreg q[1];\nU(2 ^ -1, sin(pi), -pi) q[0];\n"),
+        ""
+    );
+}
+
+fn type_errors_with(par: &str, ty: Ty, input: &str) -> String {
+    let mut program = parser::parse_str("<test>", input).ok().unwrap();
+    let mut binder = Binder::new();
+    binder.declare_par(Rc::new(ParDec::new_typed(String::from(par), ty)));
+    binder.bind(&mut program);
+    assert!(binder.get_error().empty());
+    let mut type_checker = TypeChecker::new();
+    type_checker.check_types(&program);
+    type_checker.get_error().get_desc()
+}
+
+#[test]
+fn parameters_take_the_type_of_their_declaration() {
+    assert_eq!(
+        type_errors_with(
+            "n",
+            Ty::IntTy,
+            "OPENQASM 2.0;\nqreg q[1];\nU(2 ^ n, 0, 0) q[0];\n"
+        ),
+        ""
+    );
+    assert_eq!(
+        type_errors_with(
+            "c",
+            Ty::BitTy,
+            "OPENQASM 2.0;\nqreg q[1];\nU(c, 0, 0) q[0];\n"
+        ),
+        "expected a numeric parameter got bit_ty at <test>:2:2-3"
+    );
+    assert_eq!(
+        type_errors_with(
+            "c",
+            Ty::CRegTy(2),
+            "OPENQASM 2.0;\nqreg q[1];\nU(pi, -c, 0) q[0];\n"
+        ),
+        "expected a numeric expression got creg_ty[2] at <test>:2:7-8"
+    );
+}
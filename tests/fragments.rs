@@ -0,0 +1,100 @@
+use std::rc::Rc;
+
+use openqasm_parser::ast::{fragment, node};
+use openqasm_parser::bind::binder::Binder;
+use openqasm_parser::parse::parser;
+use openqasm_parser::utils::error::Error;
+
+#[test]
+fn expressions_parse_without_a_header() {
+    let exp = fragment::parse_exp("<ui>", "pi/2 + 0.1").ok().unwrap();
+    assert_eq!(exp.get_loc().to_string(), "<ui>:0:0-10");
+
+    let error = fragment::parse_exp("<ui>", "pi/2 +").err().unwrap();
+    assert_eq!(error.get_exit_code(), 3);
+    assert_eq!(error.get_desc(), "unexpected token EOF at <ui>:0:6");
+}
+
+#[test]
+fn statements_are_checked_against_a_context() {
+    let program = parser::parse_str("<ctx>", "OPENQASM 2.0; qreg q[2]; creg c[2];")
+        .ok()
+        .unwrap();
+    let stmt = fragment::parse_stmt("<ui>", "CX q[0], q[1];", Some(&program));
+    assert!(stmt.is_ok());
+
+    let error = fragment::parse_stmt("<ui>", "CX q[0], q[2];", Some(&program))
+        .err()
+        .unwrap();
+    assert_eq!(error.get_exit_code(), 5);
+
+    let error = fragment::parse_stmt("<ui>", "measure q -> c;", None)
+        .err()
+        .unwrap();
+    assert_eq!(error.get_exit_code(), 4);
+
+    let error = fragment::parse_stmt("<ui>", "reset q; reset q;", Some(&program))
+        .err()
+        .unwrap();
+    assert_eq!(error.get_exit_code(), 3);
+}
+
+#[test]
+fn gate_bodies_declare_their_arguments() {
+    let body =
+        fragment::parse_gate_body("<ui>", "{ U(pi/2, 0, pi) a; CX a, b; }", &[], &["a", "b"])
+            .ok()
+            .unwrap();
+    assert_eq!(body.len(), 2);
+
+    let error = fragment::parse_gate_body("<ui>", "{ CX a, c; }", &[], &["a", "b"])
+        .err()
+        .unwrap();
+    assert_eq!(error.get_desc(), "undeclared register 'c' at <ui>:0:8-9");
+}
+
+#[test]
+fn gate_bodies_declare_their_parameters() {
+    let body = fragment::parse_gate_body("<ui>", "{ U(theta, 0, pi) a; }", &["theta"], &["a"])
+        .ok()
+        .unwrap();
+    assert_eq!(body.len(), 1);
+
+    let error = fragment::parse_gate_body("<ui>", "{ U(theta, phi, pi) a; }", &["theta"], &["a"])
+        .err()
+        .unwrap();
+    assert_eq!(
+        error.get_desc(),
+        "undeclared parameter 'phi' at <ui>:0:11-14"
+    );
+}
+
+#[test]
+fn contexts_are_declared_but_not_bound() {
+    let mut program = parser::parse_str(
+        "<ctx>",
+        "OPENQASM 2.0; gate h a { U(pi/2, 0, pi) a; } qreg q[2]; reset r;",
+    )
+    .ok()
+    .unwrap();
+    let stmt = fragment::parse_stmt("<ui>", "h q[1];", Some(&program));
+    assert!(stmt.is_ok());
+    assert!(matches!(
+        &program.stmts[1],
+        node::Stmt::DecStmt(node::Dec::RegDec { dec: None, .. })
+    ));
+
+    let mut binder = Binder::new();
+    binder.bind(&mut program);
+    assert_eq!(binder.get_error().get_exit_code(), 4);
+    let stmt = fragment::parse_stmt("<ui>", "h q[0];", Some(&program))
+        .ok()
+        .unwrap();
+    match (&stmt, &program.stmts[1]) {
+        (
+            node::Stmt::GateStmt { args, .. },
+            node::Stmt::DecStmt(node::Dec::RegDec { dec: Some(dec), .. }),
+        ) => assert!(Rc::ptr_eq(args[0].get_dec().unwrap(), dec)),
+        _ => panic!("expected a gate and a register"),
+    }
+}
@@ -0,0 +1,245 @@
+use openqasm_parser::ast::node;
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::ast::visitor::Visitor;
+use openqasm_parser::parse::incremental::{Document, TextEdit};
+use openqasm_parser::parse::parser;
+use openqasm_parser::utils::error::Error;
+use openqasm_parser::utils::location::Location;
+
+struct Locations {
+    output: Vec<String>,
+}
+
+impl Locations {
+    fn push(&mut self, loc: &Location) {
+        self.output.push(format!("{} {:?}", loc, loc.get_span()));
+    }
+}
+
+impl Visitor for Locations {
+    fn visit_dec(&mut self, e: &node::Dec) {
+        self.push(e.get_loc());
+    }
+
+    fn visit_reg(&mut self, e: &node::Reg) {
+        self.push(e.get_loc());
+    }
+
+    fn visit_stmt(&mut self, e: &node::Stmt) {
+        self.push(e.get_loc());
+        match e {
+            node::Stmt::DecStmt(dec) => self.visit_dec(dec),
+            node::Stmt::GateStmt { pars, args, .. } => {
+                pars.iter().for_each(|par| self.visit_exp(par));
+                args.iter().for_each(|arg| self.visit_reg(arg));
+            }
+            node::Stmt::MeasureStmt { src, dst, .. } => {
+                self.visit_reg(src);
+                self.visit_reg(dst);
+            }
+            node::Stmt::ResetStmt { reg, .. } => self.visit_reg(reg),
+            node::Stmt::BarrierStmt { args, .. } => {
+                args.iter().for_each(|arg| self.visit_reg(arg));
+            }
+            node::Stmt::IfStmt { reg, stmt, .. } => {
+                self.visit_reg(reg);
+                self.visit_stmt(stmt);
+            }
+        }
+    }
+
+    fn visit_exp(&mut self, e: &node::Exp) {
+        self.push(e.get_loc());
+        match e {
+            node::Exp::BinopExp { left, right, .. } => {
+                self.visit_exp(left);
+                self.visit_exp(right);
+            }
+            node::Exp::UnaryExp { exp, .. } => self.visit_exp(exp),
+            _ => {}
+        }
+    }
+}
+
+fn dump(program: &node::Program) -> String {
+    let mut locations = Locations { output: Vec::new() };
+    program
+        .stmts
+        .iter()
+        .for_each(|stmt| locations.visit_stmt(stmt));
+    for include in program.includes.iter() {
+        locations.push(include.get_loc());
+    }
+    format!(
+        "{}{}",
+        PrettyPrinter::new().pretty_print(program),
+        locations.output.join("\n")
+    )
+}
+
+fn replace(document: &mut Document, old: &str, new: &str) -> Option<std::ops::Range<usize>> {
+    let start = document.get_text().find(old).unwrap() as u64;
+    document
+        .apply(TextEdit {
+            start,
+            end: start + old.len() as u64,
+            text: String::from(new),
+        })
+        .ok()
+        .unwrap()
+}
+
+fn assert_matches_full_parse(document: &Document) {
+    match parser::parse_str("<document>", document.get_text()) {
+        Ok(program) => assert_eq!(dump(document.get_program().unwrap()), dump(&program)),
+        Err(error) => assert_eq!(document.get_error().get_desc(), error.get_desc()),
+    }
+}
+
+const SOURCE: &str = "OPENQASM 2.0;
+include \"qelib1.inc\";
+
+qreg q[2];
+creg c[2];
+
+U (pi/2, 0, pi) q[0];
+CX q[0], q[1];
+U (pi,
+   0, pi) q[1]; reset q[1];
+measure q -> c;
+";
+
+#[test]
+fn edits_reparse_only_the_touched_statements() {
+    let mut document = Document::new(String::from("<document>"), String::from(SOURCE));
+    assert_matches_full_parse(&document);
+
+    assert_eq!(replace(&mut document, "pi/2", "pi/4 + 1"), Some(2..3));
+    assert_matches_full_parse(&document);
+
+    assert_eq!(
+        replace(
+            &mut document,
+            "CX q[0], q[1];\n",
+            "CX q[0], q[1];\nreset q;\n"
+        ),
+        Some(3..5)
+    );
+    assert_matches_full_parse(&document);
+
+    assert_eq!(replace(&mut document, "(pi,\n", "(pi,\n\n  "), Some(5..6));
+    assert_matches_full_parse(&document);
+
+    assert_eq!(replace(&mut document, "reset q;\n", ""), Some(4..4));
+    assert_matches_full_parse(&document);
+
+    assert_eq!(replace(&mut document, "CX", "// CX"), Some(3..3));
+    assert_matches_full_parse(&document);
+    assert_eq!(document.get_program().unwrap().stmts.len(), 6);
+}
+
+#[test]
+fn edits_outside_statements_fall_back_to_a_full_parse() {
+    let mut document = Document::new(String::from("<document>"), String::from(SOURCE));
+
+    assert_eq!(replace(&mut document, "qelib1", "stdgates"), None);
+    assert_matches_full_parse(&document);
+
+    assert_eq!(
+        replace(&mut document, "CX q[0], q[1];", "CX q[0] q[1];"),
+        None
+    );
+    assert!(document.get_program().is_none());
+    assert_eq!(document.get_error().get_exit_code(), 3);
+    assert_matches_full_parse(&document);
+
+    assert_eq!(
+        replace(&mut document, "CX q[0] q[1];", "CX q[0], q[1];"),
+        None
+    );
+    assert_matches_full_parse(&document);
+
+    assert_eq!(replace(&mut document, "reset", "/* reset"), None);
+    assert_matches_full_parse(&document);
+}
+
+#[test]
+fn single_character_edits_match_a_full_parse() {
+    for offset in 0..SOURCE.len() as u64 {
+        for (end, text) in [(offset, " "), (offset, "\n"), (offset + 1, "")] {
+            let mut document = Document::new(String::from("<document>"), String::from(SOURCE));
+            let edit = TextEdit {
+                start: offset,
+                end,
+                text: String::from(text),
+            };
+            assert!(document.apply(edit).is_ok());
+            assert_matches_full_parse(&document);
+        }
+    }
+}
+
+#[test]
+fn pragmas_after_an_edit_keep_their_place() {
+    let source = "OPENQASM 2.0;\nqreg q[2];\nreset q[0];\npragma between\nreset q[1];\n";
+    let mut document = Document::new(String::from("<document>"), String::from(source));
+    assert_eq!(
+        replace(&mut document, "reset q[0];", "reset q[0]; reset q[1];"),
+        Some(1..3)
+    );
+    assert_matches_full_parse(&document);
+    assert_eq!(document.get_program().unwrap().pragmas[0].index, 3);
+}
+
+#[test]
+fn invalid_edits_are_rejected() {
+    let source = "OPENQASM 2.0;\n// π\nqreg q[1];\n";
+    let mut document = Document::new(String::from("<document>"), String::from(source));
+    let pi = source.find('π').unwrap() as u64;
+    for (start, end) in [(4, 2), (0, 1000), (pi + 1, pi + 1), (pi, pi + 1)] {
+        let error = document
+            .apply(TextEdit {
+                start,
+                end,
+                text: String::from("x"),
+            })
+            .err()
+            .unwrap();
+        assert_eq!(error.get_exit_code(), 1);
+    }
+    assert_eq!(document.get_text(), source);
+    assert_matches_full_parse(&document);
+}
+
+#[test]
+fn edits_to_block_comments_match_a_full_parse() {
+    let source = "OPENQASM 2.0;\n/*\nqreg r[1];\n*/\nqreg q[1];\n";
+    let mut document = Document::new(String::from("<document>"), String::from(source));
+    assert_eq!(replace(&mut document, "/*", ""), None);
+    assert!(document.get_program().is_none());
+    assert_eq!(
+        document.get_error().get_desc(),
+        parser::parse_str("<document>", document.get_text())
+            .err()
+            .unwrap()
+            .get_desc()
+    );
+
+    let mut document = Document::new(String::from("<document>"), String::from(source));
+    assert_eq!(replace(&mut document, "r[1]", "r[2]"), None);
+    assert_matches_full_parse(&document);
+    assert_eq!(document.get_program().unwrap().stmts.len(), 1);
+
+    for offset in 0..source.len() as u64 {
+        for (end, text) in [(offset, "/"), (offset, "*"), (offset + 1, "")] {
+            let mut document = Document::new(String::from("<document>"), String::from(source));
+            let edit = TextEdit {
+                start: offset,
+                end,
+                text: String::from(text),
+            };
+            assert!(document.apply(edit).is_ok());
+            assert_matches_full_parse(&document);
+        }
+    }
+}
@@ -0,0 +1,174 @@
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::bind::binder::Binder;
+use openqasm_parser::parse::parser;
+use openqasm_parser::transform::inliner::Inliner;
+use openqasm_parser::utils::error::Error;
+
+fn inline(input: &str) -> String {
+    let mut program = parser::parse_str("<test>", input).ok().unwrap();
+    let mut inliner = Inliner::new();
+    inliner.inline(&mut program);
+    assert!(inliner.get_error().empty());
+    let output = PrettyPrinter::new().pretty_print(&program);
+
+    let mut program = parser::parse_str("<output>", &output).ok().unwrap();
+    let mut binder = Binder::new();
+    binder.bind(&mut program);
+    assert!(
+        binder.get_error().empty(),
+        "{}",
+        binder.get_error().get_desc()
+    );
+    output
+}
+
+#[test]
+fn nested_gates_expand_to_builtins() {
+    let output = inline(
+        "OPENQASM 2.0;\n\
+         gate h a { U(pi/2, 0, pi) a; }\n\
+         gate cz a, b { h b; CX a, b; h b; }\n\
+         qreg q[2];\n\
+         cz q[1], q[0];\n",
+    );
+    assert_eq!(
+        output,
+        "OPENQASM 2.0;\n\
+         qreg q[2];\n\
+         U ((pi / 2), 0, pi) q[0];\n\
+         CX q[1], q[0];\n\
+         U ((pi / 2), 0, pi) q[0];\n"
+    );
+}
+
+#[test]
+fn parameters_are_substituted_with_expressions() {
+    let output = inline(
+        "OPENQASM 2.0;\n\
+         gate rz(theta) a { U(0, 0, theta) a; }\n\
+         gate crz(lambda) a, b { rz(lambda/2) b; CX a, b; rz(-lambda/2) b; CX a, b; }\n\
+         qreg q[2];\n\
+         qreg r[2];\n\
+         crz(pi/4 + 0.1) q, r;\n",
+    );
+    assert_eq!(
+        output,
+        "OPENQASM 2.0;\n\
+         qreg q[2];\n\
+         qreg r[2];\n\
+         U (0, 0, (((pi / 4) + 0.1) / 2)) r;\n\
+         CX q, r;\n\
+         U (0, 0, (-(((pi / 4) + 0.1)) / 2)) r;\n\
+         CX q, r;\n"
+    );
+}
+
+#[test]
+fn opaque_gates_are_kept() {
+    let output = inline(
+        "OPENQASM 2.0;\n\
+         opaque magic(x) a;\n\
+         gate twice(x) a { magic(x) a; magic(x) a; }\n\
+         qreg q[1];\n\
+         twice(0.5) q[0];\n",
+    );
+    assert_eq!(
+        output,
+        "OPENQASM 2.0;\n\
+         opaque magic(x) a;\n\
+         qreg q[1];\n\
+         magic (0.5) q[0];\n\
+         magic (0.5) q[0];\n"
+    );
+}
+
+#[test]
+fn recursive_gates_are_reported() {
+    let mut program = parser::parse_str(
+        "<test>",
+        "OPENQASM 2.0;\ngate loop a { U(0, 0, 0) a; loop a; }\nqreg q[1];\nloop q[0];\n",
+    )
+    .ok()
+    .unwrap();
+    let mut inliner = Inliner::new();
+    inliner.inline(&mut program);
+    assert_eq!(inliner.get_error().get_exit_code(), 4);
+    assert_eq!(
+        inliner.get_error().get_desc(),
+        "recursive gate 'loop' at <test>:1:28-35"
+    );
+}
+
+#[test]
+fn pragmas_stay_between_the_expanded_statements() {
+    let output = inline(
+        "OPENQASM 2.0;\n\
+         gate h a { U(pi/2, 0, pi) a; }\n\
+         gate bell a, b { h a; CX a, b; }\n\
+         qreg q[2];\n\
+         bell q[0], q[1];\n\
+         pragma after_bell\n\
+         h q[1];\n",
+    );
+    assert_eq!(
+        output,
+        "OPENQASM 2.0;\n\
+         qreg q[2];\n\
+         U ((pi / 2), 0, pi) q[0];\n\
+         CX q[0], q[1];\n\
+         pragma after_bell\n\
+         U ((pi / 2), 0, pi) q[1];\n"
+    );
+}
+
+#[test]
+fn expanded_statements_point_back_to_the_call() {
+    let mut program = parser::parse_str(
+        "<test>",
+        "OPENQASM 2.0;\ngate h a { U(pi/2, 0, pi) a; }\nqreg q[1];\nh q[0];\n",
+    )
+    .ok()
+    .unwrap();
+    Inliner::new().inline(&mut program);
+    let loc = program.stmts[1].get_loc();
+    assert!(loc.is_synthetic());
+    assert_eq!(
+        loc.to_string(),
+        "<synthetic>, expanded from gate h at <test>:3:0-7"
+    );
+    assert_eq!(loc.get_source().unwrap().to_string(), "<test>:3:0-7");
+}
+
+#[test]
+fn whole_registers_expand_once_per_element() {
+    let mut program = parser::parse_str(
+        "<test>",
+        "OPENQASM 2.0;\n\
+         gate g a, b { U(0.1, 0, 0) a; CX a, b; }\n\
+         qreg q[1];\n\
+         qreg r[2];\n\
+         g q[0], r;\n",
+    )
+    .ok()
+    .unwrap();
+    let mut binder = Binder::new();
+    binder.bind(&mut program);
+    assert!(binder.get_error().empty());
+    let mut inliner = Inliner::new();
+    inliner.inline(&mut program);
+    assert!(inliner.get_error().empty());
+
+    // Printing a bound program annotates it with comments, reparse to drop them.
+    let output = PrettyPrinter::new().pretty_print(&program);
+    let program = parser::parse_str("<output>", &output).ok().unwrap();
+    assert_eq!(
+        PrettyPrinter::new().pretty_print(&program),
+        "OPENQASM 2.0;\n\
+         qreg q[1];\n\
+         qreg r[2];\n\
+         U (0.1, 0, 0) q[0];\n\
+         CX q[0], r[0];\n\
+         U (0.1, 0, 0) q[0];\n\
+         CX q[0], r[1];\n"
+    );
+}
@@ -0,0 +1,41 @@
+use openqasm_parser::parse::keywords::Dialect;
+use openqasm_parser::parse::lexer::Lexer;
+
+fn token_names(lexer: Lexer<&[u8]>) -> Vec<String> {
+    lexer.map(|token| token.get_ty().to_string()).collect()
+}
+
+#[test]
+fn keywords_follow_the_header_version() {
+    let lexer = Lexer::from_str(String::from("<test>"), "OPENQASM 2.0; CX qubit H;");
+    assert_eq!(
+        token_names(lexer),
+        [
+            "OPENQASM",
+            "REAL(2)",
+            "SEMI",
+            "CXGATE",
+            "ID(qubit)",
+            "ID(H)",
+            "SEMI",
+            "EOF"
+        ]
+    );
+    let lexer = Lexer::from_str(String::from("<test>"), "OPENQASM 3.0; CX qubit H;");
+    assert_eq!(
+        token_names(lexer),
+        [
+            "OPENQASM", "REAL(3)", "SEMI", "ID(CX)", "QUBIT", "ID(H)", "SEMI", "EOF"
+        ]
+    );
+}
+
+#[test]
+fn dialect_can_be_set_without_a_header() {
+    let mut lexer = Lexer::from_str(String::from("<test>"), "bit b = \"0_1\";");
+    lexer.set_dialect(Dialect::OpenQASM3);
+    assert_eq!(
+        token_names(lexer),
+        ["BIT", "ID(b)", "ASSIGN", "BIT_STRING(0_1)", "SEMI", "EOF"]
+    );
+}
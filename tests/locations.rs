@@ -0,0 +1,69 @@
+use openqasm_parser::parse::lexer::Lexer;
+use openqasm_parser::parse::parser;
+use openqasm_parser::utils::location::Location;
+use openqasm_parser::utils::source_map::FileId;
+
+fn locations(lexer: Lexer<&[u8]>) -> Vec<String> {
+    lexer.map(|token| token.get_loc().to_string()).collect()
+}
+
+#[test]
+fn crlf_counts_as_a_single_newline() {
+    let lexer = Lexer::from_str(
+        String::from("<test>"),
+        "OPENQASM 2.0;\r\nqreg q[1];\rreset q;",
+    );
+    let locations = locations(lexer);
+    assert_eq!(locations[3], "<test>:1:0-4");
+    assert_eq!(locations[9], "<test>:2:0-5");
+}
+
+#[test]
+fn tabs_advance_to_the_next_tab_stop() {
+    let input = "OPENQASM 2.0;\n\tqreg\tq[1];";
+    let mut lexer = Lexer::from_str(String::from("<test>"), input);
+    lexer.set_tab_width(4);
+    let locations = locations(lexer);
+    assert_eq!(locations[3], "<test>:1:4-8");
+    assert_eq!(locations[4], "<test>:1:12-13");
+}
+
+#[test]
+fn tabs_are_one_column_by_default() {
+    let input = "OPENQASM 2.0;\n\tqreg\tq[1];";
+    let lexer = Lexer::from_str(String::from("<test>"), input);
+    let locations = locations(lexer);
+    assert_eq!(locations[3], "<test>:1:1-5");
+    assert_eq!(locations[4], "<test>:1:6-7");
+}
+
+#[test]
+fn derived_locations_point_back_to_their_source() {
+    let program = parser::parse_str("<derived>", "OPENQASM 2.0;\nqreg q[1];")
+        .ok()
+        .unwrap();
+    let loc = program.stmts[0].get_loc();
+    assert!(!loc.is_synthetic());
+
+    let derived = Location::new_derived("gate h", loc);
+    let twice = Location::new_derived("gate g", &derived);
+    assert!(derived.is_synthetic());
+    assert_eq!(
+        derived.to_string(),
+        "<synthetic>, expanded from gate h at <derived>:1:0-10"
+    );
+    assert_eq!(twice.get_source().unwrap().to_string(), "<derived>:1:0-10");
+    assert!(twice.get_span().is_none());
+
+    let synthetic = Location::new_synthetic();
+    assert!(synthetic.is_synthetic());
+    assert!(synthetic.get_source().is_none());
+}
+
+#[test]
+fn new_locations_keep_their_offsets() {
+    let file = FileId::new(String::from("<offsets>"));
+    let loc = Location::new(file, 1, 2, 16, 1, 6, 20);
+    assert_eq!(loc.get_span(), Some((16, 20)));
+    assert_eq!(loc.to_string(), "<offsets>:1:2-6");
+}
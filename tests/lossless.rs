@@ -0,0 +1,73 @@
+use std::fs;
+use std::path::Path;
+
+use openqasm_parser::parse::lexer::Lexer;
+use openqasm_parser::parse::token::{TokenTy, TriviaTy};
+
+fn regenerate(lexer: Lexer<&[u8]>) -> String {
+    let mut output = String::new();
+    for token in lexer {
+        for trivia in token.get_leading() {
+            output += trivia.get_text();
+        }
+        output += token.get_text();
+    }
+    output
+}
+
+#[test]
+fn lossless_lexing_regenerates_the_source() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testsuite/files");
+    for category in ["good", "measure", "parse", "bind", "type", "eval"] {
+        for entry in fs::read_dir(root.join(category)).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "qasm") {
+                continue;
+            }
+            let input = fs::read_to_string(&path).unwrap();
+            let mut lexer = Lexer::from_str(path.to_string_lossy().into_owned(), &input);
+            lexer.set_lossless(true);
+            assert_eq!(regenerate(lexer), input, "{}", path.display());
+        }
+    }
+}
+
+#[test]
+fn trivia_is_attached_to_the_next_token() {
+    let input = "OPENQASM 2.0; // header\n/* registers */ qreg q[1];";
+    let mut lexer = Lexer::from_str(String::from("<test>"), input);
+    lexer.set_lossless(true);
+    let tokens = lexer.collect::<Vec<_>>();
+
+    let qreg = tokens
+        .iter()
+        .find(|token| matches!(token.get_ty(), TokenTy::QReg))
+        .unwrap();
+    let trivia = qreg
+        .get_leading()
+        .iter()
+        .map(|trivia| (trivia.get_ty().to_string(), trivia.get_text().as_str()))
+        .collect::<Vec<_>>();
+    assert_eq!(
+        trivia,
+        vec![
+            (TriviaTy::Whitespace.to_string(), " "),
+            (TriviaTy::LineComment.to_string(), "// header"),
+            (TriviaTy::Whitespace.to_string(), "\n"),
+            (TriviaTy::BlockComment.to_string(), "/* registers */"),
+            (TriviaTy::Whitespace.to_string(), " "),
+        ]
+    );
+    assert!(matches!(tokens.last().unwrap().get_ty(), TokenTy::Eof));
+}
+
+#[test]
+fn trivia_is_dropped_by_default() {
+    let input = "OPENQASM 2.0; // header\nqreg q[1];";
+    let lexer = Lexer::from_str(String::from("<test>"), input);
+    assert!(
+        lexer
+            .into_iter()
+            .all(|token| token.get_leading().is_empty())
+    );
+}
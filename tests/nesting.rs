@@ -0,0 +1,33 @@
+use openqasm_parser::parse::parser;
+use openqasm_parser::utils::error::Error;
+
+fn program(exp: &str) -> String {
+    format!("OPENQASM 2.0;\nqreg q[1];\nU({}, 0, 0) q[0];\n", exp)
+}
+
+fn chain(op: &str, terms: usize) -> String {
+    vec!["pi"; terms].join(op)
+}
+
+#[test]
+fn long_flat_chains_are_rejected_instead_of_overflowing() {
+    for op in ["+", "-", "*", "/"] {
+        for terms in [10_000, 100_000] {
+            let error = parser::parse_str("<chain>", &program(&chain(op, terms)))
+                .err()
+                .unwrap();
+            assert_eq!(error.get_exit_code(), 3);
+            assert!(
+                error
+                    .get_desc()
+                    .starts_with("expression is nested too deeply")
+            );
+        }
+    }
+}
+
+#[test]
+fn short_chains_still_parse() {
+    let exp = format!("{} + {}", chain("*", 100), chain("/", 100));
+    assert!(parser::parse_str("<chain>", &program(&exp)).is_ok());
+}
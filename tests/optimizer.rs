@@ -0,0 +1,114 @@
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::circuit::dag::Dag;
+use openqasm_parser::circuit::ir::{Circuit, Operation};
+use openqasm_parser::circuit::lower::Lowerer;
+use openqasm_parser::circuit::optimizer::Optimizer;
+use openqasm_parser::circuit::unitary;
+use openqasm_parser::parse::parser;
+use openqasm_parser::transform::inliner::Inliner;
+
+fn optimize(input: &str, level: u32) -> Circuit {
+    let mut program = parser::parse_str("<test>", input).ok().unwrap();
+    Inliner::new().inline(&mut program);
+    let mut dag = Dag::new(Lowerer::new().lower(&program).unwrap());
+    Optimizer::new(level).optimize(&mut dag);
+    dag.to_circuit()
+}
+
+fn print(circuit: &Circuit) -> String {
+    let output = PrettyPrinter::new().pretty_print(&circuit.to_program());
+    assert!(parser::parse_str("<output>", &output).is_ok());
+    output
+}
+
+const PROGRAM: &str = "OPENQASM 2.0;\n\
+                       gate h a { U(pi/2, 0, pi) a; }\n\
+                       qreg q[2];\n\
+                       h q[0];\n\
+                       h q[0];\n\
+                       CX q[0], q[1];\n\
+                       CX q[0], q[1];\n\
+                       U(0, 0, 0) q[1];\n";
+
+#[test]
+fn level_zero_keeps_everything() {
+    assert_eq!(optimize(PROGRAM, 0).ops.len(), 5);
+}
+
+#[test]
+fn level_one_drops_identities_and_cx_pairs() {
+    assert_eq!(
+        print(&optimize(PROGRAM, 1)),
+        "OPENQASM 2.0;\n\
+         qreg q[2];\n\
+         U ((pi / 2), 0, pi) q[0];\n\
+         U ((pi / 2), 0, pi) q[0];\n"
+    );
+}
+
+#[test]
+fn level_two_merges_u_gates() {
+    assert_eq!(print(&optimize(PROGRAM, 2)), "OPENQASM 2.0;\nqreg q[2];\n");
+
+    let circuit = optimize(
+        "OPENQASM 2.0;\n\
+         qreg q[1];\n\
+         U(0.1, 0.2, 0.3) q[0];\n\
+         U(1.2, -0.4, 2.5) q[0];\n\
+         U(pi, 0, pi) q[0];\n",
+        2,
+    );
+    let expected = unitary::u_matrix(std::f64::consts::PI, 0.0, std::f64::consts::PI)
+        .mul(&unitary::u_matrix(1.2, -0.4, 2.5))
+        .mul(&unitary::u_matrix(0.1, 0.2, 0.3));
+    match &circuit.ops[..] {
+        [Operation::Gate { params, .. }] => {
+            assert!(unitary::u_matrix(params[0], params[1], params[2]).equivalent(&expected))
+        }
+        _ => panic!("expected a single gate"),
+    }
+}
+
+#[test]
+fn barriers_and_measurements_block_rewrites() {
+    assert_eq!(
+        print(&optimize(
+            "OPENQASM 2.0;\n\
+             qreg q[2];\n\
+             creg c[1];\n\
+             CX q[0], q[1];\n\
+             barrier q;\n\
+             CX q[0], q[1];\n\
+             U(0.5, 0, 0) q[0];\n\
+             measure q[0] -> c[0];\n\
+             U(0.5, 0, 0) q[0];\n\
+             CX q[1], q[0];\n\
+             CX q[0], q[1];\n",
+            2,
+        )),
+        "OPENQASM 2.0;\n\
+         qreg q[2];\n\
+         creg c[1];\n\
+         CX q[0], q[1];\n\
+         barrier q[0], q[1];\n\
+         CX q[0], q[1];\n\
+         U (0.5, 0, 0) q[0];\n\
+         measure q[0] -> c[0];\n\
+         U (0.5, 0, 0) q[0];\n\
+         CX q[1], q[0];\n\
+         CX q[0], q[1];\n"
+    );
+}
+
+#[test]
+fn conditional_gates_are_left_alone() {
+    let circuit = optimize(
+        "OPENQASM 2.0;\n\
+         qreg q[1];\n\
+         creg c[1];\n\
+         if (c == 1) U(0, 0, 0) q[0];\n\
+         if (c == 1) U(0, 0, 0) q[0];\n",
+        2,
+    );
+    assert_eq!(circuit.ops.len(), 2);
+}
@@ -0,0 +1,53 @@
+use std::fs;
+use std::path::Path;
+
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::parse::lexer::Lexer;
+use openqasm_parser::parse::parser::Parser;
+use openqasm_parser::utils::error::Error;
+
+fn pretty_print(name: &str, input: &str) -> String {
+    let lexer = Lexer::new(String::from(name), input.as_bytes());
+    let mut parser = Parser::new(lexer);
+    match parser.parse_input() {
+        Some(program) => PrettyPrinter::new().pretty_print(&program),
+        None => panic!("{}: {}", name, parser.get_error().get_desc()),
+    }
+}
+
+#[test]
+fn pretty_print_reparses_to_the_same_program() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testsuite/files");
+    for category in ["good", "measure"] {
+        for entry in fs::read_dir(root.join(category)).unwrap() {
+            let path = entry.unwrap().path();
+            if path.extension().is_none_or(|ext| ext != "qasm") {
+                continue;
+            }
+            let name = path.to_string_lossy().into_owned();
+            let printed = pretty_print(&name, &fs::read_to_string(&path).unwrap());
+            assert_eq!(printed, pretty_print(&name, &printed), "{}", name);
+        }
+    }
+}
+
+#[test]
+fn includes_and_pragmas_keep_their_place_among_statements() {
+    let input = "OPENQASM 2.0;\n\
+                 qreg q[1];\n\
+                 pragma first\n\
+                 include \"qelib1.inc\";\n\
+                 U (0, 0, 0) q[0];\n\
+                 pragma last\n";
+    let printed = pretty_print("<interleaved>", input);
+    assert_eq!(
+        printed,
+        "OPENQASM 2.0;\n\
+         qreg q[1];\n\
+         pragma first\n\
+         include \"qelib1.inc\";\n\
+         U (0, 0, 0) q[0];\n\
+         pragma last\n"
+    );
+    assert_eq!(printed, pretty_print("<interleaved>", &printed));
+}
@@ -0,0 +1,228 @@
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::circuit::dag::Dag;
+use openqasm_parser::circuit::ir::{Circuit, Operation};
+use openqasm_parser::circuit::lower::Lowerer;
+use openqasm_parser::circuit::routing::{CouplingMap, Router, Routing};
+use openqasm_parser::circuit::unitary::{self, Complex, Matrix};
+use openqasm_parser::parse::parser;
+use openqasm_parser::utils::error::Error;
+
+fn lower(input: &str) -> Circuit {
+    let program = parser::parse_str("<test>", input).ok().unwrap();
+    Lowerer::new().lower(&program).unwrap()
+}
+
+fn route(input: &str, coupling: &str) -> Result<Routing, String> {
+    let mut router = Router::new(CouplingMap::parse(coupling).ok().unwrap());
+    router
+        .route(&Dag::new(lower(input)))
+        .ok_or_else(|| router.get_error().get_desc())
+}
+
+fn unitary_of(ops: &[Operation], size: usize, layout: &[usize]) -> Matrix {
+    let mut matrix = Matrix::identity(1 << size);
+    for op in ops {
+        let (gate, qubits) = match op {
+            Operation::Gate {
+                name,
+                params,
+                qubits,
+            } if name == "U" => (unitary::u_matrix(params[0], params[1], params[2]), qubits),
+            Operation::Gate { qubits, .. } => (unitary::cx_matrix(), qubits),
+            _ => panic!("unexpected operation"),
+        };
+        let qubits: Vec<usize> = qubits.iter().map(|qubit| layout[*qubit]).collect();
+        matrix = matrix.apply(&gate, &qubits);
+    }
+    matrix
+}
+
+fn permutation(size: usize, from: &[usize], to: &[usize]) -> Matrix {
+    let targets: Vec<usize> = (0..1 << size)
+        .map(|state| {
+            from.iter().zip(to.iter()).fold(0, |target, (at, next)| {
+                target | (((state >> at) & 1) << next)
+            })
+        })
+        .collect();
+    Matrix::from_rows(
+        (0..1 << size)
+            .map(|row| {
+                targets
+                    .iter()
+                    .map(|target| Complex::new(if *target == row { 1.0 } else { 0.0 }, 0.0))
+                    .collect()
+            })
+            .collect(),
+    )
+}
+
+fn assert_routed(input: &str, coupling: &str) -> Routing {
+    let routing = route(input, coupling).unwrap();
+    let map = CouplingMap::parse(coupling).ok().unwrap();
+    for op in routing.circuit.ops.iter() {
+        if let [a, b] = op.get_qubits()[..] {
+            assert!(map.is_connected(a, b), "CX q[{}], q[{}]", a, b);
+        }
+    }
+
+    let circuit = lower(input);
+    let size = map.get_num_qubits();
+    let identity: Vec<usize> = (0..size).collect();
+    let mut initial = routing.initial_layout.clone();
+    let mut last = routing.final_layout.clone();
+    for qubit in 0..size {
+        if !initial.contains(&qubit) {
+            initial.push(qubit);
+        }
+        if !last.contains(&qubit) {
+            last.push(qubit);
+        }
+    }
+    let expected =
+        permutation(size, &initial, &last).mul(&unitary_of(&circuit.ops, size, &initial));
+    let actual = unitary_of(&routing.circuit.ops, size, &identity);
+    assert!(actual.equivalent(&expected));
+    routing
+}
+
+#[test]
+fn coupling_maps_are_read_from_json_or_text() {
+    let json = CouplingMap::parse("[[0, 1], [1, 2]]").ok().unwrap();
+    let text = CouplingMap::parse("# line\n0 1\n1-2\n").ok().unwrap();
+    for map in [json, text] {
+        assert_eq!(map.get_num_qubits(), 3);
+        assert!(map.is_connected(2, 1));
+        assert!(!map.is_connected(0, 2));
+        assert_eq!(map.get_distance(0, 2), Some(2));
+    }
+    assert!(CouplingMap::parse("[[0, 1], [1]]").is_err());
+    for map in ["0 1\n2 3\n", "[[0, 1], [2, 3], [0, 1]]", "0 200000\n"] {
+        assert_eq!(
+            CouplingMap::parse(map).err().unwrap().get_desc(),
+            "the coupling map is not connected"
+        );
+    }
+    let line = (0..5000)
+        .map(|qubit| format!("{} {}\n", qubit, qubit + 1))
+        .collect::<String>();
+    assert_eq!(
+        CouplingMap::parse(&line).err().unwrap().get_desc(),
+        "invalid coupling map: more than 4096 qubits"
+    );
+    assert_eq!(
+        CouplingMap::parse("0 1\n1 2 3\n").err().unwrap().get_desc(),
+        "invalid coupling map: bad edge on line 2"
+    );
+}
+
+#[test]
+fn swaps_are_inserted_on_a_line() {
+    let routing = assert_routed(
+        "OPENQASM 2.0;\n\
+         qreg q[4];\n\
+         CX q[0], q[1];\n\
+         CX q[0], q[2];\n\
+         CX q[0], q[3];\n\
+         U(0.3, 0.2, 0.1) q[0];\n\
+         CX q[1], q[3];\n\
+         CX q[2], q[0];\n\
+         CX q[3], q[2];\n",
+        "[[0, 1], [1, 2], [2, 3]]",
+    );
+    assert!(routing.circuit.ops.len() > 7);
+}
+
+#[test]
+fn connected_programs_need_no_swaps() {
+    let routing = assert_routed(
+        "OPENQASM 2.0;\n\
+         qreg a[2];\n\
+         qreg b[1];\n\
+         CX a[0], b[0];\n\
+         CX b[0], a[1];\n",
+        "0 1\n1 2\n",
+    );
+    assert_eq!(routing.circuit.ops.len(), 2);
+    assert_eq!(routing.initial_layout, routing.final_layout);
+}
+
+#[test]
+fn routed_circuits_print_as_qasm() {
+    let routing = route(
+        "OPENQASM 2.0;\n\
+         qreg a[2];\n\
+         creg q[2];\n\
+         CX a[0], a[1];\n\
+         measure a -> q;\n",
+        "0 1\n1 2\n",
+    )
+    .unwrap();
+    let output = PrettyPrinter::new().pretty_print(&routing.circuit.to_program());
+    assert!(output.starts_with("OPENQASM 2.0;\nqreg q_[3];\ncreg q[2];\n"));
+    assert!(parser::parse_str("<output>", &output).is_ok());
+}
+
+#[test]
+fn unroutable_circuits_are_reported() {
+    let program = "OPENQASM 2.0;\nqreg q[3];\nCX q[0], q[2];\n";
+    assert_eq!(
+        route(program, "0 1\n").err().unwrap(),
+        "the circuit needs 3 qubits but the coupling map has 2"
+    );
+    assert_eq!(
+        route(
+            "OPENQASM 2.0;\nopaque ccx a, b, c;\nqreg q[3];\nccx q[0], q[1], q[2];\n",
+            "0 1\n1 2\n"
+        )
+        .err()
+        .unwrap(),
+        "cannot route an operation on 3 qubits"
+    );
+}
+
+#[test]
+fn operations_on_a_repeated_qubit_are_reported() {
+    assert_eq!(
+        route(
+            "OPENQASM 2.0;\nqreg q[3];\nCX q[0], q[2];\nCX q[1], q[1];\n",
+            "0 1\n1 2\n"
+        )
+        .err()
+        .unwrap(),
+        "cannot route an operation using qubit 1 twice"
+    );
+}
+
+#[test]
+fn large_devices_are_routed() {
+    let size = 100;
+    let mut edges = String::new();
+    for qubit in 0..size {
+        if qubit % 10 != 9 {
+            edges += &format!("{} {}\n", qubit, qubit + 1);
+        }
+        if qubit + 10 < size {
+            edges += &format!("{} {}\n", qubit, qubit + 10);
+        }
+    }
+    let mut input = format!("OPENQASM 2.0;\nqreg q[{}];\n", size);
+    let mut seed: u64 = 7;
+    for _ in 0..300 {
+        seed = seed
+            .wrapping_mul(6364136223846793005)
+            .wrapping_add(1442695040888963407);
+        let a = (seed >> 33) as usize % size;
+        let b = (a + 1 + (seed >> 13) as usize % (size - 1)) % size;
+        input += &format!("CX q[{}], q[{}];\n", a, b);
+    }
+    let routing = route(&input, &edges).unwrap();
+    let map = CouplingMap::parse(&edges).ok().unwrap();
+    let mut gates = 0;
+    for op in routing.circuit.ops.iter() {
+        let qubits = op.get_qubits();
+        assert!(map.is_connected(qubits[0], qubits[1]));
+        gates += 1;
+    }
+    assert_eq!((gates - 300) % 3, 0);
+}
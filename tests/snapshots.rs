@@ -0,0 +1,139 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::bind::binder::Binder;
+use openqasm_parser::eval::evaluator::Evaluator;
+use openqasm_parser::parse::lexer::Lexer;
+use openqasm_parser::parse::parser::Parser;
+use openqasm_parser::typing::type_checker::TypeChecker;
+use openqasm_parser::utils::error::{CompoundError, Error};
+
+use regex::{Captures, Regex};
+
+// Run with `BLESS=1 cargo test --test snapshots` to update the `.expected` files.
+
+fn files_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testsuite/files")
+}
+
+fn collect(dir: &Path, programs: &mut Vec<PathBuf>) {
+    let mut entries = fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .collect::<Vec<PathBuf>>();
+    entries.sort();
+    for path in entries {
+        if path.is_dir() {
+            collect(&path, programs);
+        } else if path.extension().is_some_and(|ext| ext == "qasm") {
+            programs.push(path);
+        }
+    }
+}
+
+fn dump_tokens(name: &str, input: &[u8]) -> String {
+    let mut output = String::new();
+    for token in Lexer::new(String::from(name), input) {
+        output += &token.to_string();
+        output += "\n";
+    }
+    output
+}
+
+/// Replaces the declaration addresses printed for bound names by their order
+/// of first appearance, so that snapshots do not depend on the allocator.
+fn number_pointers(text: &str) -> String {
+    let pointer = Regex::new(r"0x[0-9a-f]+").unwrap();
+    let mut seen: HashMap<String, usize> = HashMap::new();
+    pointer
+        .replace_all(text, |captures: &Captures| {
+            let next = seen.len();
+            let id = *seen.entry(String::from(&captures[0])).or_insert(next);
+            format!("#{}", id)
+        })
+        .into_owned()
+}
+
+fn run_phases(name: &str, input: &[u8]) -> (String, String) {
+    let lexer = Lexer::new(String::from(name), input);
+    let mut parser = Parser::new(lexer);
+    let mut binder = Binder::new();
+    let mut type_checker = TypeChecker::new();
+    let mut evaluator = Evaluator::new();
+
+    // Same phases and order as `main`, which prints the bound program.
+    let mut pretty = String::new();
+    if let Some(program) = &mut parser.parse_input() {
+        binder.bind(program);
+        type_checker.check_types(program);
+        evaluator.evaluate(program);
+        pretty = number_pointers(&PrettyPrinter::new().pretty_print(program));
+    }
+
+    let mut error = CompoundError::new();
+    error.consume(parser.get_error_mut());
+    error.consume(binder.get_error_mut());
+    error.consume(type_checker.get_error_mut());
+    error.consume(evaluator.get_error_mut());
+
+    let mut diagnostics = String::new();
+    if !error.empty() {
+        diagnostics += &error.get_desc();
+        diagnostics += "\n";
+    }
+    diagnostics += &format!("exit code {}\n", error.get_exit_code());
+
+    (pretty, diagnostics)
+}
+
+fn snapshot(name: &str, input: &[u8]) -> String {
+    let tokens = dump_tokens(name, input);
+    let (pretty, diagnostics) = run_phases(name, input);
+    format!(
+        "==> tokens\n{}==> pretty\n{}==> diagnostics\n{}",
+        tokens, pretty, diagnostics
+    )
+}
+
+#[test]
+fn snapshots() {
+    let bless = std::env::var_os("BLESS").is_some();
+    let root = files_dir();
+    let mut programs = Vec::new();
+    collect(&root, &mut programs);
+    assert!(!programs.is_empty());
+
+    let mut failures = Vec::new();
+    for program in programs {
+        let name = program
+            .strip_prefix(&root)
+            .unwrap()
+            .to_string_lossy()
+            .into_owned();
+        let input = fs::read(&program).unwrap();
+        let actual = snapshot(&name, &input);
+        let expected_path = program.with_extension("expected");
+
+        if bless {
+            fs::write(&expected_path, &actual).unwrap();
+            continue;
+        }
+
+        match fs::read_to_string(&expected_path) {
+            Ok(expected) if expected == actual => {}
+            Ok(expected) => failures.push(format!(
+                "{}: snapshot mismatch\n--- expected\n{}--- actual\n{}",
+                name, expected, actual
+            )),
+            Err(_) => failures.push(format!("{}: missing {}", name, expected_path.display())),
+        }
+    }
+
+    assert!(
+        failures.is_empty(),
+        "{}\n\nrun with BLESS=1 to update the snapshots",
+        failures.join("\n")
+    );
+}
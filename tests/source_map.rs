@@ -0,0 +1,67 @@
+use std::path::Path;
+
+use openqasm_parser::parse::lexer::Lexer;
+use openqasm_parser::parse::parser;
+use openqasm_parser::utils::error::Error;
+use openqasm_parser::utils::source_map::SourceMap;
+
+#[test]
+fn locations_refer_to_the_loaded_text() {
+    let input = "OPENQASM 2.0;\nqreg q[1];\n";
+    let program = parser::parse_str("<source>", input).ok().unwrap();
+    let loc = program.stmts[0].get_loc();
+    let file = loc.get_file().unwrap();
+    let (start, end) = loc.get_span().unwrap();
+    assert_eq!(file.get_name(), "<source>");
+    assert_eq!(file.get_text(), input);
+    assert_eq!(file.get_slice(start, end).unwrap(), b"qreg q[1];");
+    assert_eq!(loc.to_string(), "<source>:1:0-10");
+}
+
+#[test]
+fn each_lexer_gets_its_own_file() {
+    let first = Lexer::from_str(String::from("<first>"), "OPENQASM 2.0;");
+    let second = Lexer::from_str(String::from("<second>"), "OPENQASM 2.0;");
+    assert!(first.get_file() != second.get_file());
+}
+
+#[test]
+fn files_outlive_their_lexer_while_referenced() {
+    let program = parser::parse_str("<kept>", "OPENQASM 2.0;\nqreg q[1];\n")
+        .ok()
+        .unwrap();
+    let file = program.stmts[0].get_loc().get_file().unwrap().clone();
+    drop(program);
+    assert_eq!(file.get_name(), "<kept>");
+    assert_eq!(file.get_text(), "OPENQASM 2.0;\nqreg q[1];\n");
+}
+
+#[test]
+fn source_maps_own_the_files_added_to_them() {
+    let mut map = SourceMap::new();
+    let lexer = Lexer::from_str(String::from("<owned>"), "OPENQASM 2.0;");
+    map.add(lexer.get_file().clone());
+    map.add(lexer.get_file().clone());
+    drop(lexer);
+    assert_eq!(map.get_files().len(), 1);
+
+    let file = map.find("<owned>").unwrap().clone();
+    map.remove(&file);
+    assert!(map.find("<owned>").is_none());
+    assert_eq!(file.to_string(), "<owned>");
+}
+
+#[test]
+fn parse_file_reads_from_disk() {
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/testsuite/files");
+    let program = parser::parse_file(&root.join("good/bell_state.qasm"))
+        .ok()
+        .unwrap();
+    assert_eq!(program.stmts.len(), 3);
+
+    let error = parser::parse_file(&root.join("good/missing.qasm"))
+        .err()
+        .unwrap();
+    assert_eq!(error.get_exit_code(), 1);
+    assert!(error.get_desc().starts_with("cannot open"));
+}
@@ -0,0 +1,92 @@
+use openqasm_parser::ast::node;
+use openqasm_parser::ast::pretty_printer::PrettyPrinter;
+use openqasm_parser::bind::binder::Binder;
+use openqasm_parser::parse::parser;
+use openqasm_parser::utils::error::Error;
+
+fn bind_errors(input: &str) -> String {
+    let mut program = parser::parse_str("<test>", input).ok().unwrap();
+    let mut binder = Binder::new();
+    binder.bind(&mut program);
+    binder.get_error().get_desc()
+}
+
+#[test]
+fn barriers_and_conditions_parse() {
+    let program = parser::parse_str(
+        "<test>",
+        "OPENQASM 2.0;\n\
+         gate sync a, b { barrier a, b; }\n\
+         barrier q, r[1];\n\
+         if (c == 3) reset q;\n",
+    )
+    .ok()
+    .unwrap();
+    match &program.stmts[1] {
+        node::Stmt::BarrierStmt { loc, args } => {
+            assert_eq!(args.len(), 2);
+            assert_eq!(loc.to_string(), "<test>:2:0-16");
+        }
+        _ => panic!("expected a barrier"),
+    }
+    match &program.stmts[2] {
+        node::Stmt::IfStmt {
+            loc, value, stmt, ..
+        } => {
+            assert_eq!(*value, 3);
+            assert!(matches!(**stmt, node::Stmt::ResetStmt { .. }));
+            assert_eq!(loc.to_string(), "<test>:3:0-20");
+        }
+        _ => panic!("expected a condition"),
+    }
+    assert_eq!(
+        PrettyPrinter::new().pretty_print(&program),
+        "OPENQASM 2.0;\n\
+         gate sync a, b {\n  barrier a, b;\n}\n\
+         barrier q, r[1];\n\
+         if (c == 3) reset q;\n"
+    );
+}
+
+#[test]
+fn conditions_only_guard_quantum_operations() {
+    for input in [
+        "OPENQASM 2.0;\nif (c == 1) qreg q[1];\n",
+        "OPENQASM 2.0;\nif (c == 1) barrier q;\n",
+        "OPENQASM 2.0;\nif (c[0] == 1) reset q;\n",
+        "OPENQASM 2.0;\ngate g a { reset a; }\n",
+    ] {
+        assert_eq!(
+            parser::parse_str("<test>", input)
+                .err()
+                .unwrap()
+                .get_exit_code(),
+            3,
+            "{}",
+            input
+        );
+    }
+}
+
+#[test]
+fn barriers_and_conditions_bind_their_registers() {
+    assert_eq!(
+        bind_errors(
+            "OPENQASM 2.0;\n\
+             gate sync a, b { barrier a, b; }\n\
+             qreg q[2];\n\
+             creg c[2];\n\
+             barrier q;\n\
+             if (c == 1) sync q[0], q[1];\n"
+        ),
+        ""
+    );
+    assert_eq!(
+        bind_errors("OPENQASM 2.0;\nqreg q[1];\nif (d == 1) reset q;\n"),
+        "undeclared register 'd' at <test>:2:4-5"
+    );
+    assert_eq!(
+        bind_errors("OPENQASM 2.0;\ngate g a { barrier b; }\n"),
+        "undeclared register 'b' at <test>:1:19-20"
+    );
+}
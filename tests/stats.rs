@@ -0,0 +1,145 @@
+use openqasm_parser::analysis::stats::{Stats, StatsCollector};
+use openqasm_parser::bind::binder::Binder;
+use openqasm_parser::circuit::lower::Lowerer;
+use openqasm_parser::parse::parser;
+use openqasm_parser::transform::inliner::Inliner;
+use openqasm_parser::utils::error::Error;
+
+fn stats(input: &str) -> Stats {
+    let mut program = parser::parse_str("<test>", input).ok().unwrap();
+    let mut binder = Binder::new();
+    binder.bind(&mut program);
+    assert!(
+        binder.get_error().empty(),
+        "{}",
+        binder.get_error().get_desc()
+    );
+    StatsCollector::new().collect(&program)
+}
+
+const PROGRAM: &str = "OPENQASM 2.0;\n\
+                       gate h a { U(pi/2, 0, pi) a; }\n\
+                       qreg q[3];\n\
+                       creg c[3];\n\
+                       h q;\n\
+                       CX q[0], q[1];\n\
+                       barrier q;\n\
+                       CX q[1], q[2];\n\
+                       measure q -> c;\n\
+                       if (c == 1) reset q[0];\n";
+
+#[test]
+fn counts_are_broadcast_over_registers() {
+    let stats = stats(PROGRAM);
+    assert_eq!(stats.qubits, 3);
+    assert_eq!(stats.clbits, 3);
+    assert_eq!(stats.get_gate_count(), 5);
+    assert_eq!(stats.gates["h"], 3);
+    assert_eq!(stats.gates["CX"], 2);
+    assert_eq!(stats.measurements, 3);
+    assert_eq!(stats.resets, 1);
+}
+
+#[test]
+fn barriers_and_conditions_extend_the_depth() {
+    let stats = stats(PROGRAM);
+    assert_eq!(stats.depth, 5);
+    assert_eq!(stats.two_qubit_depth, 2);
+    assert_eq!(
+        stats
+            .critical_path
+            .iter()
+            .map(|loc| loc.to_string())
+            .collect::<Vec<String>>(),
+        vec![
+            "<test>:4:0-4",
+            "<test>:5:0-14",
+            "<test>:7:0-14",
+            "<test>:8:0-15",
+            "<test>:9:12-23"
+        ]
+    );
+}
+
+#[test]
+fn independent_wires_do_not_add_up() {
+    let stats = stats(
+        "OPENQASM 2.0;\n\
+         qreg q[4];\n\
+         CX q[0], q[1];\n\
+         CX q[2], q[3];\n\
+         U(0, 0, 0) q[0];\n",
+    );
+    assert_eq!(stats.depth, 2);
+    assert_eq!(stats.two_qubit_depth, 1);
+    assert_eq!(stats.critical_path.len(), 2);
+}
+
+#[test]
+fn output_formats() {
+    let stats = stats(
+        "OPENQASM 2.0;\n\
+         qreg q[2];\n\
+         creg c[1];\n\
+         CX q[0], q[1];\n\
+         measure q[1] -> c[0];\n",
+    );
+    assert_eq!(
+        stats.to_text(),
+        "qubits: 2\n\
+         clbits: 1\n\
+         gates: 1\n\
+         \x20 CX: 1\n\
+         depth: 2\n\
+         two-qubit depth: 1\n\
+         measurements: 1\n\
+         resets: 0\n\
+         critical path:\n\
+         \x20 <test>:3:0-14\n\
+         \x20 <test>:4:0-21\n"
+    );
+    assert_eq!(
+        stats.to_json(),
+        "{\"qubits\": 2, \"clbits\": 1, \"gates\": 1, \"gate_counts\": {\"CX\": 1}, \
+         \"depth\": 2, \"two_qubit_depth\": 1, \"measurements\": 1, \"resets\": 0, \
+         \"critical_path\": [\"<test>:3:0-14\", \"<test>:4:0-21\"]}\n"
+    );
+}
+
+fn critical_path(stats: &Stats) -> Vec<String> {
+    stats
+        .critical_path
+        .iter()
+        .map(|loc| loc.to_string())
+        .collect()
+}
+
+const CALLS: &str = "OPENQASM 2.0;\n\
+                     gate bell a, b { U(pi/2, 0, pi) a; CX a, b; }\n\
+                     qreg q[2];\n\
+                     bell q[0], q[1];\n";
+
+#[test]
+fn inlined_statements_are_reported_at_their_call() {
+    let mut program = parser::parse_str("<test>", CALLS).ok().unwrap();
+    Inliner::new().inline(&mut program);
+    Binder::new().bind(&mut program);
+    let stats = StatsCollector::new().collect(&program);
+    assert_eq!(stats.depth, 2);
+    assert_eq!(
+        critical_path(&stats),
+        vec!["<test>:3:0-16", "<test>:3:0-16"]
+    );
+}
+
+#[test]
+fn compiled_programs_have_no_source_locations() {
+    let mut program = parser::parse_str("<test>", CALLS).ok().unwrap();
+    Binder::new().bind(&mut program);
+    Inliner::new().inline(&mut program);
+    let mut program = Lowerer::new().lower(&program).unwrap().to_program();
+    Binder::new().bind(&mut program);
+    let stats = StatsCollector::new().collect(&program);
+    assert_eq!(stats.depth, 2);
+    assert_eq!(critical_path(&stats), vec!["<synthetic>", "<synthetic>"]);
+}
@@ -0,0 +1,5 @@
+OPENQASM 3.0;
+
+qreg q[2];
+
+CX q[0], q[1];
@@ -0,0 +1,6 @@
+OPENQASM 2.0;
+gate loop a {
+  loop a;
+}
+qreg q[1];
+loop q[0];
@@ -0,0 +1,3 @@
+OPENQASM 2.0;
+gate h a { U(pi/2, 0, pi) a; }
+gate h b { U(pi/2, 0, pi) b; }
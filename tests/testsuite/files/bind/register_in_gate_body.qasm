@@ -0,0 +1,3 @@
+OPENQASM 2.0;
+qreg q[1];
+gate h a { U(pi/2, 0, pi) q[0]; }
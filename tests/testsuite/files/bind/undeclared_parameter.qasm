@@ -0,0 +1,4 @@
+OPENQASM 2.0;
+gate rz(theta) a {
+  U(0, 0, phi) a;
+}
@@ -0,0 +1,3 @@
+OPENQASM 2.0;
+qreg q[1];
+U(pi/(2-2), 0, 0) q[0];
@@ -0,0 +1,3 @@
+OPENQASM 2.0;
+qreg q[1];
+U(ln(1-pi), 0, 0) q[0];
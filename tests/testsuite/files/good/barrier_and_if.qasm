@@ -0,0 +1,12 @@
+OPENQASM 2.0;
+gate h a { U(pi/2, 0, pi) a; }
+gate sync a, b { barrier a, b; }
+qreg q[2];
+creg c[2];
+h q[0];
+barrier q;
+sync q[0], q[1];
+measure q[0] -> c[0];
+if (c == 1) U(0, 0, pi) q[1];
+if (c == 3) reset q;
+measure q -> c;
@@ -0,0 +1,13 @@
+OPENQASM 2.0;
+gate h a { U(pi/2, 0, pi) a; }
+gate rz(theta) a { U(0, 0, theta) a; }
+gate cz a, b { h b; CX a, b; h b; }
+gate crz(lambda) a, b { rz(lambda/2) b; CX a, b; rz(-lambda/2) b; CX a, b; }
+opaque magic(x) a;
+qreg q[2];
+qreg r[2];
+h q[0];
+cz q[0], q[1];
+crz(pi/4) q[0], q[1];
+cz q, r;
+magic(0.5) q[1];
@@ -0,0 +1,3 @@
+OPENQASM 2.0;
+qreg q[1];
+U(2^(-4294967296*2147483648), 0, 0) q[0];
@@ -0,0 +1,9 @@
+OPENQASM 2.0;
+include "qelib1.inc";
+#pragma optimize off
+
+qreg q[1];
+creg c[1];
+
+U (pi/2, 0, pi) q[0];
+measure q[0] -> c[0];
@@ -0,0 +1,8 @@
+OPENQASM 2.0;
+
+qreg q[0x2];
+creg c[0b10];
+
+U (1_000.5, 0o7, 1.5e-3) q[0];
+CX q[0], q[1];
+measure q[0] -> c[0];
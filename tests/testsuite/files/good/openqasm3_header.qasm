@@ -0,0 +1,5 @@
+OPENQASM 3.0;
+
+qreg q[1];
+
+U (0, 0, 0) q[0];
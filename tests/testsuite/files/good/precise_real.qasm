@@ -0,0 +1,5 @@
+OPENQASM 2.0;
+
+qreg q[1];
+
+U (3.14159265358979, 0.000000000001, 1.10) q[0];
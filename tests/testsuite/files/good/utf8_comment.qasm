@@ -0,0 +1,7 @@
+OPENQASM 2.0;
+
+// Prépare l’état |ψ⟩
+qreg q[1]; /* ψ → φ */ creg c[1];
+
+U (pi/2, 0, pi) q[0];
+measure q[0] -> c[0];
@@ -0,0 +1,3 @@
+OPENQASM 2.0;
+
+qreg q[18446744073709551616];
@@ -0,0 +1,5 @@
+OPENQASM 2.0;
+
+qreg q[1];
+
+U (1.5xy, 0, pi) q[0];
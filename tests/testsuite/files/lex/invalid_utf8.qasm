@@ -0,0 +1,5 @@
+OPENQASM 2.0;
+
+qreg q[1];
+// 
+reset q;
@@ -0,0 +1,10 @@
+OPENQASM 3;
+
+qubit[2] q;
+bit[2] c = "01";
+int x = 0x1f % 3 ** 2;
+if (c == "10" && x >= 2 || !(x != 1)) {
+    x <<= 1; x >>= 1; x += 1; x -= 1; x *= 2; x /= 2; x **= 2; x %= 3;
+    x &= 1; x |= 2; x ^= 3; x ~= 4; x = ~x & x | x << 1 >> 1;
+    ctrl @ inv @ pow(2) @ U(pi, tau, euler) q[0:1];
+}
@@ -0,0 +1,5 @@
+OPENQASM 2.0;
+
+qreg q[1];
+
+U (100ns, 0, 1.5im) q[0];
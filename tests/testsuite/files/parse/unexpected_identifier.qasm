@@ -1,5 +1,5 @@
 OPENQASM 2.0;
 
-reg q[0];
+qreg q[2] q;
 
 U (0, 0, 0) q;
@@ -0,0 +1,3 @@
+OPENQASM 2.1;
+
+qreg q[1];
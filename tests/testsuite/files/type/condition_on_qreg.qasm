@@ -0,0 +1,3 @@
+OPENQASM 2.0;
+qreg q[1];
+if (q == 1) U(0, 0, pi) q[0];
@@ -0,0 +1,4 @@
+OPENQASM 2.0;
+gate cz a, b { CX a, b; }
+qreg q[2];
+cz q[0];
@@ -0,0 +1,6 @@
+OPENQASM 2.0;
+gate g a, b {
+  CX a, a;
+}
+qreg q[2];
+g q[0], q[1];
@@ -0,0 +1,3 @@
+OPENQASM 2.0;
+qreg q[2];
+CX q[0], q[0];
@@ -0,0 +1,5 @@
+OPENQASM 2.0;
+qreg q[2];
+qreg r[2];
+CX q, r;
+CX q, q;
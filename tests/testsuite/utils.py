@@ -9,5 +9,5 @@ FILES_PATH = Path(os.path.dirname(__file__), "files")
 def load(categories: list[str]) -> list[Path]:
     tests = []
     for category in categories:
-        tests.extend((FILES_PATH / category).iterdir())
+        tests.extend((FILES_PATH / category).glob("*.qasm"))
     return tests